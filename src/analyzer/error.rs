@@ -0,0 +1,65 @@
+use std::fmt;
+
+use crate::parser::Span;
+
+/// Why a statement cannot be planned, and where in its SQL.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AnalyzeError {
+    pub kind: AnalyzeErrorKind,
+    /// The innermost expression, name or statement the error was found in.
+    /// `None` when the statement was analyzed without spans.
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum AnalyzeErrorKind {
+    UnknownTable(String),
+    UnknownColumn(String),
+    /// A column name that more than one relation in scope has.
+    AmbiguousColumn(String),
+    UnknownFunction(String),
+    /// What did not fit, such as `Int Plus Bool`.
+    TypeMismatch(String),
+    /// A list of values, columns or arguments of the wrong length.
+    WrongArity(String),
+    /// A window function where it is not allowed, or without OVER.
+    AggregateMisuse(String),
+    /// A statement or clause the analyzer cannot plan.
+    Unsupported(String),
+    /// A statement at odds with the catalog or with itself, such as one
+    /// creating a table that exists.
+    Invalid(String),
+}
+
+impl AnalyzeError {
+    pub fn new(kind: AnalyzeErrorKind) -> Self {
+        AnalyzeError { kind, span: None }
+    }
+}
+
+impl From<AnalyzeErrorKind> for AnalyzeError {
+    fn from(kind: AnalyzeErrorKind) -> Self {
+        AnalyzeError::new(kind)
+    }
+}
+
+impl fmt::Display for AnalyzeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            AnalyzeErrorKind::UnknownTable(name) => write!(f, "Unknown table: {}", name),
+            AnalyzeErrorKind::UnknownColumn(name) => write!(f, "Unknown column: {}", name),
+            AnalyzeErrorKind::AmbiguousColumn(name) => write!(f, "Ambiguous column: {}", name),
+            AnalyzeErrorKind::UnknownFunction(name) => write!(f, "Unknown function: {}", name),
+            AnalyzeErrorKind::TypeMismatch(message) => write!(f, "Type mismatch: {}", message),
+            AnalyzeErrorKind::WrongArity(message)
+            | AnalyzeErrorKind::AggregateMisuse(message)
+            | AnalyzeErrorKind::Unsupported(message)
+            | AnalyzeErrorKind::Invalid(message) => write!(f, "{}", message),
+        }?;
+
+        match self.span {
+            Some(span) => write!(f, " at {}..{}", span.start, span.end),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::fmt;
+
+use crate::analyzer::{ConflictAction, Expression, LogicalNode, LogicalPlan, Operator};
+use crate::parser::{Literal, Op, Parameter};
+
+impl LogicalNode {
+    /// One line per operator, children indented under their parent. The
+    /// plan is executed as it is, so this is also the physical plan.
+    /// `annotate` may add a suffix to the line of each operator.
+    pub fn explain(&self, annotate: &dyn Fn(&LogicalNode) -> Option<String>) -> Vec<String> {
+        let mut lines = vec![];
+        self.explain_into(0, annotate, &mut lines);
+
+        lines
+    }
+
+    fn explain_into(
+        &self,
+        depth: usize,
+        annotate: &dyn Fn(&LogicalNode) -> Option<String>,
+        lines: &mut Vec<String>,
+    ) {
+        let mut line = format!("{}{}", "  ".repeat(depth), self.operator.describe());
+
+        if let Some(annotation) = annotate(self) {
+            line.push_str("  ");
+            line.push_str(&annotation);
+        }
+
+        lines.push(line);
+
+        for child in &self.children {
+            child.explain_into(depth + 1, annotate, lines);
+        }
+    }
+}
+
+impl fmt::Display for LogicalPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.root.explain(&|_| None) {
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Operator {
+    /// Name of the operator with its arguments, on one line.
+    pub fn describe(&self) -> String {
+        match self {
+            Operator::Projection(info) => format!("Projection: {}", join(&info.expressions)),
+            Operator::Filter(info) => format!("Filter: {}", info.predicate),
+            Operator::Read(info) => format!("Read: {}", info.table.name),
+            Operator::Join(_) => "Join".to_string(),
+            Operator::Group(_) => "Group".to_string(),
+            Operator::Sort(_) => "Sort".to_string(),
+            Operator::Limit(_) => "Limit".to_string(),
+            Operator::Distinct(_) => "Distinct".to_string(),
+            Operator::Insert(info) => {
+                let mut description =
+                    format!("Insert: {} ({})", info.table.name, join(&info.columns));
+
+                match info
+                    .on_conflict
+                    .as_ref()
+                    .map(|on_conflict| &on_conflict.action)
+                {
+                    Some(ConflictAction::Nothing) => {
+                        description.push_str(" ON CONFLICT DO NOTHING")
+                    }
+                    Some(ConflictAction::Update { .. }) => {
+                        description.push_str(" ON CONFLICT DO UPDATE")
+                    }
+                    None => {}
+                }
+
+                description
+            }
+            Operator::Values(info) => format!("Values: {} rows", info.rows.len()),
+            Operator::Update(info) => {
+                let assignments: Vec<String> = info
+                    .assignments
+                    .iter()
+                    .map(|assignment| format!("{} = {}", assignment.column, assignment.expression))
+                    .collect();
+
+                with_predicate(
+                    format!("Update: {} SET {}", info.table.name, assignments.join(", ")),
+                    &info.predicate,
+                )
+            }
+            Operator::Delete(info) => {
+                with_predicate(format!("Delete: {}", info.table.name), &info.predicate)
+            }
+            Operator::Merge(info) => format!("Merge: {} ON {}", info.table.name, info.condition),
+            Operator::Union(info) if info.all => "Union All".to_string(),
+            Operator::Union(_) => "Union".to_string(),
+            Operator::With(info) => {
+                let names: Vec<&str> = info.ctes.iter().map(|cte| cte.name.as_str()).collect();
+
+                match info.ctes.iter().any(|cte| cte.recursive) {
+                    true => format!("With Recursive: {}", names.join(", ")),
+                    false => format!("With: {}", names.join(", ")),
+                }
+            }
+            Operator::Window(info) => {
+                let names: Vec<&str> = info
+                    .functions
+                    .iter()
+                    .map(|function| function.function.name())
+                    .collect();
+
+                format!("Window: {}", names.join(", "))
+            }
+            Operator::Subquery(info) => {
+                format!("Subquery: {} ({})", info.name, info.columns.join(", "))
+            }
+            Operator::CreateTable(info) => format!("CreateTable: {}", info.table.name),
+            Operator::DropTable(info) => {
+                let names: Vec<&str> = info
+                    .tables
+                    .iter()
+                    .map(|table| table.name.as_str())
+                    .collect();
+
+                format!("DropTable: {}", names.join(", "))
+            }
+            Operator::CreateView(info) => format!("CreateView: {}", info.view.name),
+            Operator::DropView(info) => format!("DropView: {}", info.name),
+            Operator::CreateIndex(info) => format!(
+                "CreateIndex: {} ON {} ({})",
+                info.index.name,
+                info.table.name,
+                info.index.columns.join(", ")
+            ),
+            Operator::DropIndex(info) => format!("DropIndex: {}", info.name),
+            Operator::Transaction(statement) => format!("Transaction: {:?}", statement),
+            Operator::Explain(info) if info.analyze => "Explain Analyze".to_string(),
+            Operator::Explain(_) => "Explain".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Constant(literal) => write_literal(f, literal),
+            Expression::Column(column) => write!(f, "{}", column),
+            Expression::Unary(Op::Not, operand) => write!(f, "NOT {}", Operand(operand)),
+            Expression::Unary(op, operand) => write!(f, "{}{}", op_symbol(*op), Operand(operand)),
+            Expression::Binary(op, lhs, rhs) => {
+                write!(f, "{} {} {}", Operand(lhs), op_symbol(*op), Operand(rhs))
+            }
+            Expression::Default => write!(f, "DEFAULT"),
+            Expression::Parameter(index) => write!(f, "${}", index + 1),
+        }
+    }
+}
+
+/// An expression inside another one, parenthesized unless it is a single
+/// value.
+struct Operand<'a>(&'a Expression);
+
+impl fmt::Display for Operand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Expression::Unary(..) | Expression::Binary(..) => write!(f, "({})", self.0),
+            expression => write!(f, "{}", expression),
+        }
+    }
+}
+
+fn write_literal(f: &mut fmt::Formatter<'_>, literal: &Literal) -> fmt::Result {
+    match literal {
+        Literal::Numeric(i) => write!(f, "{}", i),
+        Literal::Float(x) => write!(f, "{:?}", x),
+        Literal::Decimal(d) => write!(f, "{}", d),
+        Literal::String(s) => write!(f, "'{}'", s.replace('\'', "''")),
+        Literal::Date(date) => write!(f, "DATE '{}'", date),
+        Literal::Time(time) => write!(f, "TIME '{}'", time),
+        Literal::Timestamp(timestamp) => write!(f, "TIMESTAMP '{}'", timestamp),
+        Literal::Interval(interval) => write!(f, "INTERVAL '{}'", interval),
+        Literal::Bool(true) => write!(f, "TRUE"),
+        Literal::Bool(false) => write!(f, "FALSE"),
+        Literal::Null => write!(f, "NULL"),
+        Literal::Default => write!(f, "DEFAULT"),
+        Literal::Wildcard => write!(f, "*"),
+        Literal::Identifier {
+            first_name,
+            second_name,
+            third_name,
+        } => {
+            write!(f, "{}", first_name)?;
+
+            for part in second_name.iter().chain(third_name) {
+                write!(f, ".{}", part)?;
+            }
+
+            Ok(())
+        }
+        Literal::Parameter(Parameter::Positional(number)) => write!(f, "${}", number),
+        Literal::Parameter(Parameter::Named(name)) => write!(f, ":{}", name),
+    }
+}
+
+fn op_symbol(op: Op) -> &'static str {
+    match op {
+        Op::And => "AND",
+        Op::Or => "OR",
+        Op::Not => "NOT",
+        Op::Plus => "+",
+        Op::Minus => "-",
+        Op::Multiply => "*",
+        Op::Divide => "/",
+        Op::Modulo => "%",
+        Op::Concat => "||",
+        Op::Equals => "=",
+        Op::NotEquals => "<>",
+        Op::LessThan => "<",
+        Op::GreaterThan => ">",
+        Op::LessThanOrEquals => "<=",
+        Op::GreaterThanOrEquals => ">=",
+        op => panic!("Unexpected operator: {:?}", op),
+    }
+}
+
+fn join<T: fmt::Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn with_predicate(description: String, predicate: &Option<Expression>) -> String {
+    match predicate {
+        Some(predicate) => format!("{} WHERE {}", description, predicate),
+        None => description,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyzer::Analyzer;
+    use crate::catalog::{Catalog, ColumnDefinition, TableDefinition};
+    use crate::parser::lexer::Lexer;
+    use crate::parser::{Parser, Type};
+
+    fn explain(input: &str) -> String {
+        let mut catalog = Catalog::new();
+
+        catalog.create_table(TableDefinition {
+            name: "table1".to_string(),
+            columns: vec![
+                ColumnDefinition::new("col1", Type::Int),
+                ColumnDefinition::new("col2", Type::String),
+            ],
+            constraints: vec![],
+            indexes: vec![],
+        });
+
+        let ast = Parser::new(Lexer::new(input)).parse().unwrap();
+
+        Analyzer::new(&catalog).analyze(&ast).unwrap().to_string()
+    }
+
+    #[test]
+    fn explain_select() {
+        assert_eq!(
+            explain("SELECT col1, col1 + 1 FROM table1 WHERE col1 > 1 AND NOT (col2 = 'x')"),
+            "Projection: col1, col1 + 1\n  \
+             Filter: (col1 > 1) AND (NOT (col2 = 'x'))\n    \
+             Read: table1\n"
+        );
+    }
+
+    #[test]
+    fn explain_operators() {
+        assert_eq!(
+            explain("SELECT - col1 % 2, col2 || 'x' FROM table1"),
+            "Projection: (-col1) % 2, col2 || 'x'\n  \
+             Read: table1\n"
+        );
+    }
+
+    #[test]
+    fn explain_update() {
+        assert_eq!(
+            explain("UPDATE table1 SET col2 = 'a' WHERE col1 = 1"),
+            "Update: table1 SET col2 = 'a' WHERE col1 = 1\n"
+        );
+    }
+}
@@ -1,304 +1,4177 @@
-use crate::parser::{Node, Op};
+#![allow(dead_code)]
 
-pub struct Analyzer {}
+mod error;
+mod explain;
+mod parameters;
 
-impl Analyzer {
-    pub fn new() -> Self {
-        Analyzer {}
+pub use error::{AnalyzeError, AnalyzeErrorKind};
+pub use parameters::ParameterInfo;
+
+use std::cell::RefCell;
+
+use crate::catalog::{
+    Catalog, ColumnDefinition, Constraint, IndexDefinition, TableDefinition, ViewDefinition,
+};
+use crate::parser::lexer::Lexer;
+use crate::parser::{Literal, Node, Op, Parameter, Parser, Spans, Type};
+use crate::transaction::IsolationLevel;
+
+pub struct Analyzer<'a> {
+    catalog: &'a Catalog,
+    /// The statement and where its nodes were written, to point errors at.
+    spans: Option<(&'a Node, &'a Spans)>,
+    /// Common table expressions visible at the current point of the walk,
+    /// innermost last.
+    ctes: RefCell<Vec<(String, Scope)>>,
+    /// Window functions found in the select list being built, with their
+    /// types. `None` where window functions are not allowed.
+    windows: RefCell<Option<Vec<TypedWindowFunction>>>,
+    /// Parameters of the statement, by index, with the types inferred so
+    /// far.
+    parameters: RefCell<Vec<ParameterInfo>>,
+}
+
+type TypedWindowFunction = (WindowFunction, Option<Type>);
+
+impl<'a> Analyzer<'a> {
+    pub fn new(catalog: &'a Catalog) -> Self {
+        Analyzer {
+            catalog,
+            spans: None,
+            ctes: RefCell::new(vec![]),
+            windows: RefCell::new(None),
+            parameters: RefCell::new(vec![]),
+        }
     }
 
-    pub fn analyze(&self, ast: &Node) -> LogicalPlan {
-        LogicalPlan {
-            root: self.walk(ast)[0].clone()
+    /// An analyzer whose errors point at the nodes they are about, using
+    /// the spans the statement was parsed with. Only that statement, not a
+    /// copy of it, can be analyzed with them.
+    pub fn with_spans(catalog: &'a Catalog, statement: &'a Node, spans: &'a Spans) -> Self {
+        Analyzer {
+            spans: Some((statement, spans)),
+            ..Self::new(catalog)
         }
     }
 
-    fn walk(&self, node: &Node) -> Vec<LogicalNode> {
+    /// Parameters of the statement analyzed last. A parameter whose type
+    /// could not be inferred accepts any value.
+    pub fn parameters(&self) -> Vec<ParameterInfo> {
+        self.parameters.borrow().clone()
+    }
+
+    pub fn analyze(&self, ast: &Node) -> Result<LogicalPlan, AnalyzeError> {
+        Ok(LogicalPlan {
+            root: self.walk(ast)?,
+        })
+    }
+
+    fn walk(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
         match node.op() {
-            Some(Op::Select) => {
-                let children = node.children();
-                let from_node = children[children.len() - 1].clone();
-                let columns_node = children[0].clone();
+            Some(Op::Select)
+            | Some(Op::Union)
+            | Some(Op::UnionAll)
+            | Some(Op::With)
+            | Some(Op::WithRecursive) => self.walk_query(node).map(|(node, _)| node),
+            Some(Op::InsertInto) => self.walk_returning(node, self.walk_insert_into(node)?),
+            Some(Op::Update) => self.walk_returning(node, self.walk_update(node)?),
+            Some(Op::Delete) => self.walk_returning(node, self.walk_delete(node)?),
+            Some(Op::Merge) => self.walk_merge(node),
+            Some(Op::CreateTable) => self.walk_create_table(node),
+            Some(Op::CreateTableAs) => self.walk_create_table_as(node),
+            Some(Op::DropTable) => self.walk_drop_table(node),
+            Some(Op::CreateView) => self.walk_create_view(node),
+            Some(Op::DropView) => self.walk_drop_view(node),
+            Some(Op::CreateIndex) => self.walk_create_index(node),
+            Some(Op::DropIndex) => self.walk_drop_index(node),
+            Some(Op::Explain) | Some(Op::ExplainAnalyze) => self.walk_explain(node),
+            Some(Op::Begin) | Some(Op::Commit) | Some(Op::Rollback) | Some(Op::RollbackTo)
+            | Some(Op::Savepoint) | Some(Op::Release) => self.walk_transaction(node),
+            _ => Err(
+                AnalyzeErrorKind::Unsupported(format!("Not a statement: {}", node.to_sql())).into(),
+            ),
+        }
+        .map_err(self.locate(node))
+    }
 
-                let columns = self.build_columns(&columns_node);
+    /// Points an error that does not have a span yet at the node.
+    fn locate<'b>(&'b self, node: &'b Node) -> impl FnOnce(AnalyzeError) -> AnalyzeError + 'b {
+        move |mut error| {
+            if error.span.is_none() {
+                error.span = self
+                    .spans
+                    .and_then(|(statement, spans)| spans.get(statement, node));
+            }
 
-                vec![LogicalNode {
-                    operator: Operator::Projection(ProjectionInfo {
-                        columns
-                    }),
-                    children: self.walk(&from_node)
-                }]
-            },
-            Some(Op::From) => {
-                let children = node.children();
-                let tables_node = children[0].clone();
+            error
+        }
+    }
 
-                let tables = self.build_tables(&tables_node);
+    fn walk_query(&self, node: &Node) -> Result<(LogicalNode, Scope), AnalyzeError> {
+        match node.op() {
+            Some(Op::Select) => self.walk_select(node),
+            Some(Op::Union) | Some(Op::UnionAll) => self.walk_union(node),
+            Some(Op::With) | Some(Op::WithRecursive) => self.walk_with(node),
+            _ => {
+                Err(AnalyzeErrorKind::Unsupported(format!("Not a query: {}", node.to_sql())).into())
+            }
+        }
+        .map_err(self.locate(node))
+    }
 
-                let mut nodes = vec![];
+    fn walk_union(&self, node: &Node) -> Result<(LogicalNode, Scope), AnalyzeError> {
+        let [lhs, rhs] = operands(node)?;
 
-                for table in tables {
-                    let node = LogicalNode {
-                            operator: Operator::Read(ReadInfo {
-                                table: Table {
-                                    name: table.name
-                                }
-                            }),
-                            children: vec![]
-                        };
+        let (lhs, lhs_scope) = self.walk_query(lhs)?;
+        let (rhs, rhs_scope) = self.walk_query(rhs)?;
 
-                    nodes.push(node);
-                }
+        let node = LogicalNode {
+            operator: Operator::Union(UnionInfo {
+                all: node.op() == Some(Op::UnionAll),
+            }),
+            children: vec![lhs, rhs],
+        };
 
-                nodes
-            },
-            _ => panic!()
-        }
+        Ok((node, union_scope(lhs_scope, &rhs_scope)?))
     }
 
-    fn build_columns(&self, columns_node: &Node) -> Vec<Column> {
-        let mut column_walker = ColumnWalker::new();
+    fn walk_with(&self, node: &Node) -> Result<(LogicalNode, Scope), AnalyzeError> {
+        let children = node.children();
+        let (body, definitions) = children.split_last().ok_or_else(|| unexpected(node))?;
 
-        column_walker.walk(&columns_node)
-    }
+        let recursive = node.op() == Some(Op::WithRecursive);
+        let depth = self.ctes.borrow().len();
 
-    fn build_tables(&self, tables_node: &Node) -> Vec<Table> {
-        let mut table_walker = TableWalker::new();
+        let mut ctes = vec![];
+        let mut nodes = vec![];
 
-        table_walker.walk(&tables_node)
-    }
-}
+        let mut walk = || {
+            for definition in definitions {
+                let (cte, node) = self.walk_cte(definition, recursive)?;
 
-struct ColumnWalker {
-    columns: Vec<Column>
-}
+                if ctes.iter().any(|existing: &Cte| existing.name == cte.name) {
+                    return Err(AnalyzeError::from(AnalyzeErrorKind::Invalid(format!(
+                        "CTE specified more than once: {}",
+                        cte.name
+                    ))))
+                    .map_err(self.locate(definition));
+                }
 
-impl ColumnWalker {
-    fn new() -> Self {
-        ColumnWalker {
-            columns: vec![]
-        }
+                ctes.push(cte);
+                nodes.push(node);
+            }
+
+            self.walk_query(body)
+        };
+
+        let walked = walk();
+
+        // The CTEs go out of scope even when the WITH fails, so that the
+        // analyzer can be used again.
+        self.ctes.borrow_mut().truncate(depth);
+
+        let (body, scope) = walked?;
+        nodes.push(body);
+
+        let node = LogicalNode {
+            operator: Operator::With(WithInfo { ctes }),
+            children: nodes,
+        };
+
+        Ok((node, scope))
     }
 
-    fn walk(&mut self, column_node: &Node) -> Vec<Column> {
-        match column_node.op() {
-            Some(Op::Comma) => {
-                let children = column_node.children();
+    /// Analyzes a CTE definition and makes it visible to the rest of the
+    /// WITH. Under `WITH RECURSIVE`, the right side of a UNION may read the
+    /// CTE itself, which then sees the rows produced by the previous step.
+    fn walk_cte(&self, node: &Node, recursive: bool) -> Result<(Cte, LogicalNode), AnalyzeError> {
+        let [name, names, query] = operands(node)?;
+        let name = name_of(name)?;
 
-                self.walk(&children[0].clone());
-                self.walk(&children[1].clone())
-            }
-            None => {
-                let column_name = column_node.literal().unwrap().get_first_name_as_string();
+        let names = names
+            .children()
+            .iter()
+            .map(name_of)
+            .collect::<Result<Vec<String>, _>>()?;
 
-                self.columns.push(
-                    Column {
-                        name: column_name
-                    }
-                );
+        let (plan, scope, recursive) = match query.op() {
+            Some(Op::Union) | Some(Op::UnionAll) if recursive => {
+                let [anchor, term] = operands(query)?;
 
-                self.columns.clone()
-            },
+                let (anchor, anchor_scope) = self.walk_query(anchor)?;
+                let scope = rename_scope(anchor_scope, &names)?;
+
+                self.ctes
+                    .borrow_mut()
+                    .push((name.clone(), scope.clone().qualified(&name)));
+                let term = self.walk_query(term);
+                self.ctes.borrow_mut().pop();
+                let (term, term_scope) = term?;
+
+                let recursive = reads(&term, &name);
+
+                let plan = LogicalNode {
+                    operator: Operator::Union(UnionInfo {
+                        all: query.op() == Some(Op::UnionAll),
+                    }),
+                    children: vec![anchor, term],
+                };
+
+                (
+                    plan,
+                    union_scope(scope, &term_scope).map_err(self.locate(query))?,
+                    recursive,
+                )
+            }
             _ => {
-                panic!("Unexpected")
+                let (plan, scope) = self.walk_query(query)?;
+
+                (
+                    plan,
+                    rename_scope(scope, &names).map_err(self.locate(node))?,
+                    false,
+                )
             }
-        }
+        };
+
+        let cte = Cte {
+            name: name.clone(),
+            columns: scope
+                .columns
+                .iter()
+                .map(|column| column.name.clone())
+                .collect(),
+            recursive,
+        };
+
+        self.ctes
+            .borrow_mut()
+            .push((name.clone(), scope.qualified(&name)));
+
+        Ok((cte, plan))
     }
-}
 
-struct TableWalker {
-    tables: Vec<Table>
-}
+    fn walk_select(&self, node: &Node) -> Result<(LogicalNode, Scope), AnalyzeError> {
+        let [columns_node] = operands(node)?;
 
-impl TableWalker {
-    fn new() -> Self {
-        TableWalker {
-            tables: vec![]
+        let (inputs, scope) = match node.children().get(1) {
+            Some(from_node) => self.walk_from(from_node)?,
+            None => (vec![], Scope::default()),
+        };
+
+        let previous = self.windows.replace(Some(vec![]));
+        let expressions = self.build_columns(columns_node, &scope);
+        let windows = self.windows.replace(previous).unwrap();
+        let expressions = expressions?;
+
+        let mut scope = scope;
+        let mut inputs = inputs;
+
+        if !windows.is_empty() {
+            let mut functions = vec![];
+
+            for (index, (function, typ)) in windows.into_iter().enumerate() {
+                scope.columns.push(ScopeColumn {
+                    table: Some(window_table(index)),
+                    name: function.function.name().to_string(),
+                    typ,
+                });
+                functions.push(function);
+            }
+
+            inputs = vec![LogicalNode {
+                operator: Operator::Window(WindowInfo { functions }),
+                children: inputs,
+            }];
         }
+
+        let output = Scope {
+            columns: expressions
+                .iter()
+                .map(|expression| {
+                    Ok(ScopeColumn {
+                        table: None,
+                        name: expression.output_name(),
+                        typ: self.expression_type(expression, &scope)?,
+                    })
+                })
+                .collect::<Result<_, AnalyzeError>>()?,
+        };
+
+        let node = LogicalNode {
+            operator: Operator::Projection(ProjectionInfo { expressions }),
+            children: inputs,
+        };
+
+        Ok((node, output))
     }
 
-    fn walk(&mut self, table_node: &Node) -> Vec<Table> {
-        match table_node.op() {
-            Some(Op::Comma) => {
-                let children = table_node.children();
+    fn walk_from(&self, node: &Node) -> Result<(Vec<LogicalNode>, Scope), AnalyzeError> {
+        let [tables_node] = operands(node)?;
 
-                self.walk(&children[0].clone());
-                self.walk(&children[1].clone());
+        let mut nodes = vec![];
+        let mut scope = Scope::default();
 
-                self.tables.clone()
-            },
-            None => {
-                let table_name = table_node.literal().unwrap().get_first_name_as_string();
+        for table_node in self.build_tables(tables_node)? {
+            let name = name_of(table_node)?;
+            let (node, relation_scope) =
+                self.walk_relation(&name).map_err(self.locate(table_node))?;
 
-                self.tables.push(
-                    Table {
-                        name: table_name
-                    }
-                );
+            scope.columns.extend(relation_scope.columns);
+            nodes.push(node);
+        }
 
-                self.tables.clone()
-            },
+        if let Some(where_node) = node.children().get(1) {
+            let [condition] = operands(where_node)?;
+            let predicate = self.build_condition(condition, &scope)?;
+
+            nodes = vec![LogicalNode {
+                operator: Operator::Filter(FilterInfo { predicate }),
+                children: nodes,
+            }];
+        }
+
+        Ok((nodes, scope))
+    }
+
+    /// Reads a relation named in FROM. A view is replaced by the plan of its
+    /// query, analyzed against the current catalog.
+    fn walk_relation(&self, name: &str) -> Result<(LogicalNode, Scope), AnalyzeError> {
+        let is_cte = self.ctes.borrow().iter().any(|(cte, _)| cte == name);
+
+        let view = match self.catalog.view(name) {
+            Some(view) if !is_cte => view,
             _ => {
-                panic!("Unexpected")
+                let node = LogicalNode {
+                    operator: Operator::Read(ReadInfo {
+                        table: Table {
+                            name: name.to_string(),
+                        },
+                    }),
+                    children: vec![],
+                };
+
+                return Ok((node, self.relation_scope(name)?));
             }
-        }
+        };
+
+        let query = Parser::new(Lexer::new(&view.query))
+            .parse()
+            .map_err(|error| {
+                AnalyzeErrorKind::Invalid(format!("Invalid query of view {}: {}", view.name, error))
+            })?;
+
+        // The view's query cannot see the CTEs of the query reading it, and
+        // its errors point at the view's name in that query.
+        let (plan, scope) = Analyzer::new(self.catalog).walk_query(&query)?;
+        let scope = rename_scope(scope, &view.columns)?;
+
+        let node = LogicalNode {
+            operator: Operator::Subquery(SubqueryInfo {
+                name: view.name.clone(),
+                columns: scope
+                    .columns
+                    .iter()
+                    .map(|column| column.name.clone())
+                    .collect(),
+            }),
+            children: vec![plan],
+        };
+
+        Ok((node, scope.qualified(name)))
     }
-}
 
-#[derive(Debug, PartialEq, Clone)]
-struct Table {
-    name: String
-}
+    fn walk_create_table(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name, definitions] = operands(node)?;
+        let table_name = name_of(name)?;
+        let if_not_exists = node
+            .children()
+            .iter()
+            .skip(2)
+            .any(|option| option.op() == Some(Op::IfNotExists));
 
-#[derive(Debug, PartialEq, Clone)]
-enum Operator {
-    Projection(ProjectionInfo),
-    Filter(FilterInfo),
-    Read(ReadInfo),
-    Join(JoinInfo),
-    Group(GroupInfo),
-    Sort(SortInfo),
-    Limit(LimitInfo),
-    Distinct(DistinctInfo),
-}
+        if !if_not_exists {
+            self.check_new_relation(&table_name)
+                .map_err(self.locate(name))?;
+        }
 
-#[derive(Debug, PartialEq, Clone)]
-struct ProjectionInfo {
-    columns: Vec<Column>,
-}
+        let mut table = TableDefinition {
+            name: table_name,
+            columns: vec![],
+            constraints: vec![],
+            indexes: vec![],
+        };
 
-#[derive(Debug, PartialEq, Clone)]
-enum LogicalOperation {
-    AndOp(),
-    LessThan()
-}
+        let definitions = match definitions.op() {
+            Some(Op::Comma) => definitions.children(),
+            _ => std::slice::from_ref(definitions),
+        };
 
-#[derive(Debug, PartialEq, Clone)]
-struct FilterInfo {
+        let mut constraints = vec![];
 
-}
+        for definition in definitions {
+            if definition.op() != Some(Op::ColumnDefinition) {
+                continue;
+            }
 
-#[derive(Debug, PartialEq, Clone)]
-struct ReadInfo {
-    table: Table
-}
+            let [name, typ] = operands(definition)?;
+            let column_name = name_of(name)?;
 
-#[derive(Debug, PartialEq, Clone)]
-struct JoinInfo {
+            if table.column(&column_name).is_some() {
+                return Err(AnalyzeError::from(duplicate_column(&column_name)))
+                    .map_err(self.locate(name));
+            }
 
-}
+            let typ = match typ {
+                Node::LeafType(typ) => *typ,
+                _ => return Err(unexpected(typ)),
+            };
 
-#[derive(Debug, PartialEq, Clone)]
-struct GroupInfo {}
+            let mut column = ColumnDefinition::new(&column_name, typ);
 
-#[derive(Debug, PartialEq, Clone)]
-struct SortInfo {}
+            for constraint in definition.children().iter().skip(2) {
+                match constraint {
+                    Node::Prefix(Op::NotNull, _) => column.nullable = false,
+                    Node::Prefix(Op::Default, _) => {
+                        let [value] = operands(constraint)?;
+                        column.default = Some(self.build_default(value, &column)?)
+                    }
+                    constraint => constraints.push((constraint, vec![column_name.clone()])),
+                }
+            }
 
-#[derive(Debug, PartialEq, Clone)]
-struct LimitInfo {}
+            table.columns.push(column);
+        }
 
-#[derive(Debug, PartialEq, Clone)]
-struct DistinctInfo {}
+        for definition in definitions {
+            if definition.op() == Some(Op::ColumnDefinition) {
+                continue;
+            }
 
-#[derive(Debug, PartialEq, Clone)]
-struct LogicalNode {
-    pub operator: Operator,
-    pub children: Vec<LogicalNode>,
-}
+            let (constraint, columns) = match definition.op() {
+                Some(Op::ForeignKey) => {
+                    let [columns, references] = operands(definition)?;
 
-#[derive(Debug, PartialEq)]
-pub struct LogicalPlan {
-    pub root: LogicalNode,
-}
+                    (references, columns.children())
+                }
+                _ => (definition, definition.children()),
+            };
 
-#[derive(Debug, PartialEq, Clone)]
-struct Column {
-    name: String
-}
+            let columns = self
+                .build_column_list(&table, columns)?
+                .into_iter()
+                .map(|column| column.name)
+                .collect();
 
-mod tests {
-    use crate::analyzer::{Analyzer, Column, LogicalNode, LogicalPlan, Operator, ProjectionInfo, ReadInfo, Table};
-    use crate::parser::lexer::Lexer;
-    use crate::parser::Parser;
+            constraints.push((constraint, columns));
+        }
 
-    fn column(name: &str) -> Column {
-        Column { name: name.to_string() }
-    }
+        // Foreign keys go last, so that a table can reference its own keys.
+        constraints.sort_by_key(|(constraint, _)| constraint.op() == Some(Op::References));
 
-    fn table(name: &str) -> Table {
-        Table { name: name.to_string() }
+        for (constraint, columns) in constraints {
+            let constraint = match constraint.op() {
+                Some(Op::PrimaryKey) => {
+                    if table
+                        .constraints
+                        .iter()
+                        .any(|constraint| matches!(constraint, Constraint::PrimaryKey(_)))
+                    {
+                        return Err(AnalyzeErrorKind::Invalid(format!(
+                            "Multiple primary keys for table: {}",
+                            table.name
+                        ))
+                        .into());
+                    }
+
+                    Constraint::PrimaryKey(columns)
+                }
+                Some(Op::Unique) => Constraint::Unique(columns),
+                Some(Op::References) => self.build_foreign_key(&table, columns, constraint)?,
+                _ => return Err(unexpected(constraint)),
+            };
+
+            table.constraints.push(constraint);
+        }
+
+        let primary_key = table.primary_key().unwrap_or_default().to_vec();
+
+        for column in table
+            .columns
+            .iter_mut()
+            .filter(|column| primary_key.contains(&column.name))
+        {
+            column.nullable = false;
+        }
+
+        Ok(LogicalNode {
+            operator: Operator::CreateTable(CreateTableInfo {
+                table,
+                if_not_exists,
+            }),
+            children: vec![],
+        })
     }
 
-    fn projection(columns: Vec<Column>) -> Operator {
-        Operator::Projection(ProjectionInfo { columns })
+    /// Creates a table with the columns of a query's output and fills it
+    /// with the query's rows. Columns whose type is unknown, such as a bare
+    /// NULL, become TEXT.
+    fn walk_create_table_as(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name, query] = operands(node)?;
+        let table_name = name_of(name)?;
+        let if_not_exists = node
+            .children()
+            .iter()
+            .skip(2)
+            .any(|option| option.op() == Some(Op::IfNotExists));
+
+        if !if_not_exists {
+            self.check_new_relation(&table_name)
+                .map_err(self.locate(name))?;
+        }
+
+        let (plan, scope) = self.walk_query(query)?;
+
+        let mut table = TableDefinition {
+            name: table_name,
+            columns: vec![],
+            constraints: vec![],
+            indexes: vec![],
+        };
+
+        for column in scope.columns {
+            if table.column(&column.name).is_some() {
+                return Err(AnalyzeError::from(duplicate_column(&column.name)))
+                    .map_err(self.locate(query));
+            }
+
+            table.columns.push(ColumnDefinition::new(
+                &column.name,
+                column.typ.unwrap_or(Type::String),
+            ));
+        }
+
+        Ok(LogicalNode {
+            operator: Operator::CreateTable(CreateTableInfo {
+                table,
+                if_not_exists,
+            }),
+            children: vec![plan],
+        })
     }
 
-    fn read(table: Table) -> Operator {
-        Operator::Read(ReadInfo { table })
+    /// Checks that a table or view can be created under the name.
+    fn check_new_relation(&self, name: &str) -> Result<(), AnalyzeError> {
+        if self.catalog.table(name).is_some() {
+            return Err(
+                AnalyzeErrorKind::Invalid(format!("Table already exists: {}", name)).into(),
+            );
+        }
+
+        if self.catalog.view(name).is_some() {
+            return Err(AnalyzeErrorKind::Invalid(format!("View already exists: {}", name)).into());
+        }
+
+        Ok(())
     }
 
-    fn analyze(input: &str) -> LogicalPlan {
-        let lexer = Lexer::new(input);
-        let mut parser = Parser::new(lexer);
-        let analyzer = Analyzer::new();
+    /// The default of a column, evaluated for every row inserted without a
+    /// value for it. It can be any expression that does not depend on the
+    /// row or on the statement.
+    fn build_default(
+        &self,
+        node: &Node,
+        column: &ColumnDefinition,
+    ) -> Result<Expression, AnalyzeError> {
+        let expression = self.build_expression(node, &Scope::default())?;
+
+        let checked = match contains_parameter(&expression) {
+            true => Err(AnalyzeErrorKind::Invalid(format!(
+                "Parameters are not allowed in the default of column: {}",
+                column.name
+            ))
+            .into()),
+            false => self.check_value(&expression, &Scope::default(), column),
+        };
+
+        checked.map_err(self.locate(node))?;
 
-        analyzer.analyze(&parser.parse())
+        Ok(expression)
     }
 
-    #[test]
-    fn simple_test() {
-        let logical_plan = analyze("SELECT col1 FROM table1");
+    /// Checks a foreign key of the given table, which may reference the
+    /// table itself. The referenced columns have to form a unique key.
+    fn build_foreign_key(
+        &self,
+        table: &TableDefinition,
+        columns: Vec<String>,
+        references: &Node,
+    ) -> Result<Constraint, AnalyzeError> {
+        let [name, referenced_columns] = operands(references)?;
+        let referenced_name = name_of(name)?;
 
-        assert_eq!(
-            logical_plan,
-            LogicalPlan {
-                root: LogicalNode {
-                    operator: projection(vec![column("col1")]),
-                    children: vec![LogicalNode {
-                        operator: read(table("table1")),
-                        children: vec![],
-                    }]
+        let referenced = match referenced_name == table.name {
+            true => table,
+            false => self
+                .table_definition(&referenced_name)
+                .map_err(self.locate(name))?,
+        };
+
+        let referenced_columns: Vec<String> = match referenced_columns.children() {
+            [] => match referenced.primary_key() {
+                Some(key) => key.to_vec(),
+                None => {
+                    return Err(AnalyzeErrorKind::Invalid(format!(
+                        "No primary key for table: {}",
+                        referenced.name
+                    ))
+                    .into())
                 }
+            },
+            nodes => self
+                .build_column_list(referenced, nodes)?
+                .into_iter()
+                .map(|column| column.name)
+                .collect(),
+        };
+
+        if columns.len() != referenced_columns.len() {
+            return Err(wrong_number(
+                "columns",
+                referenced_columns.len(),
+                columns.len(),
+            ));
+        }
+
+        let is_unique = referenced.unique_keys().iter().any(|key| {
+            key.len() == referenced_columns.len()
+                && key.iter().all(|column| referenced_columns.contains(column))
+        });
+
+        if !is_unique {
+            return Err(AnalyzeErrorKind::Invalid(format!(
+                "No unique key on referenced columns of table: {}",
+                referenced.name
+            ))
+            .into());
+        }
+
+        for (column, referenced_column) in columns.iter().zip(&referenced_columns) {
+            let typ = table.column(column).unwrap().typ;
+            let referenced_type = referenced.column(referenced_column).unwrap().typ;
+
+            if !is_comparable(typ, referenced_type) {
+                return Err(type_mismatch(format!(
+                    "{:?} references {:?}",
+                    typ, referenced_type
+                )));
             }
-        )
+        }
+
+        Ok(Constraint::ForeignKey {
+            columns,
+            table: referenced.name.clone(),
+            referenced_columns,
+        })
     }
 
-    #[test]
-    fn select_many_columns() {
-        let logical_plan = analyze("SELECT col1, col2, col3 FROM table1");
+    /// Drops every named table that exists. Views reading them and foreign
+    /// keys of other tables referencing them stop the drop, unless CASCADE
+    /// is given, in which case the views are dropped and the foreign keys
+    /// removed.
+    fn walk_drop_table(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let children = node.children();
 
-        assert_eq!(
-            logical_plan,
-            LogicalPlan {
-                root: LogicalNode {
-                    operator: projection(vec![column("col1"), column("col2"), column("col3")]),
-                    children: vec![LogicalNode {
-                        operator: read(table("table1")),
-                        children: vec![],
-                    }]
+        let (names, options): (Vec<&Node>, Vec<&Node>) =
+            children.iter().partition(|child| child.literal().is_some());
+        let options: Vec<Node> = options.into_iter().cloned().collect();
+
+        let if_exists = options
+            .iter()
+            .any(|option| option.op() == Some(Op::IfExists));
+
+        let mut tables: Vec<String> = vec![];
+
+        for node in names {
+            let name = name_of(node)?;
+
+            if self.catalog.table(&name).is_none() && if_exists {
+                continue;
+            }
+
+            self.table_definition(&name).map_err(self.locate(node))?;
+
+            if !tables.contains(&name) {
+                tables.push(name);
+            }
+        }
+
+        let mut views = vec![];
+        let mut foreign_keys = vec![];
+
+        for table in &tables {
+            for view in self.drop_dependents(table, &options)? {
+                if !views.contains(&view) {
+                    views.push(view);
                 }
             }
-        )
-    }
 
-    #[test]
-    fn select_many_tables() {
-        let logical_plan = analyze("SELECT col1 FROM table1, table2");
+            for referencing in self.catalog.referencing_tables(table) {
+                if tables.contains(&referencing) || foreign_keys.contains(&referencing) {
+                    continue;
+                }
 
-        assert_eq!(
-            logical_plan,
-            LogicalPlan {
-                root: LogicalNode {
-                    operator: projection(vec![column("col1")]),
-                    children: vec![LogicalNode {
-                        operator: read(table("table1")),
-                        children: vec![],
-                    }, LogicalNode {
-                        operator: read(table("table2")),
-                        children: vec![],
-                    }]
+                if !options
+                    .iter()
+                    .any(|option| option.op() == Some(Op::Cascade))
+                {
+                    return Err(dependent_objects(table, &referencing));
                 }
+
+                foreign_keys.push(referencing);
             }
-        )
+        }
+
+        views.sort();
+        foreign_keys.sort();
+
+        Ok(LogicalNode {
+            operator: Operator::DropTable(DropTableInfo {
+                tables: tables.into_iter().map(|name| Table { name }).collect(),
+                views,
+                foreign_keys,
+            }),
+            children: vec![],
+        })
     }
-}
\ No newline at end of file
+
+    fn walk_create_view(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name_node, names, text, query] = operands(node)?;
+        let name = name_of(name_node)?;
+        let or_replace = node.children().get(4).map(|node| node.op()) == Some(Some(Op::OrReplace));
+
+        let existing = self.catalog.view(&name);
+
+        if self.catalog.table(&name).is_some() || (existing.is_some() && !or_replace) {
+            self.check_new_relation(&name)
+                .map_err(self.locate(name_node))?;
+        }
+
+        let names = names
+            .children()
+            .iter()
+            .map(name_of)
+            .collect::<Result<Vec<String>, _>>()?;
+
+        let (plan, scope) = self.walk_query(query)?;
+
+        let query = match text.literal() {
+            Some(Literal::String(query)) => query,
+            _ => return Err(unexpected(text)),
+        };
+
+        let scope = rename_scope(scope, &names)?;
+
+        if !self.parameters.borrow().is_empty() {
+            return Err(AnalyzeErrorKind::Invalid(format!(
+                "Views cannot have parameters: {}",
+                name
+            ))
+            .into());
+        }
+
+        let columns: Vec<String> = scope
+            .columns
+            .iter()
+            .map(|column| column.name.clone())
+            .collect();
+
+        for (i, column) in columns.iter().enumerate() {
+            if columns[..i].contains(column) {
+                return Err(duplicate_column(column).into());
+            }
+        }
+
+        if let Some(existing) = existing {
+            let existing_columns = self.walk_relation(&existing.name)?.1.columns;
+
+            let keeps_columns = existing_columns.len() <= scope.columns.len()
+                && existing_columns
+                    .iter()
+                    .zip(&scope.columns)
+                    .all(|(old, new)| {
+                        old.name == new.name && (old.typ.is_none() || old.typ == new.typ)
+                    });
+
+            if !keeps_columns {
+                return Err(AnalyzeErrorKind::Invalid(format!(
+                    "Cannot change columns of view: {}",
+                    name
+                ))
+                .into());
+            }
+        }
+
+        let mut dependencies = vec![];
+        collect_dependencies(&plan, &mut dependencies);
+
+        let ctes = cte_names(&plan);
+        dependencies.retain(|dependency| {
+            !ctes.contains(dependency)
+                && (self.catalog.table(dependency).is_some()
+                    || self.catalog.view(dependency).is_some())
+        });
+        dependencies.sort();
+        dependencies.dedup();
+
+        // A replaced view must not end up reading itself.
+        let dependents = self.catalog.dependent_views(&name);
+        if let Some(dependency) = dependencies
+            .iter()
+            .find(|dependency| **dependency == name || dependents.contains(dependency))
+        {
+            return Err(AnalyzeErrorKind::Invalid(format!(
+                "View would depend on itself: {} reads {}",
+                name, dependency
+            ))
+            .into());
+        }
+
+        Ok(LogicalNode {
+            operator: Operator::CreateView(CreateViewInfo {
+                view: ViewDefinition {
+                    name,
+                    columns: names,
+                    query,
+                    dependencies,
+                },
+            }),
+            children: vec![],
+        })
+    }
+
+    fn walk_drop_view(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name_node] = operands(node)?;
+        let name = name_of(name_node)?;
+
+        if self.catalog.view(&name).is_none() {
+            return Err(AnalyzeError::from(AnalyzeErrorKind::Invalid(format!(
+                "Unknown view: {}",
+                name
+            ))))
+            .map_err(self.locate(name_node));
+        }
+
+        let dependents = self.drop_dependents(&name, &node.children()[1..])?;
+
+        Ok(LogicalNode {
+            operator: Operator::DropView(DropViewInfo { name, dependents }),
+            children: vec![],
+        })
+    }
+
+    fn walk_create_index(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name_node, table_node, columns] = operands(node)?;
+        let options = &node.children()[3..];
+        let name = name_of(name_node)?;
+        let table = self
+            .table_definition(&name_of(table_node)?)
+            .map_err(self.locate(table_node))?;
+        let if_not_exists = options
+            .iter()
+            .any(|option| option.op() == Some(Op::IfNotExists));
+
+        if self.catalog.index(&name).is_some() && !if_not_exists {
+            return Err(AnalyzeError::from(AnalyzeErrorKind::Invalid(format!(
+                "Index already exists: {}",
+                name
+            ))))
+            .map_err(self.locate(name_node));
+        }
+
+        let columns = self
+            .build_column_list(table, columns.children())?
+            .into_iter()
+            .map(|column| column.name)
+            .collect();
+
+        Ok(LogicalNode {
+            operator: Operator::CreateIndex(CreateIndexInfo {
+                table: Table {
+                    name: table.name.clone(),
+                },
+                index: IndexDefinition {
+                    name,
+                    columns,
+                    unique: options.iter().any(|option| option.op() == Some(Op::Unique)),
+                },
+                if_not_exists,
+            }),
+            children: vec![],
+        })
+    }
+
+    fn walk_drop_index(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name_node] = operands(node)?;
+        let name = name_of(name_node)?;
+
+        if self.catalog.index(&name).is_none() && node.children().get(1).is_none() {
+            return Err(AnalyzeError::from(AnalyzeErrorKind::Invalid(format!(
+                "Unknown index: {}",
+                name
+            ))))
+            .map_err(self.locate(name_node));
+        }
+
+        Ok(LogicalNode {
+            operator: Operator::DropIndex(DropIndexInfo { name }),
+            children: vec![],
+        })
+    }
+
+    fn walk_explain(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [statement] = operands(node)?;
+
+        match statement.op() {
+            Some(Op::Select)
+            | Some(Op::Union)
+            | Some(Op::UnionAll)
+            | Some(Op::With)
+            | Some(Op::WithRecursive)
+            | Some(Op::InsertInto)
+            | Some(Op::Update)
+            | Some(Op::Delete)
+            | Some(Op::Merge)
+            | Some(Op::CreateTableAs) => {}
+            op => {
+                return Err(
+                    AnalyzeErrorKind::Unsupported(format!("Cannot explain: {:?}", op)).into(),
+                )
+            }
+        }
+
+        Ok(LogicalNode {
+            operator: Operator::Explain(ExplainInfo {
+                analyze: node.op() == Some(Op::ExplainAnalyze),
+            }),
+            children: vec![self.walk(statement)?],
+        })
+    }
+
+    fn walk_transaction(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let name = || operands(node).and_then(|[name]| name_of(name));
+
+        let statement = match node.op() {
+            Some(Op::Begin) => {
+                TransactionStatement::Begin(match node.children().first().and_then(Node::op) {
+                    Some(Op::ReadUncommitted) => IsolationLevel::ReadUncommitted,
+                    Some(Op::ReadCommitted) => IsolationLevel::ReadCommitted,
+                    Some(Op::RepeatableRead) => IsolationLevel::RepeatableRead,
+                    Some(Op::Serializable) => IsolationLevel::Serializable,
+                    _ => IsolationLevel::default(),
+                })
+            }
+            Some(Op::Commit) => TransactionStatement::Commit,
+            Some(Op::Rollback) => TransactionStatement::Rollback,
+            Some(Op::RollbackTo) => TransactionStatement::RollbackTo(name()?),
+            Some(Op::Savepoint) => TransactionStatement::Savepoint(name()?),
+            Some(Op::Release) => TransactionStatement::Release(name()?),
+            _ => return Err(unexpected(node)),
+        };
+
+        Ok(LogicalNode {
+            operator: Operator::Transaction(statement),
+            children: vec![],
+        })
+    }
+
+    /// Views that have to go when the given relation is dropped. Without
+    /// CASCADE, there must be none.
+    fn drop_dependents(&self, name: &str, options: &[Node]) -> Result<Vec<String>, AnalyzeError> {
+        let cascade = options
+            .iter()
+            .any(|option| option.op() == Some(Op::Cascade));
+        let dependents = self.catalog.dependent_views(name);
+
+        if !dependents.is_empty() && !cascade {
+            return Err(dependent_objects(name, &dependents.join(", ")));
+        }
+
+        Ok(dependents)
+    }
+
+    fn walk_insert_into(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name, column_list, source] = operands(node)?;
+        let table_name = name_of(name)?;
+
+        let table = self
+            .table_definition(&table_name)
+            .map_err(self.locate(name))?;
+
+        let columns: Vec<ColumnDefinition> = if column_list.children().is_empty() {
+            table.columns.clone()
+        } else {
+            self.build_column_list(table, column_list.children())?
+        };
+
+        let source = match source.op() {
+            Some(Op::Values) => {
+                let rows = source
+                    .children()
+                    .iter()
+                    .map(|row| self.build_row(row, &columns, &Scope::default()))
+                    .collect::<Result<_, _>>()?;
+
+                LogicalNode {
+                    operator: Operator::Values(ValuesInfo { rows }),
+                    children: vec![],
+                }
+            }
+            Some(Op::Select) | Some(Op::Union) | Some(Op::UnionAll) => {
+                let (plan, scope) = self.walk_query(source)?;
+
+                let checked = match scope.columns.len() == columns.len() {
+                    true => scope
+                        .columns
+                        .iter()
+                        .zip(&columns)
+                        .filter_map(|(source_column, column)| Some((source_column.typ?, column)))
+                        .try_for_each(|(typ, column)| check_assignable(typ, column)),
+                    false => Err(wrong_number("columns", columns.len(), scope.columns.len())),
+                };
+
+                checked.map_err(self.locate(source))?;
+
+                plan
+            }
+            _ => return Err(unexpected(source)),
+        };
+
+        let on_conflict = node
+            .children()
+            .iter()
+            .find(|child| child.op() == Some(Op::OnConflict))
+            .map(|on_conflict| self.build_on_conflict(on_conflict, table))
+            .transpose()?;
+
+        Ok(LogicalNode {
+            operator: Operator::Insert(InsertInfo {
+                table: Table { name: table_name },
+                columns: columns.iter().map(Column::of).collect(),
+                on_conflict,
+            }),
+            children: vec![source],
+        })
+    }
+
+    fn build_on_conflict(
+        &self,
+        node: &Node,
+        table: &TableDefinition,
+    ) -> Result<OnConflict, AnalyzeError> {
+        let [columns, action] = operands(node)?;
+
+        let columns: Vec<Column> = self
+            .build_column_list(table, columns.children())?
+            .iter()
+            .map(Column::of)
+            .collect();
+
+        if !columns.is_empty()
+            && !table.unique_keys().iter().any(|key| {
+                key.len() == columns.len()
+                    && key
+                        .iter()
+                        .all(|name| columns.iter().any(|column| &column.name == name))
+            })
+        {
+            return Err(AnalyzeErrorKind::Invalid(format!(
+                "No unique or primary key constraint matches the ON CONFLICT columns of table: {}",
+                table.name
+            ))
+            .into());
+        }
+
+        let action = match action.op() {
+            Some(Op::DoNothing) => ConflictAction::Nothing,
+            Some(Op::DoUpdate) => {
+                if columns.is_empty() {
+                    return Err(AnalyzeErrorKind::Invalid(
+                        "ON CONFLICT DO UPDATE requires conflict columns".to_string(),
+                    )
+                    .into());
+                }
+
+                let mut scope = Scope::of_table(table);
+                scope
+                    .columns
+                    .extend(Scope::of_table(table).qualified(EXCLUDED).columns);
+
+                let [assignments] = operands(action)?;
+
+                ConflictAction::Update {
+                    assignments: self.build_assignments(assignments, table, &scope)?,
+                    predicate: self.build_predicate(action.children(), &scope)?,
+                }
+            }
+            _ => return Err(unexpected(action)),
+        };
+
+        Ok(OnConflict { columns, action })
+    }
+
+    /// Resolves a list of column names against a table, in the order given.
+    fn build_column_list(
+        &self,
+        table: &TableDefinition,
+        column_nodes: &[Node],
+    ) -> Result<Vec<ColumnDefinition>, AnalyzeError> {
+        let mut columns: Vec<ColumnDefinition> = vec![];
+
+        for column_node in column_nodes {
+            let column_name = name_of(column_node)?;
+
+            if columns.iter().any(|column| column.name == column_name) {
+                return Err(AnalyzeError::from(duplicate_column(&column_name)))
+                    .map_err(self.locate(column_node));
+            }
+
+            columns.push(
+                table_column(table, &column_name)
+                    .map_err(self.locate(column_node))?
+                    .clone(),
+            );
+        }
+
+        Ok(columns)
+    }
+
+    fn walk_update(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name, assignments] = operands(node)?;
+        let table_name = name_of(name)?;
+
+        let table = self
+            .table_definition(&table_name)
+            .map_err(self.locate(name))?;
+        let scope = Scope::of_table(table);
+
+        Ok(LogicalNode {
+            operator: Operator::Update(UpdateInfo {
+                table: Table { name: table_name },
+                assignments: self.build_assignments(assignments, table, &scope)?,
+                predicate: self.build_predicate(node.children(), &scope)?,
+            }),
+            children: vec![],
+        })
+    }
+
+    fn build_assignments(
+        &self,
+        set_node: &Node,
+        table: &TableDefinition,
+        scope: &Scope,
+    ) -> Result<Vec<Assignment>, AnalyzeError> {
+        let mut assignments: Vec<Assignment> = vec![];
+
+        for assignment in set_node.children() {
+            let [name, value] = operands(assignment)?;
+            let column_name = name_of(name)?;
+
+            let column = table_column(table, &column_name).map_err(self.locate(name))?;
+
+            if assignments
+                .iter()
+                .any(|assignment| assignment.column.name == column_name)
+            {
+                return Err(AnalyzeError::from(duplicate_column(&column_name)))
+                    .map_err(self.locate(name));
+            }
+
+            let expression = self.build_expression(value, scope)?;
+            self.check_value(&expression, scope, column)
+                .map_err(self.locate(value))?;
+
+            assignments.push(Assignment {
+                column: Column::of(column),
+                expression,
+            });
+        }
+
+        Ok(assignments)
+    }
+
+    fn walk_delete(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name] = operands(node)?;
+        let table_name = name_of(name)?;
+
+        let scope = Scope::of_table(
+            self.table_definition(&table_name)
+                .map_err(self.locate(name))?,
+        );
+
+        Ok(LogicalNode {
+            operator: Operator::Delete(DeleteInfo {
+                table: Table { name: table_name },
+                predicate: self.build_predicate(node.children(), &scope)?,
+            }),
+            children: vec![],
+        })
+    }
+
+    /// Merges the rows of a table or view into the target table. Unlike
+    /// FROM, the source cannot be a subquery.
+    fn walk_merge(&self, node: &Node) -> Result<LogicalNode, AnalyzeError> {
+        let [name, source, condition] = operands(node)?;
+        let table_name = name_of(name)?;
+
+        let table = self
+            .table_definition(&table_name)
+            .map_err(self.locate(name))?;
+
+        let (source, source_scope) = match source {
+            Node::Leaf(Literal::Identifier {
+                first_name,
+                second_name: None,
+                third_name: None,
+            }) => self.walk_relation(first_name),
+            _ => Err(AnalyzeErrorKind::Unsupported(format!(
+                "Unsupported source in MERGE, expected a table or view: {:?}",
+                source
+            ))
+            .into()),
+        }
+        .map_err(self.locate(source))?;
+
+        let mut scope = Scope::of_table(table);
+        scope.columns.extend(source_scope.columns.clone());
+
+        let condition = self.build_condition(condition, &scope)?;
+
+        let clauses = node.children()[3..]
+            .iter()
+            .map(|clause| self.build_merge_clause(clause, table, &scope, &source_scope))
+            .collect::<Result<_, _>>()?;
+
+        Ok(LogicalNode {
+            operator: Operator::Merge(MergeInfo {
+                table: Table { name: table_name },
+                condition,
+                clauses,
+            }),
+            children: vec![source],
+        })
+    }
+
+    /// WHEN MATCHED clauses see the target and source rows, WHEN NOT MATCHED
+    /// clauses only the source row.
+    fn build_merge_clause(
+        &self,
+        node: &Node,
+        table: &TableDefinition,
+        scope: &Scope,
+        source_scope: &Scope,
+    ) -> Result<MergeClause, AnalyzeError> {
+        let [action] = operands(node)?;
+
+        let matched = node.op() == Some(Op::WhenMatched);
+        let scope = if matched { scope } else { source_scope };
+
+        let action = match action.op() {
+            Some(Op::Set) => MergeAction::Update(self.build_assignments(action, table, scope)?),
+            Some(Op::Delete) => MergeAction::Delete,
+            Some(Op::DoNothing) => MergeAction::Nothing,
+            Some(Op::InsertInto) => {
+                let [column_list, row] = operands(action)?;
+
+                let columns: Vec<ColumnDefinition> = if column_list.children().is_empty() {
+                    table.columns.clone()
+                } else {
+                    self.build_column_list(table, column_list.children())?
+                };
+
+                MergeAction::Insert {
+                    values: self.build_row(row, &columns, scope)?,
+                    columns: columns.iter().map(Column::of).collect(),
+                }
+            }
+            _ => return Err(unexpected(action)),
+        };
+
+        Ok(MergeClause {
+            matched,
+            predicate: self.build_predicate(node.children(), scope)?,
+            action,
+        })
+    }
+
+    /// Wraps a data-modifying node into a projection of its RETURNING list,
+    /// evaluated over the rows of the target table it touched.
+    fn walk_returning(
+        &self,
+        node: &Node,
+        modification: LogicalNode,
+    ) -> Result<LogicalNode, AnalyzeError> {
+        let children = node.children();
+
+        let returning = match children
+            .iter()
+            .find(|child| child.op() == Some(Op::Returning))
+        {
+            Some(returning) => {
+                let [returning] = operands(returning)?;
+                returning
+            }
+            None => return Ok(modification),
+        };
+
+        let [name] = operands(node)?;
+        let table_name = name_of(name)?;
+        let scope = Scope::of_table(self.table_definition(&table_name)?);
+
+        Ok(LogicalNode {
+            operator: Operator::Projection(ProjectionInfo {
+                expressions: self.build_columns(returning, &scope)?,
+            }),
+            children: vec![modification],
+        })
+    }
+
+    fn build_predicate(
+        &self,
+        children: &[Node],
+        scope: &Scope,
+    ) -> Result<Option<Expression>, AnalyzeError> {
+        match children.iter().find(|child| child.op() == Some(Op::Where)) {
+            Some(where_node) => operands(where_node)
+                .and_then(|[condition]| self.build_condition(condition, scope))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Builds an expression that has to be a boolean.
+    fn build_condition(&self, node: &Node, scope: &Scope) -> Result<Expression, AnalyzeError> {
+        let condition = self.build_expression(node, scope)?;
+        self.expect_type(&condition, scope, Type::Bool)
+            .map_err(self.locate(node))?;
+
+        Ok(condition)
+    }
+
+    fn build_row(
+        &self,
+        row_node: &Node,
+        columns: &[ColumnDefinition],
+        scope: &Scope,
+    ) -> Result<Vec<Expression>, AnalyzeError> {
+        let values = row_node.children();
+
+        if values.len() != columns.len() {
+            return Err(wrong_number("values", columns.len(), values.len()))
+                .map_err(self.locate(row_node));
+        }
+
+        values
+            .iter()
+            .zip(columns)
+            .map(|(value, column)| {
+                if let Node::Leaf(Literal::Default) = value {
+                    return Ok(Expression::Default);
+                }
+
+                let expression = self.build_expression(value, scope)?;
+                self.check_value(&expression, scope, column)
+                    .map_err(self.locate(value))?;
+
+                Ok(expression)
+            })
+            .collect()
+    }
+
+    /// Checks that an expression can be written to the column, giving it
+    /// the column's type if it is a parameter.
+    fn check_value(
+        &self,
+        expression: &Expression,
+        scope: &Scope,
+        column: &ColumnDefinition,
+    ) -> Result<(), AnalyzeError> {
+        self.infer_parameter(expression, column.typ);
+
+        match self.expression_type(expression, scope)? {
+            Some(typ) => check_assignable(typ, column),
+            None => Ok(()),
+        }
+    }
+
+    /// Builds the expression of a node, checking the types of its operands
+    /// on the way, so that errors point at the innermost node they are
+    /// about.
+    fn build_expression(&self, node: &Node, scope: &Scope) -> Result<Expression, AnalyzeError> {
+        let expression = match node {
+            Node::Leaf(Literal::Identifier {
+                first_name,
+                second_name,
+                third_name: None,
+            }) => {
+                let mut column = match second_name {
+                    Some(second_name) => Column {
+                        table: Some(first_name.clone()),
+                        name: second_name.clone(),
+                        typ: None,
+                    },
+                    None => Column::new(first_name),
+                };
+
+                scope.resolve(&column).map(|resolved| {
+                    column.typ = resolved.typ;
+
+                    Expression::Column(column)
+                })
+            }
+            Node::Leaf(Literal::Default) => Err(AnalyzeErrorKind::Invalid(
+                "DEFAULT is only allowed as a whole value".to_string(),
+            )
+            .into()),
+            Node::Leaf(Literal::Parameter(parameter)) => {
+                self.parameter_index(parameter).map(Expression::Parameter)
+            }
+            Node::Leaf(Literal::Wildcard) | Node::Leaf(Literal::Identifier { .. }) => {
+                Err(unexpected(node))
+            }
+            Node::Leaf(literal) => Ok(Expression::Constant(literal.clone())),
+            Node::Infix(op, _) => operands(node).and_then(|[lhs, rhs]| {
+                Ok(Expression::Binary(
+                    *op,
+                    Box::new(self.build_expression(lhs, scope)?),
+                    Box::new(self.build_expression(rhs, scope)?),
+                ))
+            }),
+            Node::Prefix(op, children) => match children.first() {
+                Some(operand) => self
+                    .build_expression(operand, scope)
+                    .map(|operand| Expression::Unary(*op, Box::new(operand))),
+                None => Err(unexpected(node)),
+            },
+            Node::Postfix(Op::FunctionCall, _) => self.build_window_function(node, scope),
+            _ => Err(unexpected(node)),
+        };
+
+        let expression = expression.and_then(|expression| match expression {
+            Expression::Unary(..) | Expression::Binary(..) => {
+                self.expression_type(&expression, scope).map(|_| expression)
+            }
+            expression => Ok(expression),
+        });
+
+        expression.map_err(self.locate(node))
+    }
+
+    /// Builds a call with an OVER clause. The Window operator under the
+    /// projection computes it; the expression refers to its output column.
+    fn build_window_function(
+        &self,
+        node: &Node,
+        scope: &Scope,
+    ) -> Result<Expression, AnalyzeError> {
+        let [name, arguments] = operands(node)?;
+        let name = name_of(name)?;
+
+        let function = match name.as_str() {
+            "row_number" => WindowFunctionKind::RowNumber,
+            "rank" => WindowFunctionKind::Rank,
+            "dense_rank" => WindowFunctionKind::DenseRank,
+            "lag" => WindowFunctionKind::Lag,
+            "lead" => WindowFunctionKind::Lead,
+            "first_value" => WindowFunctionKind::FirstValue,
+            "sum" => WindowFunctionKind::Sum,
+            _ => return Err(AnalyzeErrorKind::UnknownFunction(name).into()),
+        };
+
+        let over = match node.children().get(2) {
+            Some(over) => over.children(),
+            None => {
+                return Err(AnalyzeErrorKind::AggregateMisuse(format!(
+                    "Window function requires an OVER clause: {}",
+                    name
+                ))
+                .into())
+            }
+        };
+
+        // Window functions may not be nested in each other.
+        let windows = self.windows.replace(None);
+
+        if windows.is_none() {
+            return Err(AnalyzeErrorKind::AggregateMisuse(format!(
+                "Window functions are not allowed here: {}",
+                name
+            ))
+            .into());
+        }
+
+        let build = || -> Result<_, AnalyzeError> {
+            let arguments = arguments
+                .children()
+                .iter()
+                .map(|argument| self.build_expression(argument, scope))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let clause = |op: Op| {
+                over.iter()
+                    .find(|child| child.op() == Some(op))
+                    .map(Node::children)
+            };
+
+            let partition_by = clause(Op::PartitionBy)
+                .unwrap_or_default()
+                .iter()
+                .map(|expression| self.build_expression(expression, scope))
+                .collect::<Result<_, _>>()?;
+
+            let order_by = clause(Op::OrderBy)
+                .unwrap_or_default()
+                .iter()
+                .map(|key| {
+                    let [expression] = operands(key)?;
+
+                    Ok(SortKey {
+                        expression: self.build_expression(expression, scope)?,
+                        ascending: key.op() == Some(Op::Asc),
+                    })
+                })
+                .collect::<Result<_, AnalyzeError>>()?;
+
+            let frame = clause(Op::Rows).map(build_frame).transpose()?;
+
+            Ok((arguments, partition_by, order_by, frame))
+        };
+
+        let built = build();
+        self.windows.replace(windows);
+        let (arguments, partition_by, order_by, frame) = built?;
+
+        let typ = self.window_function_type(function, &arguments, scope)?;
+
+        let mut windows = self.windows.borrow_mut();
+        let windows = windows.as_mut().unwrap();
+
+        windows.push((
+            WindowFunction {
+                function,
+                arguments,
+                partition_by,
+                order_by,
+                frame,
+            },
+            typ,
+        ));
+
+        Ok(Expression::Column(Column {
+            table: Some(window_table(windows.len() - 1)),
+            name: function.name().to_string(),
+            typ,
+        }))
+    }
+
+    fn window_function_type(
+        &self,
+        function: WindowFunctionKind,
+        arguments: &[Expression],
+        scope: &Scope,
+    ) -> Result<Option<Type>, AnalyzeError> {
+        let arity = match function {
+            WindowFunctionKind::RowNumber
+            | WindowFunctionKind::Rank
+            | WindowFunctionKind::DenseRank => 0..=0,
+            WindowFunctionKind::Lag | WindowFunctionKind::Lead => 1..=3,
+            WindowFunctionKind::FirstValue | WindowFunctionKind::Sum => 1..=1,
+        };
+
+        if !arity.contains(&arguments.len()) {
+            return Err(AnalyzeErrorKind::WrongArity(format!(
+                "Wrong number of arguments: {} takes {} to {}, got {}",
+                function.name(),
+                arity.start(),
+                arity.end(),
+                arguments.len()
+            ))
+            .into());
+        }
+
+        let types: Vec<Option<Type>> = arguments
+            .iter()
+            .map(|argument| self.expression_type(argument, scope))
+            .collect::<Result<_, _>>()?;
+
+        match function {
+            WindowFunctionKind::RowNumber
+            | WindowFunctionKind::Rank
+            | WindowFunctionKind::DenseRank => Ok(Some(Type::Int)),
+            WindowFunctionKind::Lag | WindowFunctionKind::Lead => {
+                if let Some(offset) = arguments.get(1) {
+                    self.expect_type(offset, scope, Type::Int)?;
+                }
+
+                match (types[0], types.get(2).copied().flatten()) {
+                    (Some(value), Some(default)) if !is_comparable(value, default) => Err(
+                        type_mismatch(format!("{:?} and default {:?}", value, default)),
+                    ),
+                    (Some(typ), _) | (None, Some(typ)) => Ok(Some(typ)),
+                    (None, None) => Ok(None),
+                }
+            }
+            WindowFunctionKind::FirstValue => Ok(types[0]),
+            WindowFunctionKind::Sum => match types[0] {
+                Some(Type::Int | Type::Float | Type::Decimal) | None => Ok(types[0]),
+                Some(typ) => Err(type_mismatch(format!("sum of {:?}", typ))),
+            },
+        }
+    }
+
+    /// Index of a parameter in the statement's parameter list. Positional
+    /// parameters take the slot of their number; named ones are numbered in
+    /// order of first use. A statement uses one kind or the other.
+    fn parameter_index(&self, parameter: &Parameter) -> Result<usize, AnalyzeError> {
+        let mut parameters = self.parameters.borrow_mut();
+
+        let named = |info: &ParameterInfo| info.name.is_some();
+        let is_named = matches!(parameter, Parameter::Named(_));
+
+        if parameters.iter().any(|info| named(info) != is_named) {
+            return Err(AnalyzeErrorKind::Invalid(
+                "Cannot mix positional and named parameters".to_string(),
+            )
+            .into());
+        }
+
+        let index = match parameter {
+            Parameter::Positional(number) if !(1..=MAX_PARAMETER).contains(number) => {
+                return Err(AnalyzeErrorKind::Invalid(format!(
+                    "Parameter number out of range: ${}, expected 1 to {}",
+                    number, MAX_PARAMETER
+                ))
+                .into())
+            }
+            Parameter::Positional(number) => {
+                if parameters.len() < *number {
+                    parameters.resize(
+                        *number,
+                        ParameterInfo {
+                            name: None,
+                            typ: None,
+                        },
+                    );
+                }
+
+                number - 1
+            }
+            Parameter::Named(name) => {
+                match parameters
+                    .iter()
+                    .position(|info| info.name.as_ref() == Some(name))
+                {
+                    Some(index) => index,
+                    None => {
+                        parameters.push(ParameterInfo {
+                            name: Some(name.clone()),
+                            typ: None,
+                        });
+                        parameters.len() - 1
+                    }
+                }
+            }
+        };
+
+        Ok(index)
+    }
+
+    /// Gives a parameter of unknown type the type its context expects.
+    fn infer_parameter(&self, expression: &Expression, typ: Type) {
+        if let Expression::Parameter(index) = expression {
+            let parameter = &mut self.parameters.borrow_mut()[*index];
+
+            if parameter.typ.is_none() {
+                parameter.typ = Some(typ);
+            }
+        }
+    }
+
+    /// Returns the type an expression evaluates to, or `None` when it is
+    /// NULL or DEFAULT and therefore fits any column.
+    fn expression_type(
+        &self,
+        expression: &Expression,
+        scope: &Scope,
+    ) -> Result<Option<Type>, AnalyzeError> {
+        match expression {
+            Expression::Constant(literal) => literal_type(literal),
+            Expression::Column(column) => Ok(column.typ),
+            Expression::Parameter(index) => Ok(self.parameters.borrow()[*index].typ),
+            Expression::Default => Ok(None),
+            Expression::Unary(Op::Not, operand) => {
+                self.expect_type(operand, scope, Type::Bool)?;
+                Ok(Some(Type::Bool))
+            }
+            Expression::Unary(op @ (Op::Minus | Op::Plus), operand) => {
+                match self.expression_type(operand, scope)? {
+                    typ @ (Some(Type::Int | Type::Float | Type::Decimal | Type::Interval)
+                    | None) => Ok(typ),
+                    Some(typ) => Err(type_mismatch(format!("{:?} {:?}", op, typ))),
+                }
+            }
+            Expression::Unary(op, _) => Err(AnalyzeErrorKind::Unsupported(format!(
+                "Unexpected unary operator: {:?}",
+                op
+            ))
+            .into()),
+            Expression::Binary(op, lhs, rhs) => {
+                let mut lhs_type = self.expression_type(lhs, scope)?;
+                let mut rhs_type = self.expression_type(rhs, scope)?;
+
+                // A parameter compared or combined with a value of known
+                // type takes that type; one concatenated to a value is text.
+                let inferred = |typ| match op {
+                    Op::Concat => Type::String,
+                    _ => typ,
+                };
+
+                match (lhs_type, rhs_type) {
+                    (None, Some(typ)) if !matches!(op, Op::And | Op::Or) => {
+                        self.infer_parameter(lhs, inferred(typ));
+                        lhs_type = self.expression_type(lhs, scope)?;
+                    }
+                    (Some(typ), None) if !matches!(op, Op::And | Op::Or) => {
+                        self.infer_parameter(rhs, inferred(typ));
+                        rhs_type = self.expression_type(rhs, scope)?;
+                    }
+                    _ => {}
+                }
+
+                let mismatch = || type_mismatch(format!("{:?} {:?} {:?}", lhs_type, op, rhs_type));
+
+                match op {
+                    Op::And | Op::Or => {
+                        self.expect_type(lhs, scope, Type::Bool)?;
+                        self.expect_type(rhs, scope, Type::Bool)?;
+                        Ok(Some(Type::Bool))
+                    }
+                    Op::Plus | Op::Minus | Op::Multiply | Op::Divide | Op::Modulo => {
+                        match (lhs_type, rhs_type) {
+                            (Some(Type::Int), Some(Type::Int)) => Ok(Some(Type::Int)),
+                            (Some(Type::Int | Type::Decimal), Some(Type::Int | Type::Decimal)) => {
+                                Ok(Some(Type::Decimal))
+                            }
+                            (
+                                Some(Type::Int | Type::Decimal | Type::Float),
+                                Some(Type::Int | Type::Decimal | Type::Float),
+                            ) => Ok(Some(Type::Float)),
+                            (Some(Type::Int | Type::Decimal | Type::Float) | None, None)
+                            | (None, Some(Type::Int | Type::Decimal | Type::Float)) => Ok(None),
+                            (lhs_type, rhs_type) => {
+                                match lhs_type
+                                    .zip(rhs_type)
+                                    .and_then(|(lhs, rhs)| temporal_arithmetic_type(*op, lhs, rhs))
+                                {
+                                    Some(typ) => Ok(Some(typ)),
+                                    None => Err(mismatch()),
+                                }
+                            }
+                        }
+                    }
+                    Op::Equals
+                    | Op::NotEquals
+                    | Op::LessThan
+                    | Op::GreaterThan
+                    | Op::LessThanOrEquals
+                    | Op::GreaterThanOrEquals => {
+                        if let (Some(lhs_type), Some(rhs_type)) = (lhs_type, rhs_type) {
+                            if !is_comparable(lhs_type, rhs_type) {
+                                return Err(mismatch());
+                            }
+                        }
+                        Ok(Some(Type::Bool))
+                    }
+                    // Either side may be of any type as long as the other
+                    // one is text.
+                    Op::Concat => match (lhs_type, rhs_type) {
+                        (Some(lhs_type), Some(rhs_type))
+                            if lhs_type != Type::String && rhs_type != Type::String =>
+                        {
+                            Err(mismatch())
+                        }
+                        _ => Ok(Some(Type::String)),
+                    },
+                    _ => Err(AnalyzeErrorKind::Unsupported(format!(
+                        "Unexpected binary operator: {:?}",
+                        op
+                    ))
+                    .into()),
+                }
+            }
+        }
+    }
+
+    fn expect_type(
+        &self,
+        expression: &Expression,
+        scope: &Scope,
+        expected: Type,
+    ) -> Result<(), AnalyzeError> {
+        self.infer_parameter(expression, expected);
+
+        match self.expression_type(expression, scope)? {
+            Some(typ) if typ != expected => Err(type_mismatch(format!(
+                "expected {:?}, got {:?}",
+                expected, typ
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn table_definition(&self, name: &str) -> Result<&'a TableDefinition, AnalyzeError> {
+        self.catalog
+            .table(name)
+            .ok_or_else(|| AnalyzeErrorKind::UnknownTable(name.to_string()).into())
+    }
+
+    /// Columns of a relation read in FROM: a CTE in scope, or a table.
+    fn relation_scope(&self, name: &str) -> Result<Scope, AnalyzeError> {
+        let ctes = self.ctes.borrow();
+
+        match ctes.iter().rev().find(|(cte, _)| cte == name) {
+            Some((_, scope)) => Ok(scope.clone()),
+            None => self.table_definition(name).map(Scope::of_table),
+        }
+    }
+
+    /// The expressions of a select or RETURNING list, with `*` expanded.
+    fn build_columns(
+        &self,
+        columns_node: &Node,
+        scope: &Scope,
+    ) -> Result<Vec<Expression>, AnalyzeError> {
+        let mut column_walker = ColumnWalker::new();
+
+        let mut expressions = vec![];
+
+        for column in column_walker.walk(columns_node) {
+            match column {
+                Node::Leaf(Literal::Wildcard) => {
+                    expressions.extend(scope.columns.iter().map(|column| {
+                        Expression::Column(Column {
+                            table: column.table.clone(),
+                            name: column.name.clone(),
+                            typ: column.typ,
+                        })
+                    }));
+                }
+                column => expressions.push(self.build_expression(column, scope)?),
+            }
+        }
+
+        Ok(expressions)
+    }
+
+    /// The names of the relations in a FROM list.
+    fn build_tables<'b>(&self, tables_node: &'b Node) -> Result<Vec<&'b Node>, AnalyzeError> {
+        let mut table_walker = TableWalker::new();
+
+        table_walker.walk(tables_node)
+    }
+}
+
+/// Columns visible to expressions at some point of a query, together with
+/// their types when those are known.
+#[derive(Debug, PartialEq, Clone, Default)]
+struct Scope {
+    columns: Vec<ScopeColumn>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct ScopeColumn {
+    table: Option<String>,
+    name: String,
+    typ: Option<Type>,
+}
+
+impl Scope {
+    fn of_table(table: &TableDefinition) -> Scope {
+        Scope {
+            columns: table
+                .columns
+                .iter()
+                .map(|column| ScopeColumn {
+                    table: Some(table.name.clone()),
+                    name: column.name.clone(),
+                    typ: Some(column.typ),
+                })
+                .collect(),
+        }
+    }
+
+    /// The same columns, visible under another table name.
+    fn qualified(self, table: &str) -> Scope {
+        Scope {
+            columns: self
+                .columns
+                .into_iter()
+                .map(|column| ScopeColumn {
+                    table: Some(table.to_string()),
+                    ..column
+                })
+                .collect(),
+        }
+    }
+
+    fn resolve(&self, column: &Column) -> Result<&ScopeColumn, AnalyzeError> {
+        let mut candidates = self.columns.iter().filter(|candidate| {
+            candidate.name == column.name
+                && (column.table.is_none() || candidate.table == column.table)
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(candidate), None) => Ok(candidate),
+            (Some(_), Some(_)) => Err(AnalyzeErrorKind::AmbiguousColumn(column.to_string()).into()),
+            (None, _) => Err(AnalyzeErrorKind::UnknownColumn(column.to_string()).into()),
+        }
+    }
+}
+
+struct ColumnWalker<'a> {
+    columns: Vec<&'a Node>,
+}
+
+impl<'a> ColumnWalker<'a> {
+    fn new() -> Self {
+        ColumnWalker { columns: vec![] }
+    }
+
+    fn walk(&mut self, column_node: &'a Node) -> Vec<&'a Node> {
+        match column_node.op() {
+            Some(Op::Comma) => {
+                for child in column_node.children() {
+                    self.walk(child);
+                }
+
+                self.columns.clone()
+            }
+            _ => {
+                self.columns.push(column_node);
+
+                self.columns.clone()
+            }
+        }
+    }
+}
+
+struct TableWalker<'a> {
+    tables: Vec<&'a Node>,
+}
+
+impl<'a> TableWalker<'a> {
+    fn new() -> Self {
+        TableWalker { tables: vec![] }
+    }
+
+    fn walk(&mut self, table_node: &'a Node) -> Result<Vec<&'a Node>, AnalyzeError> {
+        match table_node {
+            Node::Infix(Op::Comma, children) => {
+                for child in children {
+                    self.walk(child)?;
+                }
+
+                Ok(self.tables.clone())
+            }
+            Node::Leaf(Literal::Identifier {
+                second_name: None,
+                third_name: None,
+                ..
+            }) => {
+                self.tables.push(table_node);
+
+                Ok(self.tables.clone())
+            }
+            _ => Err(AnalyzeErrorKind::Unsupported(format!(
+                "Unsupported relation in FROM: {}",
+                table_node.to_sql()
+            ))
+            .into()),
+        }
+    }
+}
+
+/// Name of the pseudo-table holding the result of the window function with
+/// the given index in a select list. It cannot clash with a real table.
+pub fn window_table(index: usize) -> String {
+    format!("#window{}", index)
+}
+
+fn build_frame(bounds: &[Node]) -> Result<Frame, AnalyzeError> {
+    let bound = |node: &Node| {
+        let offset = match node.children().first() {
+            Some(offset) => match offset.literal() {
+                Some(Literal::Numeric(offset)) if offset >= 0 => Some(offset as usize),
+                _ => {
+                    return Err(AnalyzeErrorKind::Invalid(format!(
+                        "Frame offset must be a non-negative integer: {}",
+                        offset.to_sql()
+                    ))
+                    .into())
+                }
+            },
+            None => None,
+        };
+
+        match (node.op(), offset) {
+            (Some(Op::Preceding), None) => Ok(FrameBound::UnboundedPreceding),
+            (Some(Op::Preceding), Some(offset)) => Ok(FrameBound::Preceding(offset)),
+            (Some(Op::CurrentRow), _) => Ok(FrameBound::CurrentRow),
+            (Some(Op::Following), Some(offset)) => Ok(FrameBound::Following(offset)),
+            (Some(Op::Following), None) => Ok(FrameBound::UnboundedFollowing),
+            _ => Err(unexpected(node)),
+        }
+    };
+
+    let frame = match bounds {
+        [start, end] => Frame {
+            start: bound(start)?,
+            end: bound(end)?,
+        },
+        _ => return Err(wrong_number("frame bounds", 2, bounds.len())),
+    };
+
+    let rank = |bound: FrameBound| match bound {
+        FrameBound::UnboundedPreceding => 0,
+        FrameBound::Preceding(_) => 1,
+        FrameBound::CurrentRow => 2,
+        FrameBound::Following(_) => 3,
+        FrameBound::UnboundedFollowing => 4,
+    };
+
+    if frame.start == FrameBound::UnboundedFollowing
+        || frame.end == FrameBound::UnboundedPreceding
+        || rank(frame.start) > rank(frame.end)
+    {
+        return Err(AnalyzeErrorKind::Invalid(format!(
+            "Invalid window frame: {:?} to {:?}",
+            frame.start, frame.end
+        ))
+        .into());
+    }
+
+    Ok(frame)
+}
+
+/// Output columns of a UNION: names from the left side, types from whichever
+/// side knows them.
+fn union_scope(lhs: Scope, rhs: &Scope) -> Result<Scope, AnalyzeError> {
+    if lhs.columns.len() != rhs.columns.len() {
+        return Err(wrong_number(
+            "columns",
+            lhs.columns.len(),
+            rhs.columns.len(),
+        ));
+    }
+
+    Ok(Scope {
+        columns: lhs
+            .columns
+            .into_iter()
+            .zip(&rhs.columns)
+            .map(|(lhs, rhs)| {
+                let typ = match (lhs.typ, rhs.typ) {
+                    (Some(lhs_type), Some(rhs_type)) if !is_comparable(lhs_type, rhs_type) => {
+                        return Err(type_mismatch(format!(
+                            "{:?} UNION {:?}",
+                            lhs_type, rhs_type
+                        )))
+                    }
+                    (Some(typ), _) | (None, Some(typ)) => Some(typ),
+                    (None, None) => None,
+                };
+
+                Ok(ScopeColumn {
+                    table: None,
+                    typ,
+                    ..lhs
+                })
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Applies the column names of a CTE column list, if there is one.
+fn rename_scope(scope: Scope, names: &[String]) -> Result<Scope, AnalyzeError> {
+    if names.is_empty() {
+        return Ok(scope);
+    }
+
+    if names.len() != scope.columns.len() {
+        return Err(wrong_number("columns", names.len(), scope.columns.len()));
+    }
+
+    Ok(Scope {
+        columns: scope
+            .columns
+            .into_iter()
+            .zip(names)
+            .map(|(column, name)| ScopeColumn {
+                name: name.clone(),
+                ..column
+            })
+            .collect(),
+    })
+}
+
+/// Names of the relations a plan reads, without looking into the views it
+/// reads.
+fn collect_dependencies(node: &LogicalNode, dependencies: &mut Vec<String>) {
+    match &node.operator {
+        Operator::Read(info) => dependencies.push(info.table.name.clone()),
+        Operator::Subquery(info) => dependencies.push(info.name.clone()),
+        _ => {
+            for child in &node.children {
+                collect_dependencies(child, dependencies);
+            }
+        }
+    }
+}
+
+/// Names of the CTEs defined anywhere in a plan, outside of the views it
+/// reads.
+fn cte_names(node: &LogicalNode) -> Vec<String> {
+    let mut names = match &node.operator {
+        Operator::With(info) => info.ctes.iter().map(|cte| cte.name.clone()).collect(),
+        Operator::Subquery(_) => return vec![],
+        _ => vec![],
+    };
+
+    for child in &node.children {
+        names.extend(cte_names(child));
+    }
+
+    names
+}
+
+/// Whether a plan reads the relation with the given name.
+fn reads(node: &LogicalNode, name: &str) -> bool {
+    match &node.operator {
+        Operator::Read(info) if info.table.name == name => true,
+        _ => node.children.iter().any(|child| reads(child, name)),
+    }
+}
+
+fn literal_type(literal: &Literal) -> Result<Option<Type>, AnalyzeError> {
+    match literal {
+        Literal::Numeric(_) => Ok(Some(Type::Int)),
+        Literal::Float(_) => Ok(Some(Type::Float)),
+        Literal::Decimal(_) => Ok(Some(Type::Decimal)),
+        Literal::String(_) => Ok(Some(Type::String)),
+        Literal::Bool(_) => Ok(Some(Type::Bool)),
+        Literal::Date(_) => Ok(Some(Type::Date)),
+        Literal::Time(_) => Ok(Some(Type::Time)),
+        Literal::Timestamp(_) => Ok(Some(Type::Timestamp)),
+        Literal::Interval(_) => Ok(Some(Type::Interval)),
+        Literal::Null | Literal::Default => Ok(None),
+        Literal::Identifier { .. } | Literal::Wildcard | Literal::Parameter(_) => {
+            Err(AnalyzeErrorKind::Unsupported(format!("Unexpected literal: {:?}", literal)).into())
+        }
+    }
+}
+
+fn contains_parameter(expression: &Expression) -> bool {
+    match expression {
+        Expression::Parameter(_) => true,
+        Expression::Unary(_, operand) => contains_parameter(operand),
+        Expression::Binary(_, lhs, rhs) => contains_parameter(lhs) || contains_parameter(rhs),
+        _ => false,
+    }
+}
+
+fn check_assignable(typ: Type, column: &ColumnDefinition) -> Result<(), AnalyzeError> {
+    if !is_assignable(typ, column.typ) {
+        return Err(type_mismatch(format!(
+            "cannot assign {:?} to column {} of type {:?}",
+            typ, column.name, column.typ
+        )));
+    }
+
+    Ok(())
+}
+
+fn table_column<'t>(
+    table: &'t TableDefinition,
+    name: &str,
+) -> Result<&'t ColumnDefinition, AnalyzeError> {
+    table
+        .column(name)
+        .ok_or_else(|| AnalyzeErrorKind::UnknownColumn(format!("{}.{}", table.name, name)).into())
+}
+
+fn type_mismatch(message: String) -> AnalyzeError {
+    AnalyzeErrorKind::TypeMismatch(message).into()
+}
+
+fn wrong_number(of: &str, expected: usize, actual: usize) -> AnalyzeError {
+    AnalyzeErrorKind::WrongArity(format!(
+        "Wrong number of {}: expected {}, got {}",
+        of, expected, actual
+    ))
+    .into()
+}
+
+fn duplicate_column(name: &str) -> AnalyzeErrorKind {
+    AnalyzeErrorKind::Invalid(format!("Column specified more than once: {}", name))
+}
+
+fn dependent_objects(name: &str, dependents: &str) -> AnalyzeError {
+    AnalyzeErrorKind::Invalid(format!(
+        "Cannot drop {} because other objects depend on it: {}",
+        name, dependents
+    ))
+    .into()
+}
+
+/// A node the analyzer does not expect where it is, which the parser does
+/// not produce.
+fn unexpected(node: &Node) -> AnalyzeError {
+    AnalyzeErrorKind::Unsupported(format!("Unexpected node: {:?}", node)).into()
+}
+
+/// The first `N` children of a node. The parser always writes them, but a
+/// tree built by hand, deserialized or rewritten may have fewer.
+fn operands<const N: usize>(node: &Node) -> Result<&[Node; N], AnalyzeError> {
+    let children = node.children();
+
+    children
+        .get(..N)
+        .and_then(|operands| operands.try_into().ok())
+        .ok_or_else(|| {
+            AnalyzeErrorKind::WrongArity(format!(
+                "Wrong number of operands: expected at least {}, got {}",
+                N,
+                children.len()
+            ))
+            .into()
+        })
+}
+
+/// The name of a table, column, view or index, which the parser writes as a
+/// leaf of one part.
+fn name_of(node: &Node) -> Result<String, AnalyzeError> {
+    match node {
+        Node::Leaf(Literal::Identifier {
+            first_name,
+            second_name: None,
+            third_name: None,
+        }) => Ok(first_name.clone()),
+        _ => Err(unexpected(node)),
+    }
+}
+
+pub fn is_assignable(from: Type, to: Type) -> bool {
+    from == to
+        || matches!(
+            (from, to),
+            (Type::Int, Type::Float | Type::Decimal)
+                | (Type::Decimal, Type::Float)
+                | (Type::Date, Type::Timestamp)
+        )
+}
+
+/// The type of `lhs op rhs` for dates, times and intervals: a date moves by
+/// whole days or by an interval, and the difference of two dates is a number
+/// of days.
+fn temporal_arithmetic_type(op: Op, lhs: Type, rhs: Type) -> Option<Type> {
+    match (lhs, op, rhs) {
+        (Type::Date, Op::Plus | Op::Minus, Type::Int) | (Type::Int, Op::Plus, Type::Date) => {
+            Some(Type::Date)
+        }
+        (Type::Date, Op::Minus, Type::Date) => Some(Type::Int),
+        (Type::Date | Type::Timestamp, Op::Plus | Op::Minus, Type::Interval)
+        | (Type::Interval, Op::Plus, Type::Date | Type::Timestamp) => Some(Type::Timestamp),
+        (Type::Time, Op::Plus | Op::Minus, Type::Interval)
+        | (Type::Interval, Op::Plus, Type::Time) => Some(Type::Time),
+        (Type::Timestamp, Op::Minus, Type::Timestamp)
+        | (Type::Time, Op::Minus, Type::Time)
+        | (Type::Interval, Op::Plus | Op::Minus, Type::Interval) => Some(Type::Interval),
+        _ => None,
+    }
+}
+
+fn is_comparable(lhs: Type, rhs: Type) -> bool {
+    is_assignable(lhs, rhs) || is_assignable(rhs, lhs)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Table {
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Operator {
+    Projection(ProjectionInfo),
+    Filter(FilterInfo),
+    Read(ReadInfo),
+    Join(JoinInfo),
+    Group(GroupInfo),
+    Sort(SortInfo),
+    Limit(LimitInfo),
+    Distinct(DistinctInfo),
+    Insert(InsertInfo),
+    Values(ValuesInfo),
+    Update(UpdateInfo),
+    Delete(DeleteInfo),
+    CreateTable(CreateTableInfo),
+    DropTable(DropTableInfo),
+    Merge(MergeInfo),
+    Union(UnionInfo),
+    With(WithInfo),
+    Window(WindowInfo),
+    Subquery(SubqueryInfo),
+    CreateView(CreateViewInfo),
+    DropView(DropViewInfo),
+    CreateIndex(CreateIndexInfo),
+    DropIndex(DropIndexInfo),
+    Transaction(TransactionStatement),
+    Explain(ExplainInfo),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProjectionInfo {
+    pub expressions: Vec<Expression>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum LogicalOperation {
+    AndOp(),
+    LessThan(),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FilterInfo {
+    pub predicate: Expression,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadInfo {
+    pub table: Table,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InsertInfo {
+    pub table: Table,
+    pub columns: Vec<Column>,
+    pub on_conflict: Option<OnConflict>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OnConflict {
+    pub columns: Vec<Column>,
+    pub action: ConflictAction,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ConflictAction {
+    Nothing,
+    Update {
+        assignments: Vec<Assignment>,
+        predicate: Option<Expression>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValuesInfo {
+    pub rows: Vec<Vec<Expression>>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdateInfo {
+    pub table: Table,
+    pub assignments: Vec<Assignment>,
+    pub predicate: Option<Expression>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Assignment {
+    pub column: Column,
+    pub expression: Expression,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeleteInfo {
+    pub table: Table,
+    pub predicate: Option<Expression>,
+}
+
+/// Joins the target table with the single input on `condition` and applies,
+/// for every source row, the first clause whose predicate holds.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MergeInfo {
+    pub table: Table,
+    pub condition: Expression,
+    pub clauses: Vec<MergeClause>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MergeClause {
+    pub matched: bool,
+    pub predicate: Option<Expression>,
+    pub action: MergeAction,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum MergeAction {
+    Update(Vec<Assignment>),
+    Delete,
+    Insert {
+        columns: Vec<Column>,
+        values: Vec<Expression>,
+    },
+    Nothing,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnionInfo {
+    pub all: bool,
+}
+
+/// Evaluates the CTEs, one per child in order, and then the last child,
+/// which may read them by name.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithInfo {
+    pub ctes: Vec<Cte>,
+}
+
+/// A named relation defined by a WITH. The plan of a recursive CTE is a
+/// UNION whose right side is evaluated repeatedly until it adds no rows.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cte {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub recursive: bool,
+}
+
+/// Appends one column per function to every input row, named after the
+/// function and qualified with `window_table(index)`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowInfo {
+    pub functions: Vec<WindowFunction>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowFunction {
+    pub function: WindowFunctionKind,
+    pub arguments: Vec<Expression>,
+    pub partition_by: Vec<Expression>,
+    pub order_by: Vec<SortKey>,
+    /// `None` is the whole partition without ORDER BY, and the rows up to
+    /// the last peer of the current row with it.
+    pub frame: Option<Frame>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum WindowFunctionKind {
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag,
+    Lead,
+    FirstValue,
+    Sum,
+}
+
+impl WindowFunctionKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WindowFunctionKind::RowNumber => "row_number",
+            WindowFunctionKind::Rank => "rank",
+            WindowFunctionKind::DenseRank => "dense_rank",
+            WindowFunctionKind::Lag => "lag",
+            WindowFunctionKind::Lead => "lead",
+            WindowFunctionKind::FirstValue => "first_value",
+            WindowFunctionKind::Sum => "sum",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SortKey {
+    pub expression: Expression,
+    pub ascending: bool,
+}
+
+/// A ROWS frame, relative to the current row's position in its partition.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+    pub start: FrameBound,
+    pub end: FrameBound,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(usize),
+    CurrentRow,
+    Following(usize),
+    UnboundedFollowing,
+}
+
+/// Creates a table, filling it with the rows of the child if there is one.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateTableInfo {
+    pub table: TableDefinition,
+    /// Do nothing if a relation with the same name exists.
+    pub if_not_exists: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropTableInfo {
+    pub tables: Vec<Table>,
+    /// Views dropped along with the tables by CASCADE.
+    pub views: Vec<String>,
+    /// Tables whose foreign keys to the dropped tables are removed by
+    /// CASCADE.
+    pub foreign_keys: Vec<String>,
+}
+
+/// The rows of the child, seen as a relation with the given name and
+/// columns.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubqueryInfo {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateViewInfo {
+    pub view: ViewDefinition,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropViewInfo {
+    pub name: String,
+    /// Views dropped along with the view by CASCADE.
+    pub dependents: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateIndexInfo {
+    pub table: Table,
+    pub index: IndexDefinition,
+    /// Do nothing if an index with the same name exists.
+    pub if_not_exists: bool,
+}
+
+/// Drops the index if there is one with the name.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropIndexInfo {
+    pub name: String,
+}
+
+/// Describes the plan of the child. With `analyze`, the child is executed
+/// and every operator is annotated with what it did.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExplainInfo {
+    pub analyze: bool,
+}
+
+/// Transaction control, carried out by the session rather than the executor.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TransactionStatement {
+    Begin(IsolationLevel),
+    Commit,
+    Rollback,
+    Savepoint(String),
+    RollbackTo(String),
+    Release(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JoinInfo {}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupInfo {}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SortInfo {}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LimitInfo {}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistinctInfo {}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogicalNode {
+    pub operator: Operator,
+    pub children: Vec<LogicalNode>,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogicalPlan {
+    pub root: LogicalNode,
+}
+
+/// Name of the pseudo-table holding the row proposed for insertion in
+/// `ON CONFLICT DO UPDATE`.
+pub const EXCLUDED: &str = "excluded";
+
+/// The highest number of a positional parameter. Parameters are kept by
+/// number, so a higher one would make the statement take that many slots.
+const MAX_PARAMETER: usize = 65535;
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Column {
+    pub table: Option<String>,
+    pub name: String,
+    /// Unknown for columns of untyped values, such as a bare NULL, and for
+    /// the columns the executor makes up.
+    pub typ: Option<Type>,
+}
+
+impl Column {
+    pub fn new(name: &str) -> Self {
+        Column {
+            table: None,
+            name: name.to_string(),
+            typ: None,
+        }
+    }
+
+    /// A column of a table, as the target of a write.
+    pub fn of(column: &ColumnDefinition) -> Self {
+        Column {
+            table: None,
+            name: column.name.clone(),
+            typ: Some(column.typ),
+        }
+    }
+}
+
+impl std::fmt::Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.table {
+            Some(table) => write!(f, "{}.{}", table, self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Expression {
+    Constant(Literal),
+    Column(Column),
+    Unary(Op, Box<Expression>),
+    Binary(Op, Box<Expression>, Box<Expression>),
+    Default,
+    /// Index into the statement's parameters, replaced by a constant when
+    /// the statement is bound.
+    Parameter(usize),
+}
+
+impl Expression {
+    /// Name under which the expression shows up in a result set.
+    pub fn output_name(&self) -> String {
+        match self {
+            Expression::Column(column) => column.name.clone(),
+            _ => "?column?".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyzer::{
+        AnalyzeError, AnalyzeErrorKind, Analyzer, Assignment, Column, ConflictAction,
+        CreateIndexInfo, CreateTableInfo, CreateViewInfo, Cte, DeleteInfo, DropTableInfo,
+        Expression, FilterInfo, Frame, FrameBound, InsertInfo, LogicalNode, LogicalPlan,
+        MergeAction, MergeClause, MergeInfo, OnConflict, Operator, ProjectionInfo, ReadInfo,
+        SortKey, SubqueryInfo, Table, UnionInfo, UpdateInfo, ValuesInfo, WindowFunction,
+        WindowFunctionKind, WindowInfo, WithInfo,
+    };
+    use crate::catalog::{
+        Catalog, ColumnDefinition, Constraint, IndexDefinition, TableDefinition, ViewDefinition,
+    };
+    use crate::parser::lexer::Lexer;
+    use crate::parser::{Decimal, Literal, Node, Op, Parser, Span, Type};
+
+    /// A column of the tables in `catalog()`, with its type.
+    fn column(name: &str) -> Column {
+        let typ = match name {
+            "col1" | "col5" => Some(Type::Int),
+            "col2" | "col6" => Some(Type::String),
+            "col3" => Some(Type::Float),
+            "col4" => Some(Type::Bool),
+            _ => None,
+        };
+
+        Column {
+            typ,
+            ..Column::new(name)
+        }
+    }
+
+    fn qualified(table: &str, name: &str) -> Column {
+        Column {
+            table: Some(table.to_string()),
+            ..column(name)
+        }
+    }
+
+    fn table(name: &str) -> Table {
+        Table {
+            name: name.to_string(),
+        }
+    }
+
+    fn projection(columns: Vec<Column>) -> Operator {
+        Operator::Projection(ProjectionInfo {
+            expressions: columns.into_iter().map(Expression::Column).collect(),
+        })
+    }
+
+    fn read(table: Table) -> Operator {
+        Operator::Read(ReadInfo { table })
+    }
+
+    fn constant(literal: Literal) -> Expression {
+        Expression::Constant(literal)
+    }
+
+    fn catalog() -> Catalog {
+        let mut catalog = Catalog::new();
+
+        catalog.create_table(TableDefinition {
+            name: "table1".to_string(),
+            columns: vec![
+                ColumnDefinition::new("col1", Type::Int),
+                ColumnDefinition::new("col2", Type::String),
+                ColumnDefinition::new("col3", Type::Float),
+                ColumnDefinition::new("col4", Type::Bool),
+            ],
+            constraints: vec![
+                Constraint::PrimaryKey(vec!["col1".to_string()]),
+                Constraint::Unique(vec!["col2".to_string(), "col3".to_string()]),
+            ],
+            indexes: vec![],
+        });
+
+        catalog.create_table(TableDefinition {
+            name: "table2".to_string(),
+            columns: vec![
+                ColumnDefinition::new("col5", Type::Int),
+                ColumnDefinition::new("col6", Type::String),
+            ],
+            constraints: vec![],
+            indexes: vec![],
+        });
+
+        catalog.create_view(ViewDefinition {
+            name: "view1".to_string(),
+            columns: vec!["a".to_string()],
+            query: "SELECT col5 FROM table2".to_string(),
+            dependencies: vec!["table2".to_string()],
+        });
+
+        catalog
+    }
+
+    fn analyze(input: &str) -> LogicalPlan {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let catalog = catalog();
+        let analyzer = Analyzer::new(&catalog);
+
+        analyzer.analyze(&parser.parse().unwrap()).unwrap()
+    }
+
+    /// The error analyzing the statement fails with, located with the
+    /// statement's spans.
+    fn analyze_error(input: &str) -> AnalyzeError {
+        let mut parser = Parser::new(Lexer::new(input));
+        let ast = parser.parse().unwrap();
+        let spans = parser.into_spans();
+        let catalog = catalog();
+
+        Analyzer::with_spans(&catalog, &ast, &spans)
+            .analyze(&ast)
+            .unwrap_err()
+    }
+
+    #[test]
+    fn simple_test() {
+        let logical_plan = analyze("SELECT col1 FROM table1");
+
+        assert_eq!(
+            logical_plan,
+            LogicalPlan {
+                root: LogicalNode {
+                    operator: projection(vec![column("col1")]),
+                    children: vec![LogicalNode {
+                        operator: read(table("table1")),
+                        children: vec![],
+                    }]
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn select_many_columns() {
+        let logical_plan = analyze("SELECT col1, col2, col3 FROM table1");
+
+        assert_eq!(
+            logical_plan,
+            LogicalPlan {
+                root: LogicalNode {
+                    operator: projection(vec![column("col1"), column("col2"), column("col3")]),
+                    children: vec![LogicalNode {
+                        operator: read(table("table1")),
+                        children: vec![],
+                    }]
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn select_many_tables() {
+        let logical_plan = analyze("SELECT col1 FROM table1, table2");
+
+        assert_eq!(
+            logical_plan,
+            LogicalPlan {
+                root: LogicalNode {
+                    operator: projection(vec![column("col1")]),
+                    children: vec![
+                        LogicalNode {
+                            operator: read(table("table1")),
+                            children: vec![],
+                        },
+                        LogicalNode {
+                            operator: read(table("table2")),
+                            children: vec![],
+                        }
+                    ]
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn insert_many_rows() {
+        let logical_plan = analyze(
+            "INSERT INTO table1 (col2, col3) VALUES ('x', 1.5), (NULL, DEFAULT), ('y', -3)",
+        );
+
+        assert_eq!(
+            logical_plan,
+            LogicalPlan {
+                root: LogicalNode {
+                    operator: Operator::Insert(InsertInfo {
+                        table: table("table1"),
+                        columns: vec![column("col2"), column("col3")],
+                        on_conflict: None,
+                    }),
+                    children: vec![LogicalNode {
+                        operator: Operator::Values(ValuesInfo {
+                            rows: vec![
+                                vec![
+                                    constant(Literal::String("x".to_string())),
+                                    constant(Literal::Decimal(Decimal {
+                                        mantissa: 15,
+                                        scale: 1
+                                    })),
+                                ],
+                                vec![constant(Literal::Null), Expression::Default],
+                                vec![
+                                    constant(Literal::String("y".to_string())),
+                                    constant(Literal::Numeric(-3)),
+                                ],
+                            ],
+                        }),
+                        children: vec![],
+                    }],
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn insert_without_column_list_uses_all_columns() {
+        let logical_plan = analyze("INSERT INTO table1 VALUES (1 + 2, 'a', 2, true)");
+
+        assert_eq!(
+            logical_plan.root.operator,
+            Operator::Insert(InsertInfo {
+                table: table("table1"),
+                columns: vec![
+                    column("col1"),
+                    column("col2"),
+                    column("col3"),
+                    column("col4")
+                ],
+                on_conflict: None,
+            })
+        );
+        assert_eq!(
+            logical_plan.root.children[0].operator,
+            Operator::Values(ValuesInfo {
+                rows: vec![vec![
+                    Expression::Binary(
+                        Op::Plus,
+                        Box::new(constant(Literal::Numeric(1))),
+                        Box::new(constant(Literal::Numeric(2))),
+                    ),
+                    constant(Literal::String("a".to_string())),
+                    constant(Literal::Numeric(2)),
+                    constant(Literal::Bool(true)),
+                ]],
+            })
+        );
+    }
+
+    #[test]
+    fn insert_wrong_arity() {
+        assert_eq!(
+            analyze_error("INSERT INTO table1 (col1, col2) VALUES (1)").kind,
+            AnalyzeErrorKind::WrongArity("Wrong number of values: expected 2, got 1".to_string())
+        );
+    }
+
+    #[test]
+    fn insert_wrong_type() {
+        assert_eq!(
+            analyze_error("INSERT INTO table1 (col1) VALUES ('x')").kind,
+            AnalyzeErrorKind::TypeMismatch(
+                "cannot assign String to column col1 of type Int".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn insert_unknown_column() {
+        assert_eq!(
+            analyze_error("INSERT INTO table1 (col9) VALUES (1)").kind,
+            AnalyzeErrorKind::UnknownColumn("table1.col9".to_string())
+        );
+    }
+
+    #[test]
+    fn insert_unknown_table() {
+        assert_eq!(
+            analyze_error("INSERT INTO table9 VALUES (1)").kind,
+            AnalyzeErrorKind::UnknownTable("table9".to_string())
+        );
+    }
+
+    #[test]
+    fn select_with_where() {
+        let logical_plan = analyze("SELECT col1 FROM table1 WHERE col1 = 1");
+
+        assert_eq!(
+            logical_plan.root.children,
+            vec![LogicalNode {
+                operator: Operator::Filter(FilterInfo {
+                    predicate: Expression::Binary(
+                        Op::Equals,
+                        Box::new(Expression::Column(column("col1"))),
+                        Box::new(constant(Literal::Numeric(1))),
+                    ),
+                }),
+                children: vec![LogicalNode {
+                    operator: read(table("table1")),
+                    children: vec![],
+                }],
+            }]
+        )
+    }
+
+    #[test]
+    fn select_wildcard_expands_to_all_columns() {
+        let logical_plan = analyze("SELECT * FROM table2");
+
+        assert_eq!(
+            logical_plan.root.operator,
+            projection(vec![
+                qualified("table2", "col5"),
+                qualified("table2", "col6")
+            ])
+        )
+    }
+
+    #[test]
+    fn insert_select() {
+        let logical_plan = analyze("INSERT INTO table1 (col1, col2) SELECT col5, col6 FROM table2");
+
+        assert_eq!(
+            logical_plan,
+            LogicalPlan {
+                root: LogicalNode {
+                    operator: Operator::Insert(InsertInfo {
+                        table: table("table1"),
+                        columns: vec![column("col1"), column("col2")],
+                        on_conflict: None,
+                    }),
+                    children: vec![LogicalNode {
+                        operator: projection(vec![column("col5"), column("col6")]),
+                        children: vec![LogicalNode {
+                            operator: read(table("table2")),
+                            children: vec![],
+                        }],
+                    }],
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn insert_select_widens_int_to_float() {
+        let logical_plan = analyze("INSERT INTO table1 (col3) SELECT col5 + 1 FROM table2");
+
+        assert_eq!(
+            logical_plan.root,
+            LogicalNode {
+                operator: Operator::Insert(InsertInfo {
+                    table: table("table1"),
+                    columns: vec![Column {
+                        typ: Some(Type::Float),
+                        ..Column::new("col3")
+                    }],
+                    on_conflict: None,
+                }),
+                children: vec![LogicalNode {
+                    operator: Operator::Projection(ProjectionInfo {
+                        expressions: vec![Expression::Binary(
+                            Op::Plus,
+                            Box::new(Expression::Column(Column {
+                                typ: Some(Type::Int),
+                                ..Column::new("col5")
+                            })),
+                            Box::new(constant(Literal::Numeric(1))),
+                        )],
+                    }),
+                    children: vec![LogicalNode {
+                        operator: read(table("table2")),
+                        children: vec![],
+                    }],
+                }],
+            }
+        )
+    }
+
+    #[test]
+    fn insert_select_wrong_arity() {
+        assert_eq!(
+            analyze_error("INSERT INTO table1 (col1) SELECT col5, col6 FROM table2").kind,
+            AnalyzeErrorKind::WrongArity("Wrong number of columns: expected 1, got 2".to_string())
+        );
+    }
+
+    #[test]
+    fn insert_select_wrong_type() {
+        assert_eq!(
+            analyze_error("INSERT INTO table1 (col1, col2) SELECT col6, col5 FROM table2").kind,
+            AnalyzeErrorKind::TypeMismatch(
+                "cannot assign String to column col1 of type Int".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn update_with_where() {
+        let logical_plan = analyze("UPDATE table1 SET col1 = col1 + 1 WHERE col4 = true");
+
+        assert_eq!(
+            logical_plan.root,
+            LogicalNode {
+                operator: Operator::Update(UpdateInfo {
+                    table: table("table1"),
+                    assignments: vec![Assignment {
+                        column: column("col1"),
+                        expression: Expression::Binary(
+                            Op::Plus,
+                            Box::new(Expression::Column(column("col1"))),
+                            Box::new(constant(Literal::Numeric(1))),
+                        ),
+                    }],
+                    predicate: Some(Expression::Binary(
+                        Op::Equals,
+                        Box::new(Expression::Column(column("col4"))),
+                        Box::new(constant(Literal::Bool(true))),
+                    )),
+                }),
+                children: vec![],
+            }
+        )
+    }
+
+    #[test]
+    fn update_wrong_type() {
+        assert_eq!(
+            analyze_error("UPDATE table1 SET col4 = 1").kind,
+            AnalyzeErrorKind::TypeMismatch(
+                "cannot assign Int to column col4 of type Bool".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn delete_returning() {
+        let logical_plan = analyze("DELETE FROM table2 RETURNING col5");
+
+        assert_eq!(
+            logical_plan.root,
+            LogicalNode {
+                operator: projection(vec![column("col5")]),
+                children: vec![LogicalNode {
+                    operator: Operator::Delete(DeleteInfo {
+                        table: table("table2"),
+                        predicate: None,
+                    }),
+                    children: vec![],
+                }],
+            }
+        )
+    }
+
+    #[test]
+    fn insert_returning_wildcard() {
+        let logical_plan = analyze("INSERT INTO table2 VALUES (1, 'a') RETURNING *");
+
+        assert_eq!(
+            logical_plan.root.operator,
+            projection(vec![
+                qualified("table2", "col5"),
+                qualified("table2", "col6")
+            ])
+        );
+        assert!(matches!(
+            logical_plan.root.children[0].operator,
+            Operator::Insert(_)
+        ));
+    }
+
+    #[test]
+    fn returning_unknown_column() {
+        assert_eq!(
+            analyze_error("DELETE FROM table2 RETURNING col1").kind,
+            AnalyzeErrorKind::UnknownColumn("col1".to_string())
+        );
+    }
+
+    #[test]
+    fn create_table_with_constraints() {
+        let logical_plan =
+            analyze("CREATE TABLE table3 (a INT PRIMARY KEY, b TEXT UNIQUE, c INT, UNIQUE (b, c))");
+
+        assert_eq!(
+            logical_plan.root.operator,
+            Operator::CreateTable(CreateTableInfo {
+                table: TableDefinition {
+                    name: "table3".to_string(),
+                    columns: vec![
+                        ColumnDefinition {
+                            nullable: false,
+                            ..ColumnDefinition::new("a", Type::Int)
+                        },
+                        ColumnDefinition::new("b", Type::String),
+                        ColumnDefinition::new("c", Type::Int),
+                    ],
+                    constraints: vec![
+                        Constraint::PrimaryKey(vec!["a".to_string()]),
+                        Constraint::Unique(vec!["b".to_string()]),
+                        Constraint::Unique(vec!["b".to_string(), "c".to_string()]),
+                    ],
+                    indexes: vec![],
+                },
+                if_not_exists: false,
+            })
+        );
+    }
+
+    #[test]
+    fn create_table_with_nullability_and_defaults() {
+        let logical_plan = analyze(
+            "CREATE TABLE table3 (a INT NOT NULL DEFAULT -1, b FLOAT DEFAULT 1 + 1, c TEXT NULL)",
+        );
+
+        let table = match logical_plan.root.operator {
+            Operator::CreateTable(info) => info.table,
+            operator => panic!("Unexpected operator: {:?}", operator),
+        };
+
+        assert_eq!(
+            table.columns,
+            vec![
+                ColumnDefinition {
+                    nullable: false,
+                    default: Some(constant(Literal::Numeric(-1))),
+                    ..ColumnDefinition::new("a", Type::Int)
+                },
+                ColumnDefinition {
+                    default: Some(Expression::Binary(
+                        Op::Plus,
+                        Box::new(constant(Literal::Numeric(1))),
+                        Box::new(constant(Literal::Numeric(1)))
+                    )),
+                    ..ColumnDefinition::new("b", Type::Float)
+                },
+                ColumnDefinition::new("c", Type::String),
+            ]
+        );
+    }
+
+    #[test]
+    fn create_table_with_default_of_wrong_type() {
+        assert_eq!(
+            analyze_error("CREATE TABLE table3 (a INT DEFAULT 'x')").kind,
+            AnalyzeErrorKind::TypeMismatch(
+                "cannot assign String to column a of type Int".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn create_table_with_default_reading_column() {
+        assert_eq!(
+            analyze_error("CREATE TABLE table3 (a INT DEFAULT b, b INT)").kind,
+            AnalyzeErrorKind::UnknownColumn("b".to_string())
+        );
+    }
+
+    #[test]
+    fn create_index() {
+        assert_eq!(
+            analyze("CREATE UNIQUE INDEX index1 ON table1 (col2, col1)")
+                .root
+                .operator,
+            Operator::CreateIndex(CreateIndexInfo {
+                table: table("table1"),
+                index: IndexDefinition {
+                    name: "index1".to_string(),
+                    columns: vec!["col2".to_string(), "col1".to_string()],
+                    unique: true,
+                },
+                if_not_exists: false,
+            })
+        );
+    }
+
+    #[test]
+    fn create_index_on_unknown_column() {
+        assert_eq!(
+            analyze_error("CREATE INDEX index1 ON table1 (col5)").kind,
+            AnalyzeErrorKind::UnknownColumn("table1.col5".to_string())
+        );
+    }
+
+    #[test]
+    fn create_existing_index() {
+        let mut catalog = catalog();
+        catalog.create_index(
+            "table1",
+            IndexDefinition {
+                name: "index1".to_string(),
+                columns: vec!["col1".to_string()],
+                unique: false,
+            },
+        );
+
+        let ast = Parser::new(Lexer::new("CREATE INDEX index1 ON table2 (col5)"))
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            Analyzer::new(&catalog).analyze(&ast).unwrap_err().kind,
+            AnalyzeErrorKind::Invalid("Index already exists: index1".to_string())
+        );
+    }
+
+    #[test]
+    fn drop_unknown_index() {
+        assert_eq!(
+            analyze_error("DROP INDEX index1").kind,
+            AnalyzeErrorKind::Invalid("Unknown index: index1".to_string())
+        );
+    }
+
+    #[test]
+    fn insert_columns_have_types() {
+        let logical_plan = analyze("INSERT INTO table1 (col2, col1) VALUES ('a', 1)");
+
+        assert_eq!(
+            logical_plan.root.operator,
+            Operator::Insert(InsertInfo {
+                table: table("table1"),
+                columns: vec![column("col2"), column("col1")],
+                on_conflict: None,
+            })
+        );
+    }
+
+    #[test]
+    fn create_table_with_two_primary_keys() {
+        assert_eq!(
+            analyze_error("CREATE TABLE table3 (a INT PRIMARY KEY, b INT, PRIMARY KEY (b))").kind,
+            AnalyzeErrorKind::Invalid("Multiple primary keys for table: table3".to_string())
+        );
+    }
+
+    #[test]
+    fn create_table_constraint_on_unknown_column() {
+        assert_eq!(
+            analyze_error("CREATE TABLE table3 (a INT, UNIQUE (b))").kind,
+            AnalyzeErrorKind::UnknownColumn("table3.b".to_string())
+        );
+    }
+
+    #[test]
+    fn insert_on_conflict_do_nothing() {
+        let logical_plan = analyze("INSERT INTO table1 (col1) VALUES (1) ON CONFLICT DO NOTHING");
+
+        assert_eq!(
+            logical_plan.root.operator,
+            Operator::Insert(InsertInfo {
+                table: table("table1"),
+                columns: vec![column("col1")],
+                on_conflict: Some(OnConflict {
+                    columns: vec![],
+                    action: ConflictAction::Nothing
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn insert_on_conflict_do_update() {
+        let logical_plan = analyze(
+            "INSERT INTO table1 (col1, col2) VALUES (1, 'a') \
+             ON CONFLICT (col1) DO UPDATE SET col2 = excluded.col2 WHERE table1.col4",
+        );
+
+        assert_eq!(
+            logical_plan.root.operator,
+            Operator::Insert(InsertInfo {
+                table: table("table1"),
+                columns: vec![column("col1"), column("col2")],
+                on_conflict: Some(OnConflict {
+                    columns: vec![column("col1")],
+                    action: ConflictAction::Update {
+                        assignments: vec![Assignment {
+                            column: column("col2"),
+                            expression: Expression::Column(qualified("excluded", "col2")),
+                        }],
+                        predicate: Some(Expression::Column(qualified("table1", "col4"))),
+                    },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn insert_on_conflict_matches_unique_key_in_any_order() {
+        let logical_plan =
+            analyze("INSERT INTO table1 (col1) VALUES (1) ON CONFLICT (col3, col2) DO NOTHING");
+
+        assert!(matches!(
+            logical_plan.root.operator,
+            Operator::Insert(InsertInfo {
+                on_conflict: Some(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn insert_on_conflict_without_matching_key() {
+        assert_eq!(
+            analyze_error("INSERT INTO table1 (col1) VALUES (1) ON CONFLICT (col2) DO NOTHING").kind,
+            AnalyzeErrorKind::Invalid("No unique or primary key constraint matches the ON CONFLICT columns of table: table1".to_string())
+        );
+    }
+
+    #[test]
+    fn insert_on_conflict_do_update_without_target() {
+        assert_eq!(
+            analyze_error(
+                "INSERT INTO table1 (col1) VALUES (1) ON CONFLICT DO UPDATE SET col2 = 'a'"
+            )
+            .kind,
+            AnalyzeErrorKind::Invalid(
+                "ON CONFLICT DO UPDATE requires conflict columns".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn insert_on_conflict_unqualified_column_is_ambiguous() {
+        assert_eq!(
+            analyze_error(
+                "INSERT INTO table1 (col1) VALUES (1) ON CONFLICT (col1) DO UPDATE SET col2 = col2"
+            )
+            .kind,
+            AnalyzeErrorKind::AmbiguousColumn("col2".to_string())
+        );
+    }
+
+    #[test]
+    fn malformed_trees_are_rejected() {
+        let catalog = catalog();
+        let analyze = |node: Node| Analyzer::new(&catalog).analyze(&node).unwrap_err().kind;
+
+        assert_eq!(
+            analyze(Node::Prefix(
+                Op::DropView,
+                vec![Node::Leaf(Literal::Numeric(1))]
+            )),
+            AnalyzeErrorKind::Unsupported("Unexpected node: Leaf(Numeric(1))".to_string())
+        );
+        assert_eq!(
+            analyze(Node::Prefix(Op::With, vec![])),
+            AnalyzeErrorKind::Unsupported("Unexpected node: Prefix(With, [])".to_string())
+        );
+
+        let id = |name: &str| {
+            Node::Leaf(Literal::Identifier {
+                first_name: name.to_string(),
+                second_name: None,
+                third_name: None,
+            })
+        };
+
+        // SELECT commit
+        assert_eq!(
+            analyze(Node::Prefix(
+                Op::Select,
+                vec![Node::Prefix(Op::Commit, vec![])]
+            )),
+            AnalyzeErrorKind::Unsupported("Unexpected node: Prefix(Commit, [])".to_string())
+        );
+        // SELECT 1 FROM table1 WHERE begin
+        assert_eq!(
+            analyze(Node::Prefix(
+                Op::Select,
+                vec![
+                    Node::Leaf(Literal::Numeric(1)),
+                    Node::Prefix(
+                        Op::From,
+                        vec![
+                            id("table1"),
+                            Node::Prefix(Op::Where, vec![Node::Prefix(Op::Begin, vec![])])
+                        ]
+                    )
+                ]
+            )),
+            AnalyzeErrorKind::Unsupported("Unexpected node: Prefix(Begin, [])".to_string())
+        );
+        // INSERT INTO table2 VALUES (rollback, 'x')
+        assert_eq!(
+            analyze(Node::Prefix(
+                Op::InsertInto,
+                vec![
+                    id("table2"),
+                    Node::Prefix(Op::ColumnList, vec![]),
+                    Node::Prefix(
+                        Op::Values,
+                        vec![Node::Prefix(
+                            Op::Row,
+                            vec![
+                                Node::Prefix(Op::Rollback, vec![]),
+                                Node::Leaf(Literal::String("x".to_string()))
+                            ]
+                        )]
+                    )
+                ]
+            )),
+            AnalyzeErrorKind::Unsupported("Unexpected node: Prefix(Rollback, [])".to_string())
+        );
+
+        assert_eq!(
+            analyze(Node::Infix(
+                Op::Union,
+                vec![Node::Prefix(
+                    Op::Select,
+                    vec![Node::Leaf(Literal::Numeric(1))]
+                )]
+            )),
+            AnalyzeErrorKind::WrongArity(
+                "Wrong number of operands: expected at least 2, got 1".to_string()
+            )
+        );
+        assert_eq!(
+            analyze(Node::Prefix(
+                Op::Update,
+                vec![
+                    id("table2"),
+                    Node::Prefix(Op::Set, vec![Node::Infix(Op::Equals, vec![id("col5")])])
+                ]
+            )),
+            AnalyzeErrorKind::WrongArity(
+                "Wrong number of operands: expected at least 2, got 1".to_string()
+            )
+        );
+        assert_eq!(
+            analyze(Node::Prefix(
+                Op::Merge,
+                vec![
+                    id("table1"),
+                    Node::Prefix(Op::Select, vec![id("col5")]),
+                    Node::Leaf(Literal::Bool(true)),
+                    Node::Prefix(Op::WhenMatched, vec![Node::Prefix(Op::Delete, vec![])])
+                ]
+            )),
+            AnalyzeErrorKind::Unsupported(
+                "Unsupported source in MERGE, expected a table or view: \
+                 Prefix(Select, [Leaf(Identifier { first_name: \"col5\", \
+                 second_name: None, third_name: None })])"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            analyze(Node::Prefix(Op::Explain, vec![])),
+            AnalyzeErrorKind::WrongArity(
+                "Wrong number of operands: expected at least 1, got 0".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_innermost_node() {
+        assert_eq!(
+            analyze_error("SELECT col1 FROM table1 WHERE col1 = 1 AND col9 > 2"),
+            AnalyzeError {
+                kind: AnalyzeErrorKind::UnknownColumn("col9".to_string()),
+                span: Some(Span { start: 43, end: 47 })
+            }
+        );
+        assert_eq!(
+            analyze_error("SELECT col1 FROM table9"),
+            AnalyzeError {
+                kind: AnalyzeErrorKind::UnknownTable("table9".to_string()),
+                span: Some(Span { start: 17, end: 23 })
+            }
+        );
+        assert_eq!(
+            analyze_error("SELECT col1 FROM table9").to_string(),
+            "Unknown table: table9 at 17..23"
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_node_itself_among_equal_ones() {
+        assert_eq!(
+            analyze_error("SELECT col1 FROM table1 WHERE col1").span,
+            Some(Span { start: 30, end: 34 })
+        );
+        assert_eq!(
+            analyze_error("INSERT INTO table1 (col1, col1) VALUES (1, 1)"),
+            AnalyzeError {
+                kind: AnalyzeErrorKind::Invalid(
+                    "Column specified more than once: col1".to_string()
+                ),
+                span: Some(Span { start: 26, end: 30 }),
+            }
+        );
+    }
+
+    #[test]
+    fn wrong_number_of_values_points_at_the_row() {
+        assert_eq!(
+            analyze_error("INSERT INTO table1 VALUES (1, 'a', 1.0, true), (1)"),
+            AnalyzeError {
+                kind: AnalyzeErrorKind::WrongArity(
+                    "Wrong number of values: expected 4, got 1".to_string()
+                ),
+                span: Some(Span { start: 47, end: 50 }),
+            }
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let logical_plan = analyze(
+            "MERGE INTO table1 USING table2 ON table1.col1 = table2.col5 \
+             WHEN MATCHED AND col5 > 0 THEN UPDATE SET col2 = col6 \
+             WHEN NOT MATCHED THEN INSERT (col1) VALUES (col5)",
+        );
+
+        let col5 = || Box::new(Expression::Column(column("col5")));
+
+        assert_eq!(
+            logical_plan,
+            LogicalPlan {
+                root: LogicalNode {
+                    operator: Operator::Merge(MergeInfo {
+                        table: table("table1"),
+                        condition: Expression::Binary(
+                            Op::Equals,
+                            Box::new(Expression::Column(qualified("table1", "col1"))),
+                            Box::new(Expression::Column(qualified("table2", "col5"))),
+                        ),
+                        clauses: vec![
+                            MergeClause {
+                                matched: true,
+                                predicate: Some(Expression::Binary(
+                                    Op::GreaterThan,
+                                    col5(),
+                                    Box::new(constant(Literal::Numeric(0)))
+                                )),
+                                action: MergeAction::Update(vec![Assignment {
+                                    column: column("col2"),
+                                    expression: Expression::Column(column("col6")),
+                                }]),
+                            },
+                            MergeClause {
+                                matched: false,
+                                predicate: None,
+                                action: MergeAction::Insert {
+                                    columns: vec![column("col1")],
+                                    values: vec![Expression::Column(column("col5"))],
+                                },
+                            },
+                        ],
+                    }),
+                    children: vec![LogicalNode {
+                        operator: read(table("table2")),
+                        children: vec![],
+                    }],
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn merge_not_matched_cannot_see_target() {
+        assert_eq!(
+            analyze_error(
+                "MERGE INTO table1 USING table2 ON col1 = col5 \
+             WHEN NOT MATCHED THEN INSERT (col1) VALUES (col1)"
+            )
+            .kind,
+            AnalyzeErrorKind::UnknownColumn("col1".to_string())
+        );
+    }
+
+    #[test]
+    fn with() {
+        let logical_plan = analyze("WITH t (a) AS (SELECT col5 FROM table2) SELECT a FROM t");
+
+        assert_eq!(
+            logical_plan.root,
+            LogicalNode {
+                operator: Operator::With(WithInfo {
+                    ctes: vec![Cte {
+                        name: "t".to_string(),
+                        columns: vec!["a".to_string()],
+                        recursive: false,
+                    }],
+                }),
+                children: vec![
+                    LogicalNode {
+                        operator: projection(vec![column("col5")]),
+                        children: vec![LogicalNode {
+                            operator: read(table("table2")),
+                            children: vec![],
+                        }],
+                    },
+                    LogicalNode {
+                        operator: projection(vec![Column {
+                            typ: Some(Type::Int),
+                            ..column("a")
+                        }]),
+                        children: vec![LogicalNode {
+                            operator: read(table("t")),
+                            children: vec![],
+                        }],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn with_recursive() {
+        let logical_plan = analyze(
+            "WITH RECURSIVE t (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 5), \
+             u AS (SELECT 1 UNION SELECT 2) \
+             SELECT n FROM t",
+        );
+
+        match logical_plan.root.operator {
+            Operator::With(info) => assert_eq!(
+                info.ctes
+                    .iter()
+                    .map(|cte| cte.recursive)
+                    .collect::<Vec<_>>(),
+                vec![true, false]
+            ),
+            operator => panic!("Unexpected operator: {:?}", operator),
+        }
+        assert_eq!(
+            logical_plan.root.children[0].operator,
+            Operator::Union(UnionInfo { all: true })
+        );
+    }
+
+    #[test]
+    fn with_without_recursive_cannot_read_itself() {
+        assert_eq!(
+            analyze_error("WITH t (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t) SELECT n FROM t")
+                .kind,
+            AnalyzeErrorKind::UnknownTable("t".to_string())
+        );
+    }
+
+    #[test]
+    fn cte_is_not_visible_to_earlier_ctes() {
+        assert_eq!(
+            analyze_error("WITH t AS (SELECT 1 FROM u), u AS (SELECT 1) SELECT 1 FROM t").kind,
+            AnalyzeErrorKind::UnknownTable("u".to_string())
+        );
+    }
+
+    #[test]
+    fn union_wrong_arity() {
+        assert_eq!(
+            analyze_error("SELECT col1 FROM table1 UNION SELECT col5, col6 FROM table2").kind,
+            AnalyzeErrorKind::WrongArity("Wrong number of columns: expected 1, got 2".to_string())
+        );
+    }
+
+    #[test]
+    fn union_wrong_type() {
+        assert_eq!(
+            analyze_error("SELECT col1 FROM table1 UNION SELECT col6 FROM table2").kind,
+            AnalyzeErrorKind::TypeMismatch("Int UNION String".to_string())
+        );
+    }
+
+    #[test]
+    fn window_function() {
+        let logical_plan = analyze(
+            "SELECT col2, sum(col1) OVER (PARTITION BY col2 ORDER BY col3 DESC ROWS 1 PRECEDING) + 1 FROM table1",
+        );
+
+        assert_eq!(
+            logical_plan.root,
+            LogicalNode {
+                operator: Operator::Projection(ProjectionInfo {
+                    expressions: vec![
+                        Expression::Column(column("col2")),
+                        Expression::Binary(
+                            Op::Plus,
+                            Box::new(Expression::Column(Column {
+                                typ: Some(Type::Int),
+                                ..qualified("#window0", "sum")
+                            })),
+                            Box::new(constant(Literal::Numeric(1))),
+                        ),
+                    ],
+                }),
+                children: vec![LogicalNode {
+                    operator: Operator::Window(WindowInfo {
+                        functions: vec![WindowFunction {
+                            function: WindowFunctionKind::Sum,
+                            arguments: vec![Expression::Column(column("col1"))],
+                            partition_by: vec![Expression::Column(column("col2"))],
+                            order_by: vec![SortKey {
+                                expression: Expression::Column(column("col3")),
+                                ascending: false,
+                            }],
+                            frame: Some(Frame {
+                                start: FrameBound::Preceding(1),
+                                end: FrameBound::CurrentRow,
+                            }),
+                        }],
+                    }),
+                    children: vec![LogicalNode {
+                        operator: read(table("table1")),
+                        children: vec![],
+                    }],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn window_function_in_where() {
+        assert_eq!(
+            analyze_error("SELECT col1 FROM table1 WHERE row_number() OVER () = 1").kind,
+            AnalyzeErrorKind::AggregateMisuse(
+                "Window functions are not allowed here: row_number".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn nested_window_functions() {
+        assert_eq!(
+            analyze_error("SELECT sum(rank() OVER ()) OVER () FROM table1").kind,
+            AnalyzeErrorKind::AggregateMisuse(
+                "Window functions are not allowed here: rank".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn window_function_without_over() {
+        assert_eq!(
+            analyze_error("SELECT rank() FROM table1").kind,
+            AnalyzeErrorKind::AggregateMisuse(
+                "Window function requires an OVER clause: rank".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn window_function_wrong_arity() {
+        assert_eq!(
+            analyze_error("SELECT first_value() OVER () FROM table1").kind,
+            AnalyzeErrorKind::WrongArity(
+                "Wrong number of arguments: first_value takes 1 to 1, got 0".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn window_sum_of_text() {
+        assert_eq!(
+            analyze_error("SELECT sum(col2) OVER () FROM table1").kind,
+            AnalyzeErrorKind::TypeMismatch("sum of String".to_string())
+        );
+    }
+
+    #[test]
+    fn window_frame_ends_before_start() {
+        assert_eq!(
+            analyze_error(
+                "SELECT sum(col1) OVER (ROWS BETWEEN CURRENT ROW AND 1 PRECEDING) FROM table1"
+            )
+            .kind,
+            AnalyzeErrorKind::Invalid(
+                "Invalid window frame: CurrentRow to Preceding(1)".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn create_view() {
+        let logical_plan = analyze("CREATE VIEW view2 AS SELECT view1.a, col1 FROM view1, table1");
+
+        assert_eq!(
+            logical_plan.root.operator,
+            Operator::CreateView(CreateViewInfo {
+                view: ViewDefinition {
+                    name: "view2".to_string(),
+                    columns: vec![],
+                    query: "SELECT view1.a, col1 FROM view1, table1".to_string(),
+                    dependencies: vec!["table1".to_string(), "view1".to_string()],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn read_view() {
+        let logical_plan = analyze("SELECT a FROM view1");
+
+        assert_eq!(
+            logical_plan,
+            LogicalPlan {
+                root: LogicalNode {
+                    operator: projection(vec![Column {
+                        typ: Some(Type::Int),
+                        ..column("a")
+                    }]),
+                    children: vec![LogicalNode {
+                        operator: Operator::Subquery(SubqueryInfo {
+                            name: "view1".to_string(),
+                            columns: vec!["a".to_string()],
+                        }),
+                        children: vec![LogicalNode {
+                            operator: projection(vec![column("col5")]),
+                            children: vec![LogicalNode {
+                                operator: read(table("table2")),
+                                children: vec![],
+                            }],
+                        }],
+                    }],
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn create_existing_view() {
+        assert_eq!(
+            analyze_error("CREATE VIEW view1 AS SELECT col1 FROM table1").kind,
+            AnalyzeErrorKind::Invalid("View already exists: view1".to_string())
+        );
+    }
+
+    #[test]
+    fn replace_view_dropping_columns() {
+        assert_eq!(
+            analyze_error("CREATE OR REPLACE VIEW view1 AS SELECT col6 FROM table2").kind,
+            AnalyzeErrorKind::Invalid("Cannot change columns of view: view1".to_string())
+        );
+    }
+
+    #[test]
+    fn drop_table_with_dependent_view() {
+        assert_eq!(
+            analyze_error("DROP TABLE table2").kind,
+            AnalyzeErrorKind::Invalid(
+                "Cannot drop table2 because other objects depend on it: view1".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn drop_table_cascade() {
+        let logical_plan = analyze("DROP TABLE table2 CASCADE");
+
+        assert_eq!(
+            logical_plan.root.operator,
+            Operator::DropTable(DropTableInfo {
+                tables: vec![table("table2")],
+                views: vec!["view1".to_string()],
+                foreign_keys: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn create_table_with_foreign_keys() {
+        let logical_plan = analyze(
+            "CREATE TABLE IF NOT EXISTS table3 (a INT PRIMARY KEY REFERENCES table1, b TEXT, c FLOAT, \
+             parent INT REFERENCES table3, FOREIGN KEY (c, b) REFERENCES table1 (col3, col2))",
+        );
+
+        match logical_plan.root.operator {
+            Operator::CreateTable(CreateTableInfo {
+                table,
+                if_not_exists,
+            }) => {
+                assert!(if_not_exists);
+                assert_eq!(
+                    table.constraints,
+                    vec![
+                        Constraint::PrimaryKey(vec!["a".to_string()]),
+                        Constraint::ForeignKey {
+                            columns: vec!["a".to_string()],
+                            table: "table1".to_string(),
+                            referenced_columns: vec!["col1".to_string()],
+                        },
+                        Constraint::ForeignKey {
+                            columns: vec!["parent".to_string()],
+                            table: "table3".to_string(),
+                            referenced_columns: vec!["a".to_string()],
+                        },
+                        Constraint::ForeignKey {
+                            columns: vec!["c".to_string(), "b".to_string()],
+                            table: "table1".to_string(),
+                            referenced_columns: vec!["col3".to_string(), "col2".to_string()],
+                        },
+                    ]
+                );
+            }
+            operator => panic!("Unexpected operator: {:?}", operator),
+        }
+    }
+
+    #[test]
+    fn foreign_key_to_non_unique_columns() {
+        assert_eq!(
+            analyze_error("CREATE TABLE table3 (a TEXT REFERENCES table1 (col2))").kind,
+            AnalyzeErrorKind::Invalid(
+                "No unique key on referenced columns of table: table1".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn foreign_key_without_primary_key() {
+        assert_eq!(
+            analyze_error("CREATE TABLE table3 (a INT REFERENCES table2)").kind,
+            AnalyzeErrorKind::Invalid("No primary key for table: table2".to_string())
+        );
+    }
+
+    #[test]
+    fn create_existing_table_if_not_exists() {
+        let logical_plan = analyze("CREATE TABLE IF NOT EXISTS table1 (a INT)");
+
+        assert!(matches!(
+            logical_plan.root.operator,
+            Operator::CreateTable(CreateTableInfo {
+                if_not_exists: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn drop_tables_if_exists() {
+        let logical_plan = analyze("DROP TABLE IF EXISTS table3, table1, table1 RESTRICT");
+
+        assert_eq!(
+            logical_plan.root.operator,
+            Operator::DropTable(DropTableInfo {
+                tables: vec![table("table1")],
+                views: vec![],
+                foreign_keys: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn drop_unknown_table() {
+        assert_eq!(
+            analyze_error("DROP TABLE table1, table3").kind,
+            AnalyzeErrorKind::UnknownTable("table3".to_string())
+        );
+    }
+
+    #[test]
+    fn create_table_as() {
+        let logical_plan = analyze("CREATE TABLE table3 AS SELECT col1, col2, NULL FROM table1");
+
+        assert_eq!(
+            logical_plan.root,
+            LogicalNode {
+                operator: Operator::CreateTable(CreateTableInfo {
+                    table: TableDefinition {
+                        name: "table3".to_string(),
+                        columns: vec![
+                            ColumnDefinition::new("col1", Type::Int),
+                            ColumnDefinition::new("col2", Type::String),
+                            ColumnDefinition::new("?column?", Type::String),
+                        ],
+                        constraints: vec![],
+                        indexes: vec![],
+                    },
+                    if_not_exists: false,
+                }),
+                children: vec![LogicalNode {
+                    operator: Operator::Projection(ProjectionInfo {
+                        expressions: vec![
+                            Expression::Column(column("col1")),
+                            Expression::Column(column("col2")),
+                            constant(Literal::Null),
+                        ],
+                    }),
+                    children: vec![LogicalNode {
+                        operator: read(table("table1")),
+                        children: vec![],
+                    }],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn create_table_as_with_duplicate_columns() {
+        assert_eq!(
+            analyze_error("CREATE TABLE table3 AS SELECT col1, col1 FROM table1").kind,
+            AnalyzeErrorKind::Invalid("Column specified more than once: col1".to_string())
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_to_json() {
+        let plan = analyze("SELECT col1 FROM table1 WHERE col1 > 1");
+        let json = serde_json::to_string(&plan).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"root":{"operator":{"projection":{"expressions":[{"column":{"table":null,"name":"col1","typ":"int"}}]}},"children":[{"operator":{"filter":{"predicate":{"binary":["greater_than",{"column":{"table":null,"name":"col1","typ":"int"}},{"constant":{"numeric":1}}]}}},"children":[{"operator":{"read":{"table":{"name":"table1"}}},"children":[]}]}]}}"#
+        );
+        assert_eq!(serde_json::from_str::<LogicalPlan>(&json).unwrap(), plan);
+
+        for input in [
+            "INSERT INTO table1 (col1, col2) VALUES (1, 'a') ON CONFLICT (col1) DO UPDATE SET col2 = 'b'",
+            "CREATE TABLE table3 (id INT PRIMARY KEY, at TIMESTAMP)",
+            "BEGIN",
+        ] {
+            let plan = analyze(input);
+            let json = serde_json::to_string(&plan).unwrap();
+
+            assert_eq!(serde_json::from_str::<LogicalPlan>(&json).unwrap(), plan);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::analyzer::{
+    Assignment, ConflictAction, Expression, LogicalNode, LogicalPlan, MergeAction, Operator,
+};
+use crate::parser::{Literal, Type};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParameterInfo {
+    /// Set for `:name` parameters.
+    pub name: Option<String>,
+    pub typ: Option<Type>,
+}
+
+impl LogicalPlan {
+    /// The plan with every parameter replaced by its value. `values` holds
+    /// one literal per parameter, by index.
+    pub fn bind(&self, values: &[Literal]) -> LogicalPlan {
+        let mut root = self.root.clone();
+
+        root.for_each_expression_mut(&mut |expression| bind(expression, values));
+
+        LogicalPlan { root }
+    }
+}
+
+fn bind(expression: &mut Expression, values: &[Literal]) {
+    match expression {
+        Expression::Parameter(index) => *expression = Expression::Constant(values[*index].clone()),
+        Expression::Unary(_, operand) => bind(operand, values),
+        Expression::Binary(_, lhs, rhs) => {
+            bind(lhs, values);
+            bind(rhs, values);
+        }
+        Expression::Constant(_) | Expression::Column(_) | Expression::Default => {}
+    }
+}
+
+impl LogicalNode {
+    /// Calls `f` with every top-level expression of this node and its
+    /// descendants.
+    pub fn for_each_expression_mut(&mut self, f: &mut dyn FnMut(&mut Expression)) {
+        match &mut self.operator {
+            Operator::Projection(info) => info.expressions.iter_mut().for_each(&mut *f),
+            Operator::Filter(info) => f(&mut info.predicate),
+            Operator::Insert(info) => {
+                if let Some(ConflictAction::Update {
+                    assignments,
+                    predicate,
+                }) = info
+                    .on_conflict
+                    .as_mut()
+                    .map(|on_conflict| &mut on_conflict.action)
+                {
+                    assignments_mut(assignments, f);
+                    predicate.iter_mut().for_each(&mut *f);
+                }
+            }
+            Operator::Values(info) => info.rows.iter_mut().flatten().for_each(&mut *f),
+            Operator::Update(info) => {
+                assignments_mut(&mut info.assignments, f);
+                info.predicate.iter_mut().for_each(&mut *f);
+            }
+            Operator::Delete(info) => info.predicate.iter_mut().for_each(&mut *f),
+            Operator::Merge(info) => {
+                f(&mut info.condition);
+
+                for clause in &mut info.clauses {
+                    clause.predicate.iter_mut().for_each(&mut *f);
+
+                    match &mut clause.action {
+                        MergeAction::Update(assignments) => assignments_mut(assignments, f),
+                        MergeAction::Insert { values, .. } => values.iter_mut().for_each(&mut *f),
+                        MergeAction::Delete | MergeAction::Nothing => {}
+                    }
+                }
+            }
+            Operator::Window(info) => {
+                for function in &mut info.functions {
+                    function.arguments.iter_mut().for_each(&mut *f);
+                    function.partition_by.iter_mut().for_each(&mut *f);
+
+                    for key in &mut function.order_by {
+                        f(&mut key.expression);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for child in &mut self.children {
+            child.for_each_expression_mut(f);
+        }
+    }
+}
+
+fn assignments_mut(assignments: &mut [Assignment], f: &mut dyn FnMut(&mut Expression)) {
+    for assignment in assignments {
+        f(&mut assignment.expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyzer::{
+        AnalyzeErrorKind, Analyzer, Column, Expression, FilterInfo, Operator, ParameterInfo,
+    };
+    use crate::catalog::{Catalog, ColumnDefinition, TableDefinition};
+    use crate::parser::lexer::Lexer;
+    use crate::parser::{Literal, Node, Op, Parameter, Parser, Type};
+
+    fn catalog() -> Catalog {
+        let mut catalog = Catalog::new();
+
+        catalog.create_table(TableDefinition {
+            name: "table1".to_string(),
+            columns: vec![
+                ColumnDefinition::new("col1", Type::Int),
+                ColumnDefinition::new("col2", Type::String),
+                ColumnDefinition::new("col3", Type::Float),
+            ],
+            constraints: vec![],
+            indexes: vec![],
+        });
+
+        catalog
+    }
+
+    fn parameters(input: &str) -> Vec<ParameterInfo> {
+        let catalog = catalog();
+        let analyzer = Analyzer::new(&catalog);
+
+        analyzer
+            .analyze(&Parser::new(Lexer::new(input)).parse().unwrap())
+            .unwrap();
+        analyzer.parameters()
+    }
+
+    fn positional(typ: Option<Type>) -> ParameterInfo {
+        ParameterInfo { name: None, typ }
+    }
+
+    #[test]
+    fn infer_parameter_types() {
+        assert_eq!(
+            parameters("SELECT ? FROM table1 WHERE col1 = ? AND ? AND col3 + $5 > 1"),
+            vec![
+                positional(None),
+                positional(Some(Type::Int)),
+                positional(Some(Type::Bool)),
+                positional(None),
+                positional(Some(Type::Float)),
+            ]
+        );
+        assert_eq!(
+            parameters("INSERT INTO table1 VALUES (:a, :b, :a)"),
+            vec![
+                ParameterInfo {
+                    name: Some("a".to_string()),
+                    typ: Some(Type::Int)
+                },
+                ParameterInfo {
+                    name: Some("b".to_string()),
+                    typ: Some(Type::String)
+                },
+            ]
+        );
+        assert_eq!(
+            parameters("UPDATE table1 SET col2 = ? WHERE col1 = ?"),
+            vec![positional(Some(Type::String)), positional(Some(Type::Int))]
+        );
+    }
+
+    #[test]
+    fn mixed_parameters() {
+        let catalog = catalog();
+        let ast = Parser::new(Lexer::new(
+            "SELECT col1 FROM table1 WHERE col1 = ? AND col2 = :name",
+        ))
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            Analyzer::new(&catalog).analyze(&ast).unwrap_err().kind,
+            AnalyzeErrorKind::Invalid("Cannot mix positional and named parameters".to_string())
+        );
+    }
+
+    #[test]
+    fn parameter_numbers_are_limited() {
+        let catalog = catalog();
+        let ast = Parser::new(Lexer::new("SELECT $1000000000"))
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            Analyzer::new(&catalog).analyze(&ast).unwrap_err().kind,
+            AnalyzeErrorKind::Invalid(
+                "Parameter number out of range: $1000000000, expected 1 to 65535".to_string()
+            )
+        );
+
+        let ast = Node::Prefix(
+            Op::Select,
+            vec![Node::Leaf(Literal::Parameter(Parameter::Positional(0)))],
+        );
+
+        assert_eq!(
+            Analyzer::new(&catalog).analyze(&ast).unwrap_err().kind,
+            AnalyzeErrorKind::Invalid(
+                "Parameter number out of range: $0, expected 1 to 65535".to_string()
+            )
+        );
+        assert_eq!(parameters("SELECT $65535").len(), 65535);
+    }
+
+    #[test]
+    fn bind() {
+        let catalog = catalog();
+        let ast = Parser::new(Lexer::new("SELECT col1 FROM table1 WHERE col1 = $1"))
+            .parse()
+            .unwrap();
+        let plan = Analyzer::new(&catalog).analyze(&ast).unwrap();
+
+        let bound = plan.bind(&[Literal::Numeric(7)]);
+
+        assert_eq!(
+            bound.root.children[0].operator,
+            Operator::Filter(FilterInfo {
+                predicate: Expression::Binary(
+                    Op::Equals,
+                    Box::new(Expression::Column(Column {
+                        typ: Some(Type::Int),
+                        ..Column::new("col1")
+                    })),
+                    Box::new(Expression::Constant(Literal::Numeric(7))),
+                ),
+            })
+        );
+    }
+}
@@ -0,0 +1,254 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::analyzer::Expression;
+use crate::parser::Type;
+
+#[derive(Debug, Default, Clone)]
+pub struct Catalog {
+    tables: HashMap<String, TableDefinition>,
+    views: HashMap<String, ViewDefinition>,
+    version: u64,
+}
+
+/// Source of catalog versions. Versions are never reused, not even by a
+/// catalog restored from an older copy, so equal versions mean equal
+/// definitions.
+static VERSIONS: AtomicU64 = AtomicU64::new(1);
+
+impl Catalog {
+    pub fn new() -> Self {
+        Catalog {
+            tables: HashMap::new(),
+            views: HashMap::new(),
+            version: 0,
+        }
+    }
+
+    /// Changes whenever a table or view is created or dropped.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    fn changed(&mut self) {
+        self.version = VERSIONS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn create_table(&mut self, table: TableDefinition) {
+        self.changed();
+        self.tables.insert(table.name.clone(), table);
+    }
+
+    pub fn drop_table(&mut self, name: &str) -> Option<TableDefinition> {
+        self.changed();
+        self.tables.remove(name)
+    }
+
+    pub fn table(&self, name: &str) -> Option<&TableDefinition> {
+        self.tables.get(name)
+    }
+
+    /// Adds the index to its table, which must exist.
+    pub fn create_index(&mut self, table: &str, index: IndexDefinition) {
+        self.changed();
+        self.tables.get_mut(table).unwrap().indexes.push(index);
+    }
+
+    pub fn drop_index(&mut self, name: &str) -> Option<IndexDefinition> {
+        self.changed();
+
+        self.tables.values_mut().find_map(|table| {
+            let position = table.indexes.iter().position(|index| index.name == name)?;
+
+            Some(table.indexes.remove(position))
+        })
+    }
+
+    /// The index with the given name, with the table it is on. Index names
+    /// are shared by all tables.
+    pub fn index(&self, name: &str) -> Option<(&TableDefinition, &IndexDefinition)> {
+        self.tables.values().find_map(|table| {
+            table
+                .indexes
+                .iter()
+                .find(|index| index.name == name)
+                .map(|index| (table, index))
+        })
+    }
+
+    /// Creates the view, replacing any view with the same name.
+    pub fn create_view(&mut self, view: ViewDefinition) {
+        self.changed();
+        self.views.insert(view.name.clone(), view);
+    }
+
+    pub fn drop_view(&mut self, name: &str) -> Option<ViewDefinition> {
+        self.changed();
+        self.views.remove(name)
+    }
+
+    /// Tables other than itself with a foreign key to the given table,
+    /// sorted by name.
+    pub fn referencing_tables(&self, name: &str) -> Vec<String> {
+        let mut tables: Vec<String> = self
+            .tables
+            .values()
+            .filter(|table| table.name != name && !table.foreign_keys_to(name).is_empty())
+            .map(|table| table.name.clone())
+            .collect();
+
+        tables.sort();
+        tables
+    }
+
+    pub fn view(&self, name: &str) -> Option<&ViewDefinition> {
+        self.views.get(name)
+    }
+
+    /// Views that read the given table or view, directly or through other
+    /// views, sorted by name.
+    pub fn dependent_views(&self, name: &str) -> Vec<String> {
+        let mut dependents: Vec<String> = vec![];
+        let mut pending = vec![name.to_string()];
+
+        while let Some(name) = pending.pop() {
+            for view in self.views.values() {
+                if view.dependencies.contains(&name) && !dependents.contains(&view.name) {
+                    dependents.push(view.name.clone());
+                    pending.push(view.name.clone());
+                }
+            }
+        }
+
+        dependents.sort();
+        dependents
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableDefinition {
+    pub name: String,
+    pub columns: Vec<ColumnDefinition>,
+    pub constraints: Vec<Constraint>,
+    pub indexes: Vec<IndexDefinition>,
+}
+
+impl TableDefinition {
+    pub fn column(&self, name: &str) -> Option<&ColumnDefinition> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Column sets that may not hold the same values in two rows: the
+    /// primary key first, then unique constraints in declaration order, then
+    /// unique indexes in the order they were created.
+    pub fn unique_keys(&self) -> Vec<&[String]> {
+        let primary_key = self
+            .constraints
+            .iter()
+            .filter_map(|constraint| match constraint {
+                Constraint::PrimaryKey(columns) => Some(columns.as_slice()),
+                _ => None,
+            });
+
+        let unique = self
+            .constraints
+            .iter()
+            .filter_map(|constraint| match constraint {
+                Constraint::Unique(columns) => Some(columns.as_slice()),
+                _ => None,
+            });
+
+        let unique_indexes = self
+            .indexes
+            .iter()
+            .filter(|index| index.unique)
+            .map(|index| index.columns.as_slice());
+
+        primary_key.chain(unique).chain(unique_indexes).collect()
+    }
+
+    pub fn primary_key(&self) -> Option<&[String]> {
+        self.constraints
+            .iter()
+            .find_map(|constraint| match constraint {
+                Constraint::PrimaryKey(columns) => Some(columns.as_slice()),
+                _ => None,
+            })
+    }
+
+    /// Foreign keys of this table referencing the given table.
+    pub fn foreign_keys_to(&self, name: &str) -> Vec<&Constraint> {
+        self.constraints
+            .iter()
+            .filter(|constraint| matches!(constraint, Constraint::ForeignKey { table, .. } if table == name))
+            .collect()
+    }
+}
+
+/// A view is kept as the text of its query and analyzed again every time it
+/// is read, so it always sees the current definitions of what it reads.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ViewDefinition {
+    pub name: String,
+    /// Names given to the output columns, or empty to keep the query's own.
+    pub columns: Vec<String>,
+    pub query: String,
+    /// Tables and views the query reads.
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Constraint {
+    PrimaryKey(Vec<String>),
+    Unique(Vec<String>),
+    /// Columns referencing a unique key of another table. Only recorded for
+    /// now: writes do not check it.
+    ForeignKey {
+        columns: Vec<String>,
+        table: String,
+        referenced_columns: Vec<String>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnDefinition {
+    pub name: String,
+    pub typ: Type,
+    /// False for `NOT NULL` and primary key columns.
+    pub nullable: bool,
+    /// Value of the column in inserted rows that do not give one, NULL if
+    /// there is none.
+    pub default: Option<Expression>,
+}
+
+impl ColumnDefinition {
+    /// A nullable column without a default.
+    pub fn new(name: &str, typ: Type) -> Self {
+        ColumnDefinition {
+            name: name.to_string(),
+            typ,
+            nullable: true,
+            default: None,
+        }
+    }
+}
+
+/// An index on columns of a table. Rows are not looked up through it yet;
+/// a unique index is enforced like a unique constraint.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexDefinition {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
@@ -0,0 +1,1264 @@
+#![allow(dead_code)]
+
+mod profile;
+pub mod value;
+mod window;
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::analyzer::{
+    window_table, AnalyzeError, Assignment, Column, ConflictAction, Cte, DeleteInfo, Expression,
+    InsertInfo, LogicalNode, LogicalPlan, MergeAction, MergeClause, MergeInfo, Operator,
+    UpdateInfo, WithInfo, EXCLUDED,
+};
+use crate::catalog::{Catalog, ColumnDefinition, Constraint, TableDefinition};
+use crate::parser::{Decimal, Literal, Op, ParseError, Type};
+use crate::storage::{RowId, Storage};
+use crate::temporal::{Interval, TemporalError};
+use crate::transaction::TransactionError;
+use profile::{OperatorStats, Profile};
+use value::{Row, Value};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExecutionError {
+    /// The statement could not be parsed.
+    Parse(ParseError),
+    /// The statement could not be planned.
+    Analyze(AnalyzeError),
+    DivisionByZero,
+    /// Integer or decimal arithmetic went out of range.
+    NumericOverflow,
+    TypeMismatch(String),
+    /// A write would give two rows the same primary or unique key.
+    UniqueViolation(String),
+    /// A write would leave the named `NOT NULL` column NULL.
+    NotNullViolation(String),
+    /// `ON CONFLICT DO UPDATE` would update the same row twice.
+    CardinalityViolation,
+    Transaction(TransactionError),
+    /// A prepared statement was run with the wrong number of values.
+    ParameterCount {
+        expected: usize,
+        actual: usize,
+    },
+    /// No value was given for the named parameter.
+    UnboundParameter(String),
+    /// Date arithmetic went out of range.
+    Temporal(TemporalError),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum QueryResult {
+    Rows(ResultSet),
+    RowsAffected(usize),
+    Merged(MergeResult),
+    Done,
+}
+
+/// Rows changed by a MERGE, per action.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct MergeResult {
+    pub inserted: usize,
+    pub updated: usize,
+    pub deleted: usize,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResultSet {
+    pub columns: Vec<String>,
+    pub rows: Vec<Row>,
+}
+
+/// Rows flowing between operators. Operators pull from their inputs one row
+/// at a time, so a pipeline only holds what its blocking operators need.
+pub type RowStream = Box<dyn Iterator<Item = Result<Row, ExecutionError>>>;
+
+pub struct Executor<'a> {
+    catalog: &'a mut Catalog,
+    storage: &'a mut Storage,
+    /// Materialised CTEs, read in place of tables with the same name.
+    relations: HashMap<String, Relation>,
+    /// Set while running the statement of an EXPLAIN ANALYZE.
+    profile: Option<Rc<Profile>>,
+}
+
+struct Relation {
+    columns: Vec<String>,
+    rows: Rc<Vec<Row>>,
+}
+
+impl<'a> Executor<'a> {
+    pub fn new(catalog: &'a mut Catalog, storage: &'a mut Storage) -> Self {
+        Executor {
+            catalog,
+            storage,
+            relations: HashMap::new(),
+            profile: None,
+        }
+    }
+
+    pub fn execute(&mut self, plan: &LogicalPlan) -> Result<QueryResult, ExecutionError> {
+        self.execute_node(&plan.root)
+    }
+
+    fn execute_node(&mut self, root: &LogicalNode) -> Result<QueryResult, ExecutionError> {
+        match &root.operator {
+            Operator::With(info) => {
+                self.bind_ctes(info, &root.children)?;
+
+                self.execute_node(root.children.last().unwrap())
+            }
+            Operator::CreateTable(info) => {
+                if info.if_not_exists
+                    && (self.catalog.table(&info.table.name).is_some()
+                        || self.catalog.view(&info.table.name).is_some())
+                {
+                    return Ok(QueryResult::Done);
+                }
+
+                self.storage.create_table(&info.table.name);
+                self.catalog.create_table(info.table.clone());
+
+                let source = match root.children.first() {
+                    Some(source) => source,
+                    None => return Ok(QueryResult::Done),
+                };
+
+                let (rows, _) = self.build(source)?;
+                let mut count = 0;
+
+                for row in rows {
+                    let row = row?
+                        .into_iter()
+                        .zip(&info.table.columns)
+                        .map(|(value, column)| value.coerce(column.typ))
+                        .collect();
+
+                    self.storage.insert(&info.table.name, row);
+                    count += 1;
+                }
+
+                Ok(QueryResult::RowsAffected(count))
+            }
+            Operator::DropTable(info) => {
+                for view in &info.views {
+                    self.catalog.drop_view(view);
+                }
+
+                for name in &info.foreign_keys {
+                    let mut table = self.catalog.table(name).unwrap().clone();
+
+                    table.constraints.retain(|constraint| match constraint {
+                        Constraint::ForeignKey { table, .. } => {
+                            !info.tables.iter().any(|dropped| dropped.name == *table)
+                        }
+                        _ => true,
+                    });
+
+                    self.catalog.create_table(table);
+                }
+
+                for table in &info.tables {
+                    self.storage.drop_table(&table.name);
+                    self.catalog.drop_table(&table.name);
+                }
+
+                Ok(QueryResult::Done)
+            }
+            Operator::CreateView(info) => {
+                self.catalog.create_view(info.view.clone());
+
+                Ok(QueryResult::Done)
+            }
+            Operator::DropView(info) => {
+                for view in &info.dependents {
+                    self.catalog.drop_view(view);
+                }
+
+                self.catalog.drop_view(&info.name);
+
+                Ok(QueryResult::Done)
+            }
+            Operator::CreateIndex(info) => {
+                if info.if_not_exists && self.catalog.index(&info.index.name).is_some() {
+                    return Ok(QueryResult::Done);
+                }
+
+                let table = self.catalog.table(&info.table.name).unwrap();
+
+                if info.index.unique {
+                    let key = [info.index.columns.as_slice()];
+
+                    for (row_id, row) in self.storage.scan(&table.name) {
+                        if self
+                            .find_conflict(table, &key, &row, Some(row_id))
+                            .is_some()
+                        {
+                            return Err(ExecutionError::UniqueViolation(table.name.clone()));
+                        }
+                    }
+                }
+
+                self.catalog
+                    .create_index(&info.table.name, info.index.clone());
+
+                Ok(QueryResult::Done)
+            }
+            Operator::DropIndex(info) => {
+                self.catalog.drop_index(&info.name);
+
+                Ok(QueryResult::Done)
+            }
+            Operator::Insert(_) | Operator::Update(_) | Operator::Delete(_) => {
+                let count = self.modify(root, &mut |_| Ok(()))?;
+
+                Ok(QueryResult::RowsAffected(count))
+            }
+            Operator::Merge(info) => Ok(QueryResult::Merged(self.merge(info, &root.children[0])?)),
+            Operator::Explain(info) => self.explain(info.analyze, &root.children[0]),
+            Operator::Projection(info) if root.children.first().is_some_and(is_modification) => {
+                let modification = &root.children[0];
+                let schema = self.table_columns(modified_table(modification));
+
+                let mut rows = vec![];
+
+                self.modify(modification, &mut |row| {
+                    rows.push(
+                        info.expressions
+                            .iter()
+                            .map(|expression| evaluate(expression, &schema, row))
+                            .collect::<Result<_, _>>()?,
+                    );
+
+                    Ok(())
+                })?;
+
+                Ok(QueryResult::Rows(ResultSet {
+                    columns: info
+                        .expressions
+                        .iter()
+                        .map(|expression| expression.output_name())
+                        .collect(),
+                    rows,
+                }))
+            }
+            _ => {
+                let (rows, columns) = self.build(root)?;
+
+                Ok(QueryResult::Rows(ResultSet {
+                    columns: columns.into_iter().map(|column| column.name).collect(),
+                    rows: rows.collect::<Result<_, _>>()?,
+                }))
+            }
+        }
+    }
+
+    /// Describes the plan, one row per operator. To analyze, the statement
+    /// is run, changes included, and each operator that ran is annotated.
+    fn explain(
+        &mut self,
+        analyze: bool,
+        node: &LogicalNode,
+    ) -> Result<QueryResult, ExecutionError> {
+        let lines = if analyze {
+            let profile = Rc::new(Profile::default());
+            self.profile = Some(profile.clone());
+
+            let started = Instant::now();
+            let result = self.execute_node(node);
+            self.profile = None;
+
+            // The statement itself is not built when it modifies a table.
+            if profile.stats(node).is_none() {
+                let rows = match result? {
+                    QueryResult::Rows(result_set) => result_set.rows.len(),
+                    QueryResult::RowsAffected(count) => count,
+                    QueryResult::Merged(merged) => {
+                        merged.inserted + merged.updated + merged.deleted
+                    }
+                    QueryResult::Done => 0,
+                };
+
+                profile.set_stats(
+                    node,
+                    OperatorStats {
+                        rows,
+                        loops: 1,
+                        elapsed: started.elapsed(),
+                    },
+                );
+            }
+
+            node.explain(&|node| {
+                Some(match profile.stats(node) {
+                    Some(stats) => stats.annotation(),
+                    None => "(never executed)".to_string(),
+                })
+            })
+        } else {
+            node.explain(&|_| None)
+        };
+
+        Ok(QueryResult::Rows(ResultSet {
+            columns: vec!["QUERY PLAN".to_string()],
+            rows: lines
+                .into_iter()
+                .map(|line| vec![Value::String(line)])
+                .collect(),
+        }))
+    }
+
+    /// Runs an INSERT, UPDATE or DELETE node, calling `on_row` with every
+    /// row it writes (or, for DELETE, removes).
+    fn modify(
+        &mut self,
+        node: &LogicalNode,
+        on_row: &mut dyn FnMut(&Row) -> Result<(), ExecutionError>,
+    ) -> Result<usize, ExecutionError> {
+        match &node.operator {
+            Operator::Insert(info) => self.insert(info, &node.children[0], on_row),
+            Operator::Update(info) => self.update(info, on_row),
+            Operator::Delete(info) => self.delete(info, on_row),
+            operator => panic!("Unexpected operator: {:?}", operator),
+        }
+    }
+
+    fn insert(
+        &mut self,
+        info: &InsertInfo,
+        source: &LogicalNode,
+        on_row: &mut dyn FnMut(&Row) -> Result<(), ExecutionError>,
+    ) -> Result<usize, ExecutionError> {
+        let table = self.catalog.table(&info.table.name).unwrap().clone();
+
+        let positions = column_positions(&table, &info.columns);
+
+        // Keys an ON CONFLICT clause handles; any other conflict is an error.
+        let arbiters: Vec<&[String]> = match &info.on_conflict {
+            Some(on_conflict) if on_conflict.columns.is_empty() => table.unique_keys(),
+            Some(on_conflict) => table
+                .unique_keys()
+                .into_iter()
+                .filter(|key| {
+                    key.iter().all(|name| {
+                        on_conflict
+                            .columns
+                            .iter()
+                            .any(|column| &column.name == name)
+                    })
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        let mut schema = self.table_columns(&table.name);
+        schema.extend(table.columns.iter().map(|column| Column {
+            table: Some(EXCLUDED.to_string()),
+            name: column.name.clone(),
+            typ: Some(column.typ),
+        }));
+
+        let (rows, _) = self.build(&Self::with_defaults(source, &table, &positions))?;
+
+        let mut count = 0;
+        let mut touched: HashSet<RowId> = HashSet::new();
+
+        for row in rows {
+            let full_row = full_row(&table, &positions, row?)?;
+
+            let (row_id, existing) = match self.find_conflict(&table, &arbiters, &full_row, None) {
+                Some(conflict) => conflict,
+                None => {
+                    self.check_constraints(&table, &full_row, None)?;
+
+                    on_row(&full_row)?;
+                    let row_id = self.storage.insert(&table.name, full_row);
+                    touched.insert(row_id);
+                    count += 1;
+
+                    continue;
+                }
+            };
+
+            let (assignments, predicate) = match &info.on_conflict.as_ref().unwrap().action {
+                ConflictAction::Nothing => continue,
+                ConflictAction::Update {
+                    assignments,
+                    predicate,
+                } => (assignments, predicate),
+            };
+
+            if !touched.insert(row_id) {
+                return Err(ExecutionError::CardinalityViolation);
+            }
+
+            let mut input = existing.clone();
+            input.extend(full_row);
+
+            if !matches(predicate, &schema, &input)? {
+                continue;
+            }
+
+            let new_row = assign(&table, assignments, &schema, &input, existing)?;
+            self.check_constraints(&table, &new_row, Some(row_id))?;
+
+            on_row(&new_row)?;
+            self.storage.update(&table.name, row_id, new_row);
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    fn update(
+        &mut self,
+        info: &UpdateInfo,
+        on_row: &mut dyn FnMut(&Row) -> Result<(), ExecutionError>,
+    ) -> Result<usize, ExecutionError> {
+        let table = self.catalog.table(&info.table.name).unwrap().clone();
+        let schema = self.table_columns(&table.name);
+
+        let mut count = 0;
+
+        for (row_id, row) in self.storage.scan(&table.name) {
+            if !matches(&info.predicate, &schema, &row)? {
+                continue;
+            }
+
+            let new_row = assign(&table, &info.assignments, &schema, &row, row.clone())?;
+            self.check_constraints(&table, &new_row, Some(row_id))?;
+
+            on_row(&new_row)?;
+            self.storage.update(&table.name, row_id, new_row);
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    fn bind_ctes(
+        &mut self,
+        info: &WithInfo,
+        children: &[LogicalNode],
+    ) -> Result<(), ExecutionError> {
+        for (cte, node) in info.ctes.iter().zip(children) {
+            let rows = if cte.recursive {
+                self.iterate(cte, node)?
+            } else {
+                self.build(node)?.0.collect::<Result<_, _>>()?
+            };
+
+            self.relations.insert(
+                cte.name.clone(),
+                Relation {
+                    columns: cte.columns.clone(),
+                    rows: Rc::new(rows),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates a recursive CTE: the anchor once, then the recursive term
+    /// over the rows added by the previous step until it adds none.
+    fn iterate(&mut self, cte: &Cte, node: &LogicalNode) -> Result<Vec<Row>, ExecutionError> {
+        let all = match &node.operator {
+            Operator::Union(info) => info.all,
+            operator => panic!("Unexpected operator: {:?}", operator),
+        };
+
+        let mut result: Vec<Row> = vec![];
+        let mut step = &node.children[0];
+
+        loop {
+            let (rows, _) = self.build(step)?;
+            let mut added = vec![];
+
+            for row in rows {
+                let row = row?;
+
+                if all || !result.contains(&row) {
+                    result.push(row.clone());
+                    added.push(row);
+                }
+            }
+
+            if added.is_empty() {
+                return Ok(result);
+            }
+
+            self.relations.insert(
+                cte.name.clone(),
+                Relation {
+                    columns: cte.columns.clone(),
+                    rows: Rc::new(added),
+                },
+            );
+            step = &node.children[1];
+        }
+    }
+
+    fn merge(
+        &mut self,
+        info: &MergeInfo,
+        source: &LogicalNode,
+    ) -> Result<MergeResult, ExecutionError> {
+        let table = self.catalog.table(&info.table.name).unwrap().clone();
+        let (rows, source_columns) = self.build(source)?;
+
+        let mut schema = self.table_columns(&table.name);
+        schema.extend(source_columns);
+
+        // Rows inserted by this statement are never matched by later source rows.
+        let targets: Vec<(RowId, Row)> = self.storage.scan(&table.name).collect();
+
+        let mut result = MergeResult::default();
+        let mut touched: HashSet<RowId> = HashSet::new();
+
+        for row in rows {
+            let source_row = row?;
+            let mut matched = false;
+
+            for (row_id, target_row) in &targets {
+                let mut input = target_row.clone();
+                input.extend(source_row.iter().cloned());
+
+                if !evaluate(&info.condition, &schema, &input)?.is_true() {
+                    continue;
+                }
+
+                matched = true;
+
+                if !touched.insert(*row_id) {
+                    return Err(ExecutionError::CardinalityViolation);
+                }
+
+                match first_clause(&info.clauses, true, &schema, &input)? {
+                    Some(MergeAction::Update(assignments)) => {
+                        let new_row =
+                            assign(&table, assignments, &schema, &input, target_row.clone())?;
+                        self.check_constraints(&table, &new_row, Some(*row_id))?;
+
+                        self.storage.update(&table.name, *row_id, new_row);
+                        result.updated += 1;
+                    }
+                    Some(MergeAction::Delete) => {
+                        self.storage.delete(&table.name, *row_id);
+                        result.deleted += 1;
+                    }
+                    Some(MergeAction::Nothing) | None => {}
+                    Some(action) => panic!("Unexpected action: {:?}", action),
+                }
+            }
+
+            if matched {
+                continue;
+            }
+
+            let mut input: Row = table.columns.iter().map(|_| Value::Null).collect();
+            input.extend(source_row);
+
+            if let Some(MergeAction::Insert { columns, values }) =
+                first_clause(&info.clauses, false, &schema, &input)?
+            {
+                let positions = column_positions(&table, columns);
+                let values = values
+                    .iter()
+                    .zip(&positions)
+                    .map(|(value, &position)| match value {
+                        Expression::Default => default_value(&table.columns[position]),
+                        value => evaluate(value, &schema, &input),
+                    })
+                    .collect::<Result<_, _>>()?;
+                let new_row = full_row(&table, &positions, values)?;
+                self.check_constraints(&table, &new_row, None)?;
+
+                self.storage.insert(&table.name, new_row);
+                result.inserted += 1;
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn delete(
+        &mut self,
+        info: &DeleteInfo,
+        on_row: &mut dyn FnMut(&Row) -> Result<(), ExecutionError>,
+    ) -> Result<usize, ExecutionError> {
+        let schema = self.table_columns(&info.table.name);
+
+        let mut count = 0;
+
+        for (row_id, row) in self.storage.scan(&info.table.name) {
+            if !matches(&info.predicate, &schema, &row)? {
+                continue;
+            }
+
+            on_row(&row)?;
+            self.storage.delete(&info.table.name, row_id);
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Finds a stored row, other than `skip`, that has the same values as
+    /// `row` in one of the given keys. Keys containing a NULL never conflict.
+    fn find_conflict(
+        &self,
+        table: &TableDefinition,
+        keys: &[&[String]],
+        row: &Row,
+        skip: Option<RowId>,
+    ) -> Option<(RowId, Row)> {
+        let keys: Vec<Vec<usize>> = keys
+            .iter()
+            .map(|key| {
+                key.iter()
+                    .map(|name| {
+                        table
+                            .columns
+                            .iter()
+                            .position(|column| &column.name == name)
+                            .unwrap()
+                    })
+                    .collect()
+            })
+            .filter(|positions: &Vec<usize>| {
+                positions.iter().all(|&position| !row[position].is_null())
+            })
+            .collect();
+
+        if keys.is_empty() {
+            return None;
+        }
+
+        self.storage
+            .scan(&table.name)
+            .filter(|(row_id, _)| Some(*row_id) != skip)
+            .find(|(_, existing)| {
+                keys.iter().any(|positions| {
+                    positions.iter().all(|&position| {
+                        existing[position].compare(&row[position]) == Some(Ordering::Equal)
+                    })
+                })
+            })
+    }
+
+    /// Checks a row about to be written, replacing `skip` if that is set.
+    fn check_constraints(
+        &self,
+        table: &TableDefinition,
+        row: &Row,
+        skip: Option<RowId>,
+    ) -> Result<(), ExecutionError> {
+        if let Some(column) = table
+            .columns
+            .iter()
+            .zip(row)
+            .find(|(column, value)| !column.nullable && value.is_null())
+            .map(|(column, _)| column)
+        {
+            return Err(ExecutionError::NotNullViolation(column.name.clone()));
+        }
+
+        match self.find_conflict(table, &table.unique_keys(), row, skip) {
+            Some(_) => Err(ExecutionError::UniqueViolation(table.name.clone())),
+            None => Ok(()),
+        }
+    }
+
+    fn table_columns(&self, name: &str) -> Vec<Column> {
+        self.catalog
+            .table(name)
+            .unwrap()
+            .columns
+            .iter()
+            .map(|column| Column {
+                table: Some(name.to_string()),
+                name: column.name.clone(),
+                typ: Some(column.typ),
+            })
+            .collect()
+    }
+
+    /// Replaces `DEFAULT` in an inserted VALUES list with the default of
+    /// the column at the same position of `positions`.
+    fn with_defaults(
+        source: &LogicalNode,
+        table: &TableDefinition,
+        positions: &[usize],
+    ) -> LogicalNode {
+        let mut source = source.clone();
+
+        if let Operator::Values(values) = &mut source.operator {
+            for row in values.rows.iter_mut() {
+                for (value, &position) in row.iter_mut().zip(positions) {
+                    if *value == Expression::Default {
+                        *value = table.columns[position]
+                            .default
+                            .clone()
+                            .unwrap_or(Expression::Constant(Literal::Null));
+                    }
+                }
+            }
+        }
+
+        source
+    }
+
+    fn build(&self, node: &LogicalNode) -> Result<(RowStream, Vec<Column>), ExecutionError> {
+        let profile = match &self.profile {
+            Some(profile) => profile,
+            None => return self.build_operator(node),
+        };
+
+        let started = Instant::now();
+        let (rows, columns) = self.build_operator(node)?;
+
+        Ok((profile.instrument(node, rows, started), columns))
+    }
+
+    fn build_operator(
+        &self,
+        node: &LogicalNode,
+    ) -> Result<(RowStream, Vec<Column>), ExecutionError> {
+        match &node.operator {
+            Operator::Read(info) if self.relations.contains_key(&info.table.name) => {
+                let relation = &self.relations[&info.table.name];
+
+                let columns = relation
+                    .columns
+                    .iter()
+                    .map(|name| Column {
+                        table: Some(info.table.name.clone()),
+                        name: name.clone(),
+                        typ: None,
+                    })
+                    .collect();
+
+                let rows = relation.rows.clone();
+
+                Ok((
+                    Box::new((0..rows.len()).map(move |i| Ok(rows[i].clone()))),
+                    columns,
+                ))
+            }
+            Operator::Read(info) => {
+                let columns = self.table_columns(&info.table.name);
+
+                let rows = self.storage.scan(&info.table.name).map(|(_, row)| Ok(row));
+
+                Ok((Box::new(rows), columns))
+            }
+            Operator::Values(info) => {
+                let rows: Vec<Result<Row, ExecutionError>> = info
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|expression| evaluate(expression, &[], &[]))
+                            .collect()
+                    })
+                    .collect();
+
+                let columns = match info.rows.first() {
+                    Some(row) => row.iter().map(|_| Column::new("?column?")).collect(),
+                    None => vec![],
+                };
+
+                Ok((Box::new(rows.into_iter()), columns))
+            }
+            Operator::Filter(info) => {
+                let (rows, columns) = self.build_input(&node.children)?;
+                let predicate = info.predicate.clone();
+                let schema = columns.clone();
+
+                let rows = rows.filter_map(move |row| match row {
+                    Ok(row) => match evaluate(&predicate, &schema, &row) {
+                        Ok(value) if value.is_true() => Some(Ok(row)),
+                        Ok(_) => None,
+                        Err(error) => Some(Err(error)),
+                    },
+                    Err(error) => Some(Err(error)),
+                });
+
+                Ok((Box::new(rows), columns))
+            }
+            Operator::Projection(info) => {
+                let (rows, schema) = self.build_input(&node.children)?;
+                let expressions = info.expressions.clone();
+
+                let columns = expressions
+                    .iter()
+                    .map(|expression| Column::new(&expression.output_name()))
+                    .collect();
+
+                let rows = rows.map(move |row| {
+                    let row = row?;
+
+                    expressions
+                        .iter()
+                        .map(|expression| evaluate(expression, &schema, &row))
+                        .collect()
+                });
+
+                Ok((Box::new(rows), columns))
+            }
+            Operator::Window(info) => {
+                let (rows, mut columns) = self.build_input(&node.children)?;
+                let mut rows: Vec<Row> = rows.collect::<Result<_, _>>()?;
+                let mut order: Vec<usize> = (0..rows.len()).collect();
+
+                for (index, function) in info.functions.iter().enumerate() {
+                    let (values, sorted) = window::evaluate_window(function, &columns, &rows)?;
+
+                    for (row, value) in rows.iter_mut().zip(values) {
+                        row.push(value);
+                    }
+
+                    columns.push(Column {
+                        table: Some(window_table(index)),
+                        name: function.function.name().to_string(),
+                        typ: None,
+                    });
+                    order = sorted;
+                }
+
+                // Rows come out in the order of the last window.
+                let rows: Vec<Row> = order.into_iter().map(|i| rows[i].clone()).collect();
+
+                Ok((Box::new(rows.into_iter().map(Ok)), columns))
+            }
+            Operator::Union(info) => {
+                let (lhs, columns) = self.build(&node.children[0])?;
+                let (rhs, _) = self.build(&node.children[1])?;
+
+                let rows = lhs.chain(rhs);
+
+                if info.all {
+                    return Ok((Box::new(rows), columns));
+                }
+
+                let mut seen: Vec<Row> = vec![];
+
+                let rows = rows.filter(move |row| match row {
+                    Ok(row) if seen.contains(row) => false,
+                    Ok(row) => {
+                        seen.push(row.clone());
+                        true
+                    }
+                    Err(_) => true,
+                });
+
+                Ok((Box::new(rows), columns))
+            }
+            Operator::Subquery(info) => {
+                let (rows, _) = self.build(&node.children[0])?;
+
+                let columns = info
+                    .columns
+                    .iter()
+                    .map(|name| Column {
+                        table: Some(info.name.clone()),
+                        name: name.clone(),
+                        typ: None,
+                    })
+                    .collect();
+
+                Ok((rows, columns))
+            }
+            operator => panic!("Unexpected operator: {:?}", operator),
+        }
+    }
+
+    /// Builds the cross product of the given inputs. The first input is
+    /// streamed, the others are materialised once and replayed for each row.
+    fn build_input(
+        &self,
+        children: &[LogicalNode],
+    ) -> Result<(RowStream, Vec<Column>), ExecutionError> {
+        let mut children = children.iter();
+
+        let (mut rows, mut columns): (RowStream, Vec<Column>) = match children.next() {
+            Some(child) => self.build(child)?,
+            None => (Box::new(std::iter::once(Ok(vec![]))), vec![]),
+        };
+
+        for child in children {
+            let (right_rows, right_columns) = self.build(child)?;
+            let right_rows: Rc<Vec<Row>> = Rc::new(right_rows.collect::<Result<_, _>>()?);
+
+            rows = Box::new(rows.flat_map(move |left| -> RowStream {
+                match left {
+                    Ok(left) => {
+                        let right_rows = right_rows.clone();
+
+                        Box::new((0..right_rows.len()).map(move |i| {
+                            let mut row = left.clone();
+                            row.extend(right_rows[i].iter().cloned());
+
+                            Ok(row)
+                        }))
+                    }
+                    Err(error) => Box::new(std::iter::once(Err(error))),
+                }
+            }));
+
+            columns.extend(right_columns);
+        }
+
+        Ok((rows, columns))
+    }
+}
+
+fn is_modification(node: &LogicalNode) -> bool {
+    matches!(
+        node.operator,
+        Operator::Insert(_) | Operator::Update(_) | Operator::Delete(_)
+    )
+}
+
+fn modified_table(node: &LogicalNode) -> &str {
+    match &node.operator {
+        Operator::Insert(info) => &info.table.name,
+        Operator::Update(info) => &info.table.name,
+        Operator::Delete(info) => &info.table.name,
+        operator => panic!("Unexpected operator: {:?}", operator),
+    }
+}
+
+fn position(schema: &[Column], column: &Column) -> usize {
+    schema
+        .iter()
+        .position(|candidate| {
+            candidate.name == column.name
+                && (column.table.is_none() || candidate.table == column.table)
+        })
+        .unwrap_or_else(|| panic!("Unknown column: {}", column))
+}
+
+/// The action of the first WHEN [NOT] MATCHED clause whose condition holds.
+fn first_clause<'c>(
+    clauses: &'c [MergeClause],
+    matched: bool,
+    schema: &[Column],
+    input: &[Value],
+) -> Result<Option<&'c MergeAction>, ExecutionError> {
+    for clause in clauses.iter().filter(|clause| clause.matched == matched) {
+        if matches(&clause.predicate, schema, input)? {
+            return Ok(Some(&clause.action));
+        }
+    }
+
+    Ok(None)
+}
+
+fn column_positions(table: &TableDefinition, columns: &[Column]) -> Vec<usize> {
+    columns
+        .iter()
+        .map(|column| {
+            table
+                .columns
+                .iter()
+                .position(|definition| definition.name == column.name)
+                .unwrap()
+        })
+        .collect()
+}
+
+/// Builds a row of `table` from values for the columns at `positions`. The
+/// other columns get their defaults.
+fn full_row(
+    table: &TableDefinition,
+    positions: &[usize],
+    values: Row,
+) -> Result<Row, ExecutionError> {
+    let mut row: Vec<Option<Value>> = table.columns.iter().map(|_| None).collect();
+
+    for (value, &position) in values.into_iter().zip(positions) {
+        row[position] = Some(value);
+    }
+
+    row.into_iter()
+        .zip(&table.columns)
+        .map(|(value, column)| {
+            let value = match value {
+                Some(value) => value,
+                None => default_value(column)?,
+            };
+
+            Ok(value.coerce(column.typ))
+        })
+        .collect()
+}
+
+/// The value of the column's default, NULL if it has none.
+fn default_value(column: &ColumnDefinition) -> Result<Value, ExecutionError> {
+    match &column.default {
+        Some(default) => evaluate(default, &[], &[]),
+        None => Ok(Value::Null),
+    }
+}
+
+/// Applies SET assignments evaluated against `input` to `target`, a row of
+/// `table`.
+fn assign(
+    table: &TableDefinition,
+    assignments: &[Assignment],
+    schema: &[Column],
+    input: &[Value],
+    mut target: Row,
+) -> Result<Row, ExecutionError> {
+    for assignment in assignments {
+        let position = table
+            .columns
+            .iter()
+            .position(|column| column.name == assignment.column.name)
+            .unwrap();
+        let value = evaluate(&assignment.expression, schema, input)?;
+
+        target[position] = value.coerce(table.columns[position].typ);
+    }
+
+    Ok(target)
+}
+
+/// Whether a row passes an optional WHERE predicate.
+fn matches(
+    predicate: &Option<Expression>,
+    schema: &[Column],
+    row: &[Value],
+) -> Result<bool, ExecutionError> {
+    match predicate {
+        Some(predicate) => Ok(evaluate(predicate, schema, row)?.is_true()),
+        None => Ok(true),
+    }
+}
+
+pub fn evaluate(
+    expression: &Expression,
+    schema: &[Column],
+    row: &[Value],
+) -> Result<Value, ExecutionError> {
+    match expression {
+        Expression::Constant(literal) => Ok(Value::from_literal(literal)),
+        Expression::Column(column) => Ok(row[position(schema, column)].clone()),
+        Expression::Default => Ok(Value::Null),
+        Expression::Parameter(index) => panic!("Unbound parameter: ${}", index + 1),
+        Expression::Unary(op, operand) => {
+            let operand = evaluate(operand, schema, row)?;
+
+            match (op, operand) {
+                (Op::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+                (_, Value::Null) => Ok(Value::Null),
+                (Op::Minus, Value::Int(i)) => i
+                    .checked_neg()
+                    .map(Value::Int)
+                    .ok_or(ExecutionError::NumericOverflow),
+                (Op::Minus, Value::Float(f)) => Ok(Value::Float(-f)),
+                (Op::Minus, Value::Decimal(d)) => d
+                    .checked_neg()
+                    .map(Value::Decimal)
+                    .ok_or(ExecutionError::NumericOverflow),
+                (Op::Minus, Value::Interval(i)) => Ok(Value::Interval(i.negate())),
+                (
+                    Op::Plus,
+                    operand @ (Value::Int(_)
+                    | Value::Float(_)
+                    | Value::Decimal(_)
+                    | Value::Interval(_)),
+                ) => Ok(operand),
+                (op, operand) => Err(ExecutionError::TypeMismatch(format!(
+                    "{:?} {:?}",
+                    op, operand
+                ))),
+            }
+        }
+        Expression::Binary(op, lhs, rhs) => {
+            let lhs = evaluate(lhs, schema, row)?;
+            let rhs = evaluate(rhs, schema, row)?;
+
+            evaluate_binary(*op, lhs, rhs)
+        }
+    }
+}
+
+fn is_temporal(typ: Type) -> bool {
+    matches!(
+        typ,
+        Type::Date | Type::Time | Type::Timestamp | Type::Interval
+    )
+}
+
+/// Arithmetic on dates, times and intervals. Neither side is NULL.
+fn evaluate_temporal(op: Op, lhs: Value, rhs: Value) -> Result<Value, ExecutionError> {
+    let interval = |interval: Interval| match op {
+        Op::Minus => interval.negate(),
+        _ => interval,
+    };
+
+    let result = match (&lhs, op, &rhs) {
+        (Value::Date(d), Op::Plus | Op::Minus, Value::Int(days)) => {
+            let days = match op {
+                Op::Minus => days.checked_neg(),
+                _ => Some(*days),
+            };
+
+            days.ok_or(TemporalError::OutOfRange)
+                .and_then(|days| d.add_days(days))
+                .map(Value::Date)
+        }
+        (Value::Int(days), Op::Plus, Value::Date(d)) => d.add_days(*days).map(Value::Date),
+        (Value::Date(l), Op::Minus, Value::Date(r)) => Ok(Value::Int(l.days_since(*r))),
+        (Value::Date(d), Op::Plus | Op::Minus, Value::Interval(i)) => d
+            .to_timestamp()
+            .add_interval(interval(*i))
+            .map(Value::Timestamp),
+        (Value::Timestamp(t), Op::Plus | Op::Minus, Value::Interval(i)) => {
+            t.add_interval(interval(*i)).map(Value::Timestamp)
+        }
+        (Value::Interval(i), Op::Plus, Value::Date(d)) => {
+            d.to_timestamp().add_interval(*i).map(Value::Timestamp)
+        }
+        (Value::Interval(i), Op::Plus, Value::Timestamp(t)) => {
+            t.add_interval(*i).map(Value::Timestamp)
+        }
+        (Value::Time(t), Op::Plus | Op::Minus, Value::Interval(i)) => {
+            Ok(Value::Time(t.add_interval(interval(*i))))
+        }
+        (Value::Interval(i), Op::Plus, Value::Time(t)) => Ok(Value::Time(t.add_interval(*i))),
+        (Value::Timestamp(l), Op::Minus, Value::Timestamp(r)) => Ok(Value::Interval(l.since(*r))),
+        (Value::Time(l), Op::Minus, Value::Time(r)) => Ok(Value::Interval(l.since(*r))),
+        (Value::Interval(l), Op::Plus | Op::Minus, Value::Interval(r)) => {
+            l.add(interval(*r)).map(Value::Interval)
+        }
+        _ => {
+            return Err(ExecutionError::TypeMismatch(format!(
+                "{:?} {:?} {:?}",
+                lhs, op, rhs
+            )))
+        }
+    };
+
+    result.map_err(ExecutionError::Temporal)
+}
+
+/// Exact arithmetic on two decimals, or on a decimal and an integer.
+fn evaluate_decimal(op: Op, lhs: Decimal, rhs: Decimal) -> Result<Value, ExecutionError> {
+    let result = match op {
+        Op::Plus => lhs.checked_add(rhs),
+        Op::Minus => lhs.checked_sub(rhs),
+        Op::Multiply => lhs.checked_mul(rhs),
+        _ if rhs.is_zero() => return Err(ExecutionError::DivisionByZero),
+        Op::Divide => lhs.checked_div(rhs),
+        _ => lhs.checked_rem(rhs),
+    };
+
+    result
+        .map(Value::Decimal)
+        .ok_or(ExecutionError::NumericOverflow)
+}
+
+fn evaluate_binary(op: Op, lhs: Value, rhs: Value) -> Result<Value, ExecutionError> {
+    match op {
+        Op::And => match (lhs, rhs) {
+            (Value::Bool(false), _) | (_, Value::Bool(false)) => Ok(Value::Bool(false)),
+            (Value::Bool(true), Value::Bool(true)) => Ok(Value::Bool(true)),
+            _ => Ok(Value::Null),
+        },
+        Op::Or => match (lhs, rhs) {
+            (Value::Bool(true), _) | (_, Value::Bool(true)) => Ok(Value::Bool(true)),
+            (Value::Bool(false), Value::Bool(false)) => Ok(Value::Bool(false)),
+            _ => Ok(Value::Null),
+        },
+        Op::Equals
+        | Op::NotEquals
+        | Op::LessThan
+        | Op::GreaterThan
+        | Op::LessThanOrEquals
+        | Op::GreaterThanOrEquals => {
+            if lhs.is_null() || rhs.is_null() {
+                return Ok(Value::Null);
+            }
+
+            let ordering = match lhs.compare(&rhs) {
+                Some(ordering) => ordering,
+                None => {
+                    return Err(ExecutionError::TypeMismatch(format!(
+                        "{:?} {:?} {:?}",
+                        lhs, op, rhs
+                    )))
+                }
+            };
+
+            Ok(Value::Bool(match op {
+                Op::Equals => ordering == Ordering::Equal,
+                Op::NotEquals => ordering != Ordering::Equal,
+                Op::LessThan => ordering == Ordering::Less,
+                Op::GreaterThan => ordering == Ordering::Greater,
+                Op::LessThanOrEquals => ordering != Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }))
+        }
+        Op::Plus | Op::Minus | Op::Multiply | Op::Divide | Op::Modulo => match (lhs, rhs) {
+            (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+            (Value::Int(l), Value::Int(r)) => {
+                let result = match op {
+                    Op::Plus => l.checked_add(r),
+                    Op::Minus => l.checked_sub(r),
+                    Op::Multiply => l.checked_mul(r),
+                    _ if r == 0 => return Err(ExecutionError::DivisionByZero),
+                    Op::Divide => l.checked_div(r),
+                    _ => l.checked_rem(r),
+                };
+
+                result
+                    .map(Value::Int)
+                    .ok_or(ExecutionError::NumericOverflow)
+            }
+            (lhs, rhs)
+                if lhs.typ().is_some_and(is_temporal) || rhs.typ().is_some_and(is_temporal) =>
+            {
+                evaluate_temporal(op, lhs, rhs)
+            }
+            (lhs, rhs) => {
+                if let (Some(l), Some(r)) = (lhs.to_decimal(), rhs.to_decimal()) {
+                    return evaluate_decimal(op, l, r);
+                }
+
+                let (l, r) = match (
+                    lhs.clone().coerce(Type::Float),
+                    rhs.clone().coerce(Type::Float),
+                ) {
+                    (Value::Float(l), Value::Float(r)) => (l, r),
+                    _ => {
+                        return Err(ExecutionError::TypeMismatch(format!(
+                            "{:?} {:?} {:?}",
+                            lhs, op, rhs
+                        )))
+                    }
+                };
+
+                match op {
+                    Op::Plus => Ok(Value::Float(l + r)),
+                    Op::Minus => Ok(Value::Float(l - r)),
+                    Op::Multiply => Ok(Value::Float(l * r)),
+                    _ if r == 0.0 => Err(ExecutionError::DivisionByZero),
+                    Op::Divide => Ok(Value::Float(l / r)),
+                    _ => Ok(Value::Float(l % r)),
+                }
+            }
+        },
+        Op::Concat => match (lhs.to_text(), rhs.to_text()) {
+            (Some(l), Some(r)) => Ok(Value::String(l + &r)),
+            _ => Ok(Value::Null),
+        },
+        op => panic!("Unexpected binary operator: {:?}", op),
+    }
+}
@@ -0,0 +1,95 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::analyzer::LogicalNode;
+use crate::executor::value::Row;
+use crate::executor::{ExecutionError, RowStream};
+
+/// What an operator did during EXPLAIN ANALYZE.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct OperatorStats {
+    /// Rows produced, over all loops.
+    pub rows: usize,
+    /// Times the operator was started.
+    pub loops: usize,
+    /// Time spent in the operator and its inputs.
+    pub elapsed: Duration,
+}
+
+impl OperatorStats {
+    pub fn annotation(&self) -> String {
+        format!(
+            "(rows={} loops={} time={:.3}ms)",
+            self.rows,
+            self.loops,
+            self.elapsed.as_secs_f64() * 1000.0
+        )
+    }
+}
+
+/// Statistics of the operators of a plan being run, by node. Nodes are
+/// told apart by address, so the plan must not move while it is profiled.
+#[derive(Debug, Default)]
+pub struct Profile {
+    operators: RefCell<HashMap<*const LogicalNode, Rc<RefCell<OperatorStats>>>>,
+}
+
+impl Profile {
+    pub fn stats(&self, node: &LogicalNode) -> Option<OperatorStats> {
+        self.operators
+            .borrow()
+            .get(&(node as *const LogicalNode))
+            .map(|stats| stats.borrow().clone())
+    }
+
+    pub fn set_stats(&self, node: &LogicalNode, stats: OperatorStats) {
+        self.operators
+            .borrow_mut()
+            .insert(node as *const LogicalNode, Rc::new(RefCell::new(stats)));
+    }
+
+    /// Counts a new loop of the operator and wraps its rows so that they
+    /// are counted and timed as they are pulled. `started` is when the
+    /// operator began building, which may already have consumed input.
+    pub fn instrument(&self, node: &LogicalNode, rows: RowStream, started: Instant) -> RowStream {
+        let stats = self
+            .operators
+            .borrow_mut()
+            .entry(node as *const LogicalNode)
+            .or_default()
+            .clone();
+
+        {
+            let mut stats = stats.borrow_mut();
+            stats.loops += 1;
+            stats.elapsed += started.elapsed();
+        }
+
+        Box::new(Profiled { rows, stats })
+    }
+}
+
+struct Profiled {
+    rows: RowStream,
+    stats: Rc<RefCell<OperatorStats>>,
+}
+
+impl Iterator for Profiled {
+    type Item = Result<Row, ExecutionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let started = Instant::now();
+        let row = self.rows.next();
+
+        let mut stats = self.stats.borrow_mut();
+        stats.elapsed += started.elapsed();
+
+        if let Some(Ok(_)) = row {
+            stats.rows += 1;
+        }
+
+        row
+    }
+}
@@ -0,0 +1,150 @@
+use std::cmp::Ordering;
+
+use crate::parser::{Decimal, Literal, Type};
+use crate::temporal::{Date, Interval, Time, Timestamp};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    Null,
+    Int(i64),
+    Float(f64),
+    Decimal(Decimal),
+    String(String),
+    Bool(bool),
+    Date(Date),
+    Time(Time),
+    Timestamp(Timestamp),
+    Interval(Interval),
+}
+
+pub type Row = Vec<Value>;
+
+impl Value {
+    pub fn from_literal(literal: &Literal) -> Value {
+        match literal {
+            Literal::Numeric(i) => Value::Int(*i),
+            Literal::Float(f) => Value::Float(*f),
+            Literal::Decimal(d) => Value::Decimal(*d),
+            Literal::String(s) => Value::String(s.clone()),
+            Literal::Bool(b) => Value::Bool(*b),
+            Literal::Date(d) => Value::Date(*d),
+            Literal::Time(t) => Value::Time(*t),
+            Literal::Timestamp(t) => Value::Timestamp(*t),
+            Literal::Interval(i) => Value::Interval(*i),
+            Literal::Null | Literal::Default => Value::Null,
+            _ => panic!("Unexpected literal: {:?}", literal),
+        }
+    }
+
+    /// The literal standing for the value in a plan.
+    pub fn to_literal(&self) -> Literal {
+        match self {
+            Value::Null => Literal::Null,
+            Value::Int(i) => Literal::Numeric(*i),
+            Value::Float(f) => Literal::Float(*f),
+            Value::Decimal(d) => Literal::Decimal(*d),
+            Value::String(s) => Literal::String(s.clone()),
+            Value::Bool(b) => Literal::Bool(*b),
+            Value::Date(d) => Literal::Date(*d),
+            Value::Time(t) => Literal::Time(*t),
+            Value::Timestamp(t) => Literal::Timestamp(*t),
+            Value::Interval(i) => Literal::Interval(*i),
+        }
+    }
+
+    pub fn typ(&self) -> Option<Type> {
+        match self {
+            Value::Null => None,
+            Value::Int(_) => Some(Type::Int),
+            Value::Float(_) => Some(Type::Float),
+            Value::Decimal(_) => Some(Type::Decimal),
+            Value::String(_) => Some(Type::String),
+            Value::Bool(_) => Some(Type::Bool),
+            Value::Date(_) => Some(Type::Date),
+            Value::Time(_) => Some(Type::Time),
+            Value::Timestamp(_) => Some(Type::Timestamp),
+            Value::Interval(_) => Some(Type::Interval),
+        }
+    }
+
+    /// Converts the value to the representation used by a column of the given
+    /// type. The analyzer has already checked that the types are compatible.
+    pub fn coerce(self, typ: Type) -> Value {
+        match (self, typ) {
+            (Value::Int(i), Type::Float) => Value::Float(i as f64),
+            (Value::Int(i), Type::Decimal) => Value::Decimal(Decimal::from(i)),
+            (Value::Decimal(d), Type::Float) => Value::Float(d.to_f64()),
+            (Value::Date(d), Type::Timestamp) => Value::Timestamp(d.to_timestamp()),
+            (value, _) => value,
+        }
+    }
+
+    /// The value as `||` writes it. `None` for NULL.
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            Value::Null => None,
+            Value::Int(i) => Some(i.to_string()),
+            Value::Float(f) => Some(f.to_string()),
+            Value::Decimal(d) => Some(d.to_string()),
+            Value::String(s) => Some(s.clone()),
+            Value::Bool(b) => Some(b.to_string()),
+            Value::Date(d) => Some(d.to_string()),
+            Value::Time(t) => Some(t.to_string()),
+            Value::Timestamp(t) => Some(t.to_string()),
+            Value::Interval(i) => Some(i.to_string()),
+        }
+    }
+
+    /// The exact number for an integer or a decimal.
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        match self {
+            Value::Int(i) => Some(Decimal::from(*i)),
+            Value::Decimal(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        *self == Value::Null
+    }
+
+    pub fn is_true(&self) -> bool {
+        *self == Value::Bool(true)
+    }
+
+    /// SQL comparison: `None` if either side is NULL or the values are not
+    /// comparable.
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Int(l), Value::Int(r)) => Some(l.cmp(r)),
+            (Value::Int(l), Value::Float(r)) => (*l as f64).partial_cmp(r),
+            (Value::Float(l), Value::Int(r)) => l.partial_cmp(&(*r as f64)),
+            (Value::Float(l), Value::Float(r)) => l.partial_cmp(r),
+            (Value::Decimal(l), Value::Decimal(r)) => Some(l.compare(*r)),
+            (Value::Int(l), Value::Decimal(r)) => Some(Decimal::from(*l).compare(*r)),
+            (Value::Decimal(l), Value::Int(r)) => Some(l.compare(Decimal::from(*r))),
+            (Value::Decimal(l), Value::Float(r)) => l.to_f64().partial_cmp(r),
+            (Value::Float(l), Value::Decimal(r)) => l.partial_cmp(&r.to_f64()),
+            (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+            (Value::Bool(l), Value::Bool(r)) => Some(l.cmp(r)),
+            (Value::Date(l), Value::Date(r)) => Some(l.cmp(r)),
+            (Value::Date(l), Value::Timestamp(r)) => Some(l.to_timestamp().cmp(r)),
+            (Value::Timestamp(l), Value::Date(r)) => Some(l.cmp(&r.to_timestamp())),
+            (Value::Time(l), Value::Time(r)) => Some(l.cmp(r)),
+            (Value::Timestamp(l), Value::Timestamp(r)) => Some(l.cmp(r)),
+            (Value::Interval(l), Value::Interval(r)) => Some(l.compare(r)),
+            _ => None,
+        }
+    }
+
+    /// Total order used for sorting: NULLs come after all other values, and
+    /// values that cannot be compared are treated as equal.
+    pub fn sort_cmp(&self, other: &Value) -> Ordering {
+        match (self.is_null(), other.is_null()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.compare(other).unwrap_or(Ordering::Equal),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::cmp::Ordering;
+
+use crate::analyzer::{
+    Column, Expression, FrameBound, SortKey, WindowFunction, WindowFunctionKind,
+};
+use crate::parser::Op;
+
+use super::value::{Row, Value};
+use super::{evaluate, evaluate_binary, ExecutionError};
+
+/// Computes a window function for every row. Returns the values in input
+/// order, and the input positions sorted by partition and ORDER BY.
+pub fn evaluate_window(
+    function: &WindowFunction,
+    schema: &[Column],
+    rows: &[Row],
+) -> Result<(Vec<Value>, Vec<usize>), ExecutionError> {
+    let evaluate_all = |expressions: Vec<&Expression>| {
+        rows.iter()
+            .map(|row| {
+                expressions
+                    .iter()
+                    .map(|expression| evaluate(expression, schema, row))
+                    .collect::<Result<Row, _>>()
+            })
+            .collect::<Result<Vec<Row>, _>>()
+    };
+
+    let partition_keys = evaluate_all(function.partition_by.iter().collect())?;
+    let order_keys = evaluate_all(
+        function
+            .order_by
+            .iter()
+            .map(|key| &key.expression)
+            .collect(),
+    )?;
+
+    let mut order: Vec<usize> = (0..rows.len()).collect();
+
+    order.sort_by(|&a, &b| {
+        compare_keys(&partition_keys[a], &partition_keys[b], &[])
+            .then_with(|| compare_keys(&order_keys[a], &order_keys[b], &function.order_by))
+    });
+
+    let mut values = vec![Value::Null; rows.len()];
+    let mut start = 0;
+
+    while start < order.len() {
+        let mut end = start + 1;
+
+        while end < order.len()
+            && compare_keys(
+                &partition_keys[order[start]],
+                &partition_keys[order[end]],
+                &[],
+            ) == Ordering::Equal
+        {
+            end += 1;
+        }
+
+        let partition = Partition {
+            function,
+            schema,
+            rows: order[start..end].iter().map(|&i| &rows[i]).collect(),
+            order_keys: order[start..end].iter().map(|&i| &order_keys[i]).collect(),
+        };
+
+        for (position, &i) in order[start..end].iter().enumerate() {
+            values[i] = partition.evaluate(position)?;
+        }
+
+        start = end;
+    }
+
+    Ok((values, order))
+}
+
+/// Compares sort keys; keys without a matching `SortKey` sort ascending.
+fn compare_keys(lhs: &[Value], rhs: &[Value], sort_keys: &[SortKey]) -> Ordering {
+    for (i, (lhs, rhs)) in lhs.iter().zip(rhs).enumerate() {
+        let ordering = match sort_keys.get(i) {
+            Some(SortKey {
+                ascending: false, ..
+            }) => rhs.sort_cmp(lhs),
+            _ => lhs.sort_cmp(rhs),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// The rows of one partition, in ORDER BY order.
+struct Partition<'a> {
+    function: &'a WindowFunction,
+    schema: &'a [Column],
+    rows: Vec<&'a Row>,
+    order_keys: Vec<&'a Row>,
+}
+
+impl<'a> Partition<'a> {
+    fn evaluate(&self, position: usize) -> Result<Value, ExecutionError> {
+        let function = self.function;
+        let row = self.rows[position];
+
+        match function.function {
+            WindowFunctionKind::RowNumber => Ok(Value::Int(position as i64 + 1)),
+            WindowFunctionKind::Rank => Ok(Value::Int(self.first_peer(position) as i64 + 1)),
+            WindowFunctionKind::DenseRank => {
+                let groups = (1..=position)
+                    .filter(|&i| !self.are_peers(i - 1, i))
+                    .count();
+
+                Ok(Value::Int(groups as i64 + 1))
+            }
+            WindowFunctionKind::Lag | WindowFunctionKind::Lead => {
+                let offset = match function.arguments.get(1) {
+                    Some(offset) => match evaluate(offset, self.schema, row)? {
+                        Value::Int(offset) => offset,
+                        Value::Null => return Ok(Value::Null),
+                        value => return Err(ExecutionError::TypeMismatch(format!("{:?}", value))),
+                    },
+                    None => 1,
+                };
+
+                let target = match function.function {
+                    WindowFunctionKind::Lag => position as i64 - offset,
+                    _ => position as i64 + offset,
+                };
+
+                if target >= 0 && (target as usize) < self.rows.len() {
+                    evaluate(
+                        &function.arguments[0],
+                        self.schema,
+                        self.rows[target as usize],
+                    )
+                } else {
+                    match function.arguments.get(2) {
+                        Some(default) => evaluate(default, self.schema, row),
+                        None => Ok(Value::Null),
+                    }
+                }
+            }
+            WindowFunctionKind::FirstValue => match self.frame(position) {
+                Some((start, _)) => evaluate(&function.arguments[0], self.schema, self.rows[start]),
+                None => Ok(Value::Null),
+            },
+            WindowFunctionKind::Sum => {
+                let (start, end) = match self.frame(position) {
+                    Some(frame) => frame,
+                    None => return Ok(Value::Null),
+                };
+
+                let mut sum = Value::Null;
+
+                for row in &self.rows[start..=end] {
+                    let value = evaluate(&function.arguments[0], self.schema, row)?;
+
+                    sum = match (sum, value) {
+                        (sum, Value::Null) => sum,
+                        (Value::Null, value) => value,
+                        (sum, value) => evaluate_binary(Op::Plus, sum, value)?,
+                    };
+                }
+
+                Ok(sum)
+            }
+        }
+    }
+
+    fn are_peers(&self, lhs: usize, rhs: usize) -> bool {
+        compare_keys(
+            self.order_keys[lhs],
+            self.order_keys[rhs],
+            &self.function.order_by,
+        ) == Ordering::Equal
+    }
+
+    fn first_peer(&self, position: usize) -> usize {
+        (0..position)
+            .rev()
+            .take_while(|&i| self.are_peers(i, position))
+            .last()
+            .unwrap_or(position)
+    }
+
+    fn last_peer(&self, position: usize) -> usize {
+        (position + 1..self.rows.len())
+            .take_while(|&i| self.are_peers(i, position))
+            .last()
+            .unwrap_or(position)
+    }
+
+    /// First and last position of the frame of the row at `position`, or
+    /// `None` if the frame is empty.
+    fn frame(&self, position: usize) -> Option<(usize, usize)> {
+        let last = self.rows.len() as i64 - 1;
+
+        let (start, end) = match &self.function.frame {
+            None if self.function.order_by.is_empty() => (0, last),
+            None => (0, self.last_peer(position) as i64),
+            Some(frame) => (
+                bound(frame.start, position, last),
+                bound(frame.end, position, last),
+            ),
+        };
+
+        let (start, end) = (start.max(0), end.min(last));
+
+        if start > end {
+            return None;
+        }
+
+        Some((start as usize, end as usize))
+    }
+}
+
+fn bound(bound: FrameBound, position: usize, last: i64) -> i64 {
+    let position = position as i64;
+
+    match bound {
+        FrameBound::UnboundedPreceding => 0,
+        FrameBound::Preceding(offset) => position - offset as i64,
+        FrameBound::CurrentRow => position,
+        FrameBound::Following(offset) => position + offset as i64,
+        FrameBound::UnboundedFollowing => last,
+    }
+}
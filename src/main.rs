@@ -1,5 +1,11 @@
-mod parser;
 mod analyzer;
+mod catalog;
+mod executor;
+mod parser;
+mod session;
+mod storage;
+mod temporal;
+mod transaction;
 
 fn main() {
     print!("{}", 1);
@@ -7,7 +13,7 @@ fn main() {
 
     let ast = parser::Parser::new(lexer).parse();
 
-    // let logical_plan = analyzer::Analyzer::new().analyze(&ast);
+    // let logical_plan = analyzer::Analyzer::new(&catalog::Catalog::new()).analyze(&ast);
 
     print!("{:?}", ast);
-}
\ No newline at end of file
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use super::lexer::LexError;
+use super::Span;
+use crate::temporal::TemporalError;
+
+/// Why a statement cannot be parsed, and where in its SQL.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// The token the error was found at.
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseErrorKind {
+    /// A token that cannot come where it is, as written.
+    UnexpectedToken(String),
+    /// The statement stops where more is expected.
+    UnexpectedEnd,
+    /// Input that is not a token.
+    Lex(LexError),
+    /// A typed literal of an invalid value, such as `DATE '2023-02-29'`.
+    Temporal(TemporalError),
+    /// A `$` parameter without a number from 1 up.
+    InvalidParameter(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnexpectedToken(token) => write!(f, "Unexpected token: {}", token),
+            ParseErrorKind::UnexpectedEnd => write!(f, "Unexpected end of input"),
+            ParseErrorKind::Lex(error) => write!(f, "{}", error),
+            ParseErrorKind::Temporal(error) => write!(f, "{}", error),
+            ParseErrorKind::InvalidParameter(parameter) => {
+                write!(f, "Invalid parameter: {}", parameter)
+            }
+        }?;
+
+        write!(f, " at {}..{}", self.span.start, self.span.end)
+    }
+}
@@ -0,0 +1,454 @@
+use super::lexer::tokens::Token;
+use super::lexer::{LexError, Lexer};
+
+/// Pretty-prints SQL text: keywords in upper case, each clause on a line
+/// of its own with its items indented under it, one item per line.
+/// Comments are kept where they were, either at the end of a line or on
+/// lines of their own. Statements are separated by a blank line.
+pub fn format_sql(input: &str) -> Result<String, LexError> {
+    let tokens = trivia_tokens(input)?;
+    let mut formatter = Formatter::new();
+
+    for (i, (token, text, newline_before)) in tokens.iter().enumerate() {
+        let next = tokens[i + 1..]
+            .iter()
+            .map(|(token, _, _)| token)
+            .find(|token| !is_comment(token));
+
+        formatter.token(token, text, *newline_before, next);
+    }
+
+    Ok(formatter.finish())
+}
+
+/// The tokens of `input` other than whitespace, with their text and
+/// whether a line break came before them.
+fn trivia_tokens(input: &str) -> Result<Vec<(Token<'_>, &str, bool)>, LexError> {
+    let mut lexer = Lexer::with_trivia(input);
+    let mut tokens = vec![];
+    let mut newline_before = false;
+
+    while let Some(token) = lexer.next() {
+        match token? {
+            Token::Whitespace(whitespace) => newline_before |= whitespace.contains('\n'),
+            token => {
+                tokens.push((token, lexer.text(), newline_before));
+                newline_before = false;
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// The statement or the parentheses the formatter is in.
+#[derive(Clone, Copy)]
+struct Frame {
+    /// A statement or subquery, whose clauses go on lines of their own.
+    /// Otherwise everything in the parentheses stays on one line.
+    statement: bool,
+    /// Indentation of the clause keywords.
+    base: usize,
+    /// Whether a clause has started, so that its items are indented and
+    /// separated by line breaks.
+    in_clause: bool,
+}
+
+struct Formatter<'a> {
+    output: String,
+    frames: Vec<Frame>,
+    previous: Option<&'a Token<'a>>,
+    before_previous: Option<&'a Token<'a>>,
+    /// A clause keyword was written and its items have not started yet.
+    header: bool,
+    /// The previous token is a sign rather than an operator.
+    unary: bool,
+    /// Indentation of the line the next token must start.
+    pending_break: Option<usize>,
+    /// The next line break leaves a blank line, after a statement.
+    blank_line: bool,
+}
+
+impl<'a> Formatter<'a> {
+    fn new() -> Self {
+        Formatter {
+            output: String::new(),
+            frames: vec![Frame {
+                statement: true,
+                base: 0,
+                in_clause: false,
+            }],
+            previous: None,
+            before_previous: None,
+            header: false,
+            unary: false,
+            pending_break: None,
+            blank_line: false,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+
+        self.output
+    }
+
+    fn token(
+        &mut self,
+        token: &'a Token<'a>,
+        text: &str,
+        newline_before: bool,
+        next: Option<&Token>,
+    ) {
+        if is_comment(token) {
+            return self.comment(token, text, newline_before, next);
+        }
+
+        let text = match is_keyword(token) {
+            true => text.to_uppercase(),
+            false => text.to_string(),
+        };
+        let Frame {
+            statement, base, ..
+        } = *self.frames.last().unwrap();
+
+        if statement && is_clause(token) && !self.is_continuation(token) {
+            self.pending_break = Some(base);
+            self.write(&text, true);
+            self.header = true;
+            self.frames.last_mut().unwrap().in_clause = true;
+        } else if self.header && self.is_continuation(token) {
+            self.write(&text, true);
+        } else {
+            if self.header {
+                self.header = false;
+                self.pending_break = Some(base + 2);
+            } else if statement && self.is_line_start(token) {
+                self.pending_break = Some(self.indent());
+            }
+
+            match token {
+                Token::OpenParen => {
+                    let space = self.space_before(token);
+                    self.write(&text, space);
+
+                    let statement = matches!(next, Some(Token::Select | Token::With));
+                    let base = match statement {
+                        true => self.indent() + 2,
+                        false => self.indent(),
+                    };
+
+                    self.frames.push(Frame {
+                        statement,
+                        base,
+                        in_clause: false,
+                    });
+                }
+                Token::CloseParen => {
+                    if self.frames.len() > 1 && self.frames.pop().unwrap().statement {
+                        self.header = false;
+                        self.pending_break = Some(self.indent());
+                    }
+
+                    self.write(&text, false);
+                }
+                Token::Comma => {
+                    self.write(&text, false);
+
+                    let frame = self.frames.last().unwrap();
+
+                    if frame.statement && frame.in_clause {
+                        self.pending_break = Some(frame.base + 2);
+                    }
+                }
+                Token::Semicolon => {
+                    self.write(&text, false);
+
+                    self.frames.truncate(1);
+                    self.frames[0].in_clause = false;
+                    self.header = false;
+                    self.pending_break = Some(0);
+                    self.blank_line = true;
+                }
+                _ => {
+                    let space = self.space_before(token);
+                    self.write(&text, space);
+                }
+            }
+        }
+
+        self.unary = matches!(token, Token::Minus | Token::Plus)
+            && !self.previous.is_some_and(is_operand_end);
+        self.before_previous = self.previous;
+        self.previous = Some(token);
+    }
+
+    /// A comment on a line of its own stays on a line of its own, indented
+    /// like the clause or item after it; one after a token stays at the
+    /// end of its line.
+    fn comment(&mut self, token: &Token, text: &str, newline_before: bool, next: Option<&Token>) {
+        let own_line = newline_before || self.output.is_empty();
+
+        if own_line {
+            let indent = self.pending_break.unwrap_or_else(|| match next {
+                Some(next) if !is_clause(next) => self.indent(),
+                _ => self.frames.last().unwrap().base,
+            });
+
+            self.pending_break = Some(indent);
+            self.write(text.trim_end(), false);
+            self.pending_break = Some(indent);
+        } else {
+            let pending_break = self.pending_break.take();
+
+            self.write(text.trim_end(), true);
+            self.pending_break = pending_break;
+
+            if let Token::SingleLineComment(_) = token {
+                self.pending_break = self.pending_break.or(Some(self.indent()));
+            }
+        }
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        match self.pending_break.take() {
+            Some(indent) if !self.output.is_empty() => {
+                self.output.push('\n');
+
+                if self.blank_line {
+                    self.output.push('\n');
+                    self.blank_line = false;
+                }
+
+                self.output.push_str(&" ".repeat(indent));
+            }
+            Some(_) => {}
+            None if space && !self.output.is_empty() => self.output.push(' '),
+            None => {}
+        }
+
+        self.output.push_str(text);
+    }
+
+    /// Indentation of a line inside the current clause.
+    fn indent(&self) -> usize {
+        let frame = self.frames.last().unwrap();
+
+        match frame.statement && frame.in_clause {
+            true => frame.base + 2,
+            false => frame.base,
+        }
+    }
+
+    /// Keywords that stay on the line of the clause keyword before them,
+    /// as in `GROUP BY` or `DELETE FROM`.
+    fn is_continuation(&self, token: &Token) -> bool {
+        self.header
+            && match token {
+                Token::By | Token::All | Token::Distinct | Token::Into => true,
+                Token::From => self.previous == Some(&Token::Delete),
+                _ => false,
+            }
+    }
+
+    /// Joins start a line of their own, indented like the items of the
+    /// clause they are in.
+    fn is_line_start(&self, token: &Token) -> bool {
+        match token {
+            Token::Inner | Token::Left | Token::Right | Token::Full => true,
+            Token::Join => !matches!(
+                self.previous,
+                Some(Token::Inner | Token::Left | Token::Right | Token::Full | Token::Outer)
+            ),
+            _ => false,
+        }
+    }
+
+    fn space_before(&self, token: &Token) -> bool {
+        let previous = match self.previous {
+            Some(previous) => previous,
+            None => return false,
+        };
+
+        match (previous, token) {
+            (_, Token::Comma | Token::CloseParen | Token::Semicolon) => false,
+            (Token::OpenParen, _) => false,
+            _ if self.unary => false,
+            // A function call or a type with a length, but not a table
+            // name followed by its columns.
+            (Token::Identifier { .. }, Token::OpenParen) => matches!(
+                self.before_previous,
+                Some(
+                    Token::Into
+                        | Token::Table
+                        | Token::Exists
+                        | Token::View
+                        | Token::With
+                        | Token::References
+                )
+            ),
+            (Token::VarChar | Token::Char | Token::Decimal | Token::Numeric, Token::OpenParen) => {
+                false
+            }
+            _ => true,
+        }
+    }
+}
+
+fn is_comment(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::SingleLineComment(_) | Token::MultiLineComment(_)
+    )
+}
+
+/// Keywords that start a clause.
+fn is_clause(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Select
+            | Token::From
+            | Token::Where
+            | Token::Group
+            | Token::Having
+            | Token::Order
+            | Token::Limit
+            | Token::Offset
+            | Token::Union
+            | Token::Insert
+            | Token::Values
+            | Token::Update
+            | Token::Set
+            | Token::Delete
+            | Token::Returning
+    )
+}
+
+fn is_keyword(token: &Token) -> bool {
+    !matches!(
+        token,
+        Token::Identifier { .. }
+            | Token::StringLiteral(_)
+            | Token::NumericLiteral(_)
+            | Token::Parameter(_)
+            | Token::SingleLineComment(_)
+            | Token::MultiLineComment(_)
+            | Token::Whitespace(_)
+    )
+}
+
+/// Tokens after which a `-` or `+` is an operator rather than a sign.
+fn is_operand_end(token: &Token) -> bool {
+    token.is_non_reserved()
+        || matches!(
+            token,
+            Token::Identifier { .. }
+                | Token::StringLiteral(_)
+                | Token::NumericLiteral(_)
+                | Token::BooleanLiteral(_)
+                | Token::Parameter(_)
+                | Token::Null
+                | Token::CloseParen
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_sql;
+    use crate::parser::lexer::LexError;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn format_select() {
+        assert_eq!(
+            format_sql("select a, count(*) from t1 left join t2 on t1.id=t2.id where a>-1 group by a order by a desc limit 10")
+                .unwrap(),
+            "SELECT\n  \
+               a,\n  \
+               count(*)\n\
+             FROM\n  \
+               t1\n  \
+               LEFT JOIN t2 ON t1.id = t2.id\n\
+             WHERE\n  \
+               a > -1\n\
+             GROUP BY\n  \
+               a\n\
+             ORDER BY\n  \
+               a DESC\n\
+             LIMIT\n  \
+               10\n"
+        );
+        assert_eq!(
+            format_sql("select level - 1 from t").unwrap(),
+            "SELECT\n  \
+               LEVEL - 1\n\
+             FROM\n  \
+               t\n"
+        );
+    }
+
+    #[test]
+    fn format_statements() {
+        assert_eq!(
+            format_sql("create table t1 (id int, name varchar(10));insert into t1 (id, name) values (1, 'a'), (2, 'b')")
+                .unwrap(),
+            "CREATE TABLE t1 (id INT, name VARCHAR(10));\n\
+             \n\
+             INSERT INTO\n  \
+               t1 (id, name)\n\
+             VALUES\n  \
+               (1, 'a'),\n  \
+               (2, 'b')\n"
+        );
+    }
+
+    #[test]
+    fn format_subqueries() {
+        assert_eq!(
+            format_sql("with c as (select 1) select * from t where a in (select b from c)")
+                .unwrap(),
+            "WITH c AS (\n  \
+               SELECT\n    \
+                 1\n\
+             )\n\
+             SELECT\n  \
+               *\n\
+             FROM\n  \
+               t\n\
+             WHERE\n  \
+               a IN (\n    \
+                 SELECT\n      \
+                   b\n    \
+                 FROM\n      \
+                   c\n  \
+               )\n"
+        );
+    }
+
+    #[test]
+    fn keep_comments() {
+        let formatted = format_sql(
+            "-- add users\nselect id, -- the key\n  name /* display */ from \"Users\"\n/* done */",
+        )
+        .unwrap();
+
+        assert_eq!(
+            formatted,
+            "-- add users\n\
+             SELECT\n  \
+               id, -- the key\n  \
+               name /* display */\n\
+             FROM\n  \
+               \"Users\"\n\
+             /* done */\n"
+        );
+        assert_eq!(format_sql(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn format_errors() {
+        assert_eq!(format_sql("select 'a"), Err(LexError::UnterminatedString));
+    }
+}
@@ -1,19 +1,52 @@
 pub mod tokens;
 
+use std::borrow::Cow;
+use std::fmt;
+
+use super::{Literal, Span};
 use tokens::Token;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum LexError {
     InvalidCharacter(char),
+    /// A string literal without its closing quote.
+    UnterminatedString,
+    /// A quoted identifier without its closing quote.
+    UnterminatedIdentifier,
+    /// A number too large to represent, as written.
+    NumericOverflow(String),
+    /// A `/*` comment without its closing `*/`.
+    UnterminatedComment,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexError::InvalidCharacter(c) => write!(f, "Invalid character: {}", c),
+            LexError::UnterminatedString => write!(f, "Unterminated string literal"),
+            LexError::UnterminatedIdentifier => write!(f, "Unterminated quoted identifier"),
+            LexError::NumericOverflow(literal) => {
+                write!(f, "Numeric literal out of range: {}", literal)
+            }
+            LexError::UnterminatedComment => write!(f, "Unterminated comment"),
+        }
+    }
 }
 
 pub struct Lexer<'a> {
     input: &'a str,
     input_iterator: std::str::Chars<'a>,
+    /// Byte offset of the next character.
     current_position: usize,
-    is_finished: bool,
-    cache: Option<char>,
+    /// Byte offset of the first character of the token being read.
+    token_start: usize,
+    /// Whether whitespace and comments are returned as tokens.
+    trivia: bool,
     peeked: Option<Result<Token<'a>, LexError>>,
+    /// Where the last token returned by `next` is, and where the peeked
+    /// token is.
+    span: Span,
+    peeked_span: Span,
 }
 
 impl<'a> Iterator for Lexer<'a> {
@@ -23,34 +56,36 @@ impl<'a> Iterator for Lexer<'a> {
         if self.peeked.is_some() {
             let peeked = self.peeked.clone();
             self.peeked = None;
+            self.span = self.peeked_span;
             return peeked;
         }
 
-        let c = self.move_and_skip_whitespace()?;
-
-        match c {
-            '*' => self.single(Token::Asterisk),
-            ',' => self.single(Token::Comma),
-            '=' => self.single(Token::Equals),
-            '+' => self.single(Token::Plus),
-            '%' => self.single(Token::Percent),
-            '|' => self.single(Token::Concat),
-
-            '<' => self.may_be_longer(Token::LessThan),
-            '-' => self.may_be_longer(Token::Minus),
-            '>' => self.may_be_longer(Token::GreaterThan),
-            '/' => self.may_be_longer(Token::Slash),
-            '\'' => self.may_be_longer(Token::SingleQuote),
-            '"' => self.may_be_longer(Token::DoubleQuote),
-
-            '(' => Some(Ok(Token::OpenParen)),
-            ')' => Some(Ok(Token::CloseParen)),
-            ';' => Some(Ok(Token::Semicolon)),
-
-            c if c.is_alphabetic() => self.word_started(),
-            c if c.is_numeric() => self.numeric_started(false),
-
-            _ => Some(Err(LexError::InvalidCharacter(c))),
+        loop {
+            let token = match self.next_token() {
+                Some(token) => token,
+                None => {
+                    self.span = Span {
+                        start: self.current_position,
+                        end: self.current_position,
+                    };
+
+                    return None;
+                }
+            };
+
+            match token {
+                Ok(
+                    Token::Whitespace(_) | Token::SingleLineComment(_) | Token::MultiLineComment(_),
+                ) if !self.trivia => {}
+                token => {
+                    self.span = Span {
+                        start: self.token_start,
+                        end: self.current_position,
+                    };
+
+                    return Some(token);
+                }
+            }
         }
     }
 }
@@ -61,9 +96,68 @@ impl<'a> Lexer<'a> {
             input,
             input_iterator: input.chars(),
             current_position: 0,
-            is_finished: false,
-            cache: None,
+            token_start: 0,
+            trivia: false,
             peeked: None,
+            span: Span::default(),
+            peeked_span: Span::default(),
+        }
+    }
+
+    /// A lexer that also returns whitespace and comments, so that the
+    /// text of its tokens adds up to the whole input.
+    pub fn with_trivia(input: &'a str) -> Self {
+        Self {
+            trivia: true,
+            ..Self::new(input)
+        }
+    }
+
+    fn next_token(&mut self) -> Option<Result<Token<'a>, LexError>> {
+        self.token_start = self.current_position;
+
+        let c = self.get_next_and_increment()?;
+
+        match c {
+            c if c.is_whitespace() => {
+                self.skip_whitespace();
+                Some(Ok(Token::Whitespace(self.text())))
+            }
+
+            '*' => Some(Ok(Token::Asterisk)),
+            ',' => Some(Ok(Token::Comma)),
+            '=' => Some(Ok(Token::Equals)),
+            '+' => Some(Ok(Token::Plus)),
+            '%' => Some(Ok(Token::Percent)),
+            '|' if self.next_if('|') => Some(Ok(Token::Concat)),
+
+            '<' if self.next_if('=') => Some(Ok(Token::LessThanOrEquals)),
+            '<' if self.next_if('>') => Some(Ok(Token::NotEquals)),
+            '<' => Some(Ok(Token::LessThan)),
+            '>' if self.next_if('=') => Some(Ok(Token::GreaterThanOrEquals)),
+            '>' => Some(Ok(Token::GreaterThan)),
+            '-' if self.next_if('-') => self.single_line_comment_started(),
+            '-' => Some(Ok(Token::Minus)),
+            '/' if self.next_if('*') => self.multi_line_comment_started(),
+            '/' => Some(Ok(Token::Slash)),
+            '\'' => self.string_started(false),
+            '"' => self.quoted_identifier_started(),
+            'e' | 'E' if self.peek_char() == Some('\'') => {
+                self.get_next_and_increment();
+                self.string_started(true)
+            }
+
+            '?' | '$' | ':' => self.parameter_started(c),
+
+            '(' => Some(Ok(Token::OpenParen)),
+            ')' => Some(Ok(Token::CloseParen)),
+            ';' => Some(Ok(Token::Semicolon)),
+
+            c if c.is_alphabetic() => self.word_started(),
+            c if c.is_ascii_digit() => self.numeric_started(),
+            '.' if self.peek_char().is_some_and(|c| c.is_ascii_digit()) => self.numeric_started(),
+
+            _ => Some(Err(LexError::InvalidCharacter(c))),
         }
     }
 
@@ -72,141 +166,85 @@ impl<'a> Lexer<'a> {
             return self.peeked.clone();
         }
 
+        let span = self.span;
         let token = self.next();
         self.peeked = token.clone();
+        self.peeked_span = std::mem::replace(&mut self.span, span);
 
         token
     }
 
-    fn get_next_and_increment(&mut self) -> Option<char> {
-        let c = self.input_iterator.next();
-        if c.is_some() {
-            self.current_position += 1;
-        } else {
-            self.is_finished = true;
-        }
-
-        c
+    /// Where the last token returned by `next` is in the input, or the end
+    /// of the input once `next` has returned `None`.
+    pub fn span(&self) -> Span {
+        self.span
     }
 
-    fn get_last_token_end(&self) -> usize {
-        if self.is_finished {
-            return self.current_position;
-        }
-        self.current_position - 1
+    /// The input within `span`.
+    pub fn slice(&self, span: Span) -> &'a str {
+        &self.input[span.start..span.end]
     }
 
-    fn single(&mut self, token: Token<'a>) -> Option<Result<Token<'a>, LexError>> {
-        match self.get_next_and_increment() {
-            Some(c) if c.is_whitespace() => Some(Ok(token)),
-            Some(c) => Some(Err(LexError::InvalidCharacter(c))),
-            None => Some(Ok(token)),
-        }
-    }
-
-    pub fn move_and_skip_whitespace(&mut self) -> Option<char> {
-        if self.cache.is_some() {
-            let c = self.cache;
-            self.cache = None;
-            return c;
-        }
-
-        while let Some(c) = self.get_next_and_increment() {
-            if !c.is_whitespace() {
-                return Some(c);
-            }
+    /// The input from the start of the next token on. Must not be called
+    /// while a token is peeked.
+    pub fn rest(&self) -> &'a str {
+        if self.peeked.is_some() {
+            panic!("Lexer has a peeked token");
         }
 
-        None
+        self.input[self.current_position..].trim_start()
     }
 
-    fn may_be_longer(&mut self, first: Token<'a>) -> Option<Result<Token<'a>, LexError>> {
-        if first == Token::SingleQuote || first == Token::DoubleQuote {
-            return self.quote_started(first);
+    /// The input the last token was read from. Must not be called while a
+    /// token is peeked.
+    pub fn text(&self) -> &'a str {
+        if self.peeked.is_some() {
+            panic!("Lexer has a peeked token");
         }
 
-        let second = self.get_next_and_increment();
-
-        match first {
-            Token::LessThan => match second {
-                Some('=') => Some(Ok(Token::LessThanOrEquals)),
-                Some('>') => Some(Ok(Token::NotEquals)),
-                Some(' ') => Some(Ok(Token::LessThan)),
-
-                Some(c) => Some(Err(LexError::InvalidCharacter(c))),
+        &self.input[self.token_start..self.current_position]
+    }
 
-                None => Some(Ok(Token::LessThan)),
-            },
-            Token::GreaterThan => match second {
-                Some('=') => Some(Ok(Token::GreaterThanOrEquals)),
-                Some(' ') => Some(Ok(Token::GreaterThan)),
+    fn get_next_and_increment(&mut self) -> Option<char> {
+        let c = self.input_iterator.next()?;
+        self.current_position += c.len_utf8();
 
-                Some(c) => Some(Err(LexError::InvalidCharacter(c))),
+        Some(c)
+    }
 
-                None => Some(Ok(Token::GreaterThan)),
-            },
-            Token::Slash => match second {
-                Some(' ') => Some(Ok(Token::Slash)),
-                Some('*') => self.multi_line_comment_started(),
+    fn peek_char(&self) -> Option<char> {
+        self.input_iterator.clone().next()
+    }
 
-                Some(c) => Some(Err(LexError::InvalidCharacter(c))),
+    /// Reads the next character if it is `expected`.
+    fn next_if(&mut self, expected: char) -> bool {
+        let matches = self.peek_char() == Some(expected);
 
-                None => Some(Ok(Token::Slash)),
-            },
-            Token::Minus => match second {
-                Some(' ') => Some(Ok(Token::Minus)),
-                Some('-') => self.single_line_comment_started(),
+        if matches {
+            self.get_next_and_increment();
+        }
 
-                Some(c) if c.is_numeric() => self.numeric_started(true),
-                Some(c) => Some(Err(LexError::InvalidCharacter(c))),
+        matches
+    }
 
-                None => Some(Ok(Token::Minus)),
-            },
-            _ => Some(Err(LexError::InvalidCharacter(second.unwrap()))),
+    fn skip_whitespace(&mut self) {
+        while self.peek_char().is_some_and(char::is_whitespace) {
+            self.get_next_and_increment();
         }
     }
 
     fn word_started(&mut self) -> Option<Result<Token<'a>, LexError>> {
-        let started_position = self.get_last_token_end();
-
-        let is_word = |c: Option<char>| -> bool {
-            if c.is_none() {
-                return false;
-            }
-
-            let c = c.unwrap();
-
-            c.is_alphabetic() || c.is_numeric() || c == '_'
-        };
-
-        loop {
-            let c = self.get_next_and_increment();
-
-            if is_word(c) {
-                continue;
-            }
-
-            if c == Some('.') {
-                return self.identifier_dot_started(started_position);
-            }
-
-            if c.is_none() {
-                break;
-            }
-
-            if [')', ',', ';'].contains(&c.unwrap()) {
-                self.cache(c);
-            }
-
-            break;
+        while self.peek_char().is_some_and(is_word) {
+            self.get_next_and_increment();
         }
 
-        let word = &self.input[started_position..self.get_last_token_end()];
+        let word = &self.input[self.token_start..self.current_position];
 
-        let binding = dbg!(word.to_lowercase());
-        let lower_case_word = dbg!(binding.as_str());
+        if self.peek_char() == Some('.') {
+            return self.qualified_name(unquoted(word));
+        }
 
-        match lower_case_word {
+        match word.to_lowercase().as_str() {
             "select" => Some(Ok(Token::Select)),
             "from" => Some(Ok(Token::From)),
             "where" => Some(Ok(Token::Where)),
@@ -256,6 +294,7 @@ impl<'a> Lexer<'a> {
             "between" => Some(Ok(Token::Between)),
             "like" => Some(Ok(Token::Like)),
             "exists" => Some(Ok(Token::Exists)),
+            "if" => Some(Ok(Token::If)),
             "any" => Some(Ok(Token::Any)),
             "case" => Some(Ok(Token::Case)),
             "when" => Some(Ok(Token::When)),
@@ -263,6 +302,44 @@ impl<'a> Lexer<'a> {
             "else" => Some(Ok(Token::Else)),
             "end" => Some(Ok(Token::End)),
             "default" => Some(Ok(Token::Default)),
+            "returning" => Some(Ok(Token::Returning)),
+            "unique" => Some(Ok(Token::Unique)),
+            "conflict" => Some(Ok(Token::Conflict)),
+            "do" => Some(Ok(Token::Do)),
+            "nothing" => Some(Ok(Token::Nothing)),
+            "merge" => Some(Ok(Token::Merge)),
+            "using" => Some(Ok(Token::Using)),
+            "matched" => Some(Ok(Token::Matched)),
+            "with" => Some(Ok(Token::With)),
+            "recursive" => Some(Ok(Token::Recursive)),
+            "over" => Some(Ok(Token::Over)),
+            "partition" => Some(Ok(Token::Partition)),
+            "rows" => Some(Ok(Token::Rows)),
+            "row" => Some(Ok(Token::Row)),
+            "unbounded" => Some(Ok(Token::Unbounded)),
+            "preceding" => Some(Ok(Token::Preceding)),
+            "following" => Some(Ok(Token::Following)),
+            "current" => Some(Ok(Token::Current)),
+            "view" => Some(Ok(Token::View)),
+            "replace" => Some(Ok(Token::Replace)),
+            "cascade" => Some(Ok(Token::Cascade)),
+            "restrict" => Some(Ok(Token::Restrict)),
+            "begin" => Some(Ok(Token::Begin)),
+            "transaction" => Some(Ok(Token::Transaction)),
+            "commit" => Some(Ok(Token::Commit)),
+            "rollback" => Some(Ok(Token::Rollback)),
+            "savepoint" => Some(Ok(Token::Savepoint)),
+            "release" => Some(Ok(Token::Release)),
+            "to" => Some(Ok(Token::To)),
+            "isolation" => Some(Ok(Token::Isolation)),
+            "level" => Some(Ok(Token::Level)),
+            "read" => Some(Ok(Token::Read)),
+            "committed" => Some(Ok(Token::Committed)),
+            "uncommitted" => Some(Ok(Token::Uncommitted)),
+            "repeatable" => Some(Ok(Token::Repeatable)),
+            "serializable" => Some(Ok(Token::Serializable)),
+            "explain" => Some(Ok(Token::Explain)),
+            "analyze" => Some(Ok(Token::Analyze)),
             "true" => Some(Ok(Token::BooleanLiteral(true))),
             "false" => Some(Ok(Token::BooleanLiteral(false))),
             "int" => Some(Ok(Token::Int)),
@@ -282,180 +359,268 @@ impl<'a> Lexer<'a> {
             "time" => Some(Ok(Token::Time)),
             "timestamp" => Some(Ok(Token::Timestamp)),
             "datetime" => Some(Ok(Token::DateTime)),
+            "interval" => Some(Ok(Token::Interval)),
             "boolean" => Some(Ok(Token::Boolean)),
-            _ => Some(Ok(Token::identifier(word))),
+            _ => Some(Ok(Token::Identifier {
+                first_name: unquoted(word),
+                second_name: None,
+                third_name: None,
+            })),
         }
     }
 
-    fn quote_started(&mut self, quote: Token<'a>) -> Option<Result<Token<'a>, LexError>> {
-        let same_quote = |c: Option<char>| -> bool {
-            match c {
-                Some('\'') => quote == Token::SingleQuote,
-                Some('"') => quote == Token::DoubleQuote,
-                _ => false,
-            }
-        };
+    /// A string literal, its opening quote already read. A doubled quote
+    /// stands for one quote. With `escapes`, as in `E'...'`, a backslash
+    /// starts one of `\b \f \n \r \t` or takes the next character as is.
+    fn string_started(&mut self, escapes: bool) -> Option<Result<Token<'a>, LexError>> {
+        let mut literal = String::new();
 
-        let started_position = self.current_position;
         loop {
-            let c = self.get_next_and_increment();
-            if !same_quote(c) {
-                continue;
-            } else {
-                break;
+            match self.get_next_and_increment() {
+                Some('\'') if self.peek_char() == Some('\'') => {
+                    self.get_next_and_increment();
+                    literal.push('\'');
+                }
+                Some('\'') => break,
+                Some('\\') if escapes => match self.get_next_and_increment() {
+                    Some('b') => literal.push('\u{8}'),
+                    Some('f') => literal.push('\u{c}'),
+                    Some('n') => literal.push('\n'),
+                    Some('r') => literal.push('\r'),
+                    Some('t') => literal.push('\t'),
+                    Some(c) => literal.push(c),
+                    None => return Some(Err(LexError::UnterminatedString)),
+                },
+                Some(c) => literal.push(c),
+                None => return Some(Err(LexError::UnterminatedString)),
             }
         }
 
-        let literal = &self.input[started_position..self.get_last_token_end()];
+        Some(Ok(Token::StringLiteral(literal)))
+    }
 
-        return Some(Ok(Token::StringLiteral(literal.to_string())));
+    fn quoted_identifier_started(&mut self) -> Option<Result<Token<'a>, LexError>> {
+        match self.quoted_name() {
+            Ok(name) => self.qualified_name(Cow::Owned(name)),
+            Err(error) => Some(Err(error)),
+        }
     }
 
-    fn numeric_started(&mut self, has_sign: bool) -> Option<Result<Token<'a>, LexError>> {
-        let is_numeric = |c: Option<char>| -> bool {
-            match c {
-                Some(n) => n.is_numeric(),
-                _ => false,
-            }
-        };
+    /// The rest of `"..."`, its opening quote already read. A doubled
+    /// quote stands for one quote.
+    fn quoted_name(&mut self) -> Result<String, LexError> {
+        let mut name = String::new();
 
-        let started_position = self.get_last_token_end();
-        let mut seen_dot = false;
         loop {
-            let c = self.get_next_and_increment();
-            if is_numeric(c) {
-                continue;
-            } else if c == Some('.') {
-                if seen_dot {
-                    return Some(Err(LexError::InvalidCharacter('.')));
+            match self.get_next_and_increment() {
+                Some('"') if self.peek_char() == Some('"') => {
+                    self.get_next_and_increment();
+                    name.push('"');
                 }
-                seen_dot = true;
-                continue;
-            } else if c.is_none() {
-                break;
-            } else if [')', ',', ';'].contains(&c.unwrap()) {
-                self.cache(c);
-                break;
-            } else {
-                break;
+                Some('"') => break,
+                Some(c) => name.push(c),
+                None => return Err(LexError::UnterminatedIdentifier),
             }
         }
 
-        let literal = &self.input[started_position..self.get_last_token_end()];
-
-        let string_representation = if has_sign {
-            format!("-{}", literal)
-        } else {
-            literal.to_string()
-        };
+        if name.is_empty() {
+            return Err(LexError::InvalidCharacter('"'));
+        }
 
-        return Some(Ok(Token::NumericLiteral(string_representation)));
+        Ok(name)
     }
 
-    fn single_line_comment_started(&mut self) -> Option<Result<Token<'a>, LexError>> {
-        let started_position = self.current_position;
-        loop {
-            let c = self.get_next_and_increment();
-            if c == Some('\n') || c.is_none() {
-                break;
+    /// A name whose first part has been read, followed by any further
+    /// parts separated by dots.
+    fn qualified_name(&mut self, first_name: Cow<'a, str>) -> Option<Result<Token<'a>, LexError>> {
+        let mut names = vec![first_name];
+
+        while self.peek_char() == Some('.') {
+            self.get_next_and_increment();
+
+            if names.len() == 3 {
+                return Some(Err(LexError::InvalidCharacter('.')));
+            }
+
+            match self.name_part() {
+                Ok(name) => names.push(name),
+                Err(error) => return Some(Err(error)),
             }
         }
 
-        let comment = &self.input[started_position..self.get_last_token_end()];
+        let mut names = names.into_iter();
 
-        return Some(Ok(Token::SingleLineComment(comment.to_string())));
+        Some(Ok(Token::Identifier {
+            first_name: names.next().unwrap(),
+            second_name: names.next(),
+            third_name: names.next(),
+        }))
     }
 
-    fn multi_line_comment_started(&mut self) -> Option<Result<Token<'a>, LexError>> {
+    /// A part of a name after a dot, quoted or not.
+    fn name_part(&mut self) -> Result<Cow<'a, str>, LexError> {
         let started_position = self.current_position;
-        loop {
-            let c = self.get_next_and_increment();
-            if c == Some('*') {
-                let next = self.get_next_and_increment();
-                if next == Some('/') {
-                    break;
+
+        match self.get_next_and_increment() {
+            Some('"') => self.quoted_name().map(Cow::Owned),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                while self.peek_char().is_some_and(is_word) {
+                    self.get_next_and_increment();
                 }
+
+                Ok(unquoted(
+                    &self.input[started_position..self.current_position],
+                ))
             }
+            Some(c) => Err(LexError::InvalidCharacter(c)),
+            None => Err(LexError::InvalidCharacter('.')),
         }
+    }
 
-        let comment = &self.input[started_position..self.current_position - 2];
+    /// A number, its first character already read: digits with an
+    /// optional fraction and exponent, `.` and digits, or `0x` and
+    /// hexadecimal digits.
+    fn numeric_started(&mut self) -> Option<Result<Token<'a>, LexError>> {
+        let first = self.input[self.token_start..].chars().next();
 
-        return Some(Ok(Token::MultiLineComment(comment.to_string())));
-    }
-
-    fn identifier_dot_started(
-        &mut self,
-        started_position: usize,
-    ) -> Option<Result<Token<'a>, LexError>> {
-        let first_dot_position = self.current_position;
-        let mut second_dot_position = 0;
-        let mut third_dot_position = 0;
-
-        let mut is_word = |c: Option<char>, cp: usize| -> bool {
-            match c {
-                Some('.') => {
-                    if second_dot_position == 0 {
-                        second_dot_position = cp;
-                        return true;
-                    }
-                    if third_dot_position == 0 {
-                        third_dot_position = cp;
-                    }
-                    true
-                }
-                Some(n) => n.is_alphabetic() || n.is_numeric() || n == '_',
-                _ => false,
+        if first == Some('0') && matches!(self.peek_char(), Some('x' | 'X')) {
+            self.get_next_and_increment();
+
+            if !self.skip_digits(|c| c.is_ascii_hexdigit()) {
+                return Some(Err(LexError::InvalidCharacter(
+                    self.peek_char().unwrap_or('x'),
+                )));
             }
-        };
+        } else {
+            self.skip_digits(|c| c.is_ascii_digit());
 
-        loop {
-            let c = self.get_next_and_increment();
-            if is_word(c, self.current_position) {
-                continue;
-            } else {
-                break;
+            if first != Some('.') && self.peek_char() == Some('.') {
+                self.get_next_and_increment();
+                self.skip_digits(|c| c.is_ascii_digit());
+            }
+
+            if matches!(self.peek_char(), Some('e' | 'E')) {
+                let mut exponent = self.input_iterator.clone().skip(1);
+                let digit = match exponent.next() {
+                    Some('+' | '-') => exponent.next(),
+                    c => c,
+                };
+
+                if digit.is_some_and(|c| c.is_ascii_digit()) {
+                    self.get_next_and_increment();
+                    self.get_next_and_increment();
+                    self.skip_digits(|c| c.is_ascii_digit());
+                }
             }
         }
 
-        if third_dot_position > 0 {
-            return Some(Err(LexError::InvalidCharacter('.')));
+        // A number must not run into a name or another number.
+        if let Some(c) = self.peek_char().filter(|&c| is_word(c) || c == '.') {
+            return Some(Err(LexError::InvalidCharacter(c)));
         }
 
-        let first_name = &self.input[started_position..first_dot_position - 1];
-        let second_name = if second_dot_position > 0 {
-            &self.input[first_dot_position..second_dot_position - 1]
-        } else {
-            &self.input[first_dot_position..self.get_last_token_end()]
-        };
+        let literal = self.input[self.token_start..self.current_position].to_string();
 
-        let third_name = if second_dot_position > 0 {
-            Some(&self.input[second_dot_position..self.get_last_token_end()])
-        } else {
-            None
+        if Literal::numeric(&literal).is_none() {
+            return Some(Err(LexError::NumericOverflow(literal)));
+        }
+
+        Some(Ok(Token::NumericLiteral(literal)))
+    }
+
+    /// Reads the characters matching `is_digit`; `false` if there were none.
+    fn skip_digits(&mut self, is_digit: fn(char) -> bool) -> bool {
+        let mut any = false;
+
+        while self.peek_char().is_some_and(is_digit) {
+            self.get_next_and_increment();
+            any = true;
+        }
+
+        any
+    }
+
+    /// `?`, or `$` followed by a number, or `:` followed by a name.
+    fn parameter_started(&mut self, first: char) -> Option<Result<Token<'a>, LexError>> {
+        if first != '?' {
+            while self.peek_char().is_some_and(is_word) {
+                self.get_next_and_increment();
+            }
+        }
+
+        let parameter = &self.input[self.token_start..self.current_position];
+        let rest = &parameter[1..];
+
+        let is_valid = match first {
+            '$' => !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()),
+            ':' => rest.starts_with(|c: char| c.is_alphabetic() || c == '_'),
+            _ => true,
         };
 
-        Some(Ok(Token::Identifier {
-            first_name,
-            second_name: Some(second_name),
-            third_name,
-        }))
+        if !is_valid {
+            return Some(Err(LexError::InvalidCharacter(first)));
+        }
+
+        Some(Ok(Token::Parameter(parameter)))
+    }
+
+    fn single_line_comment_started(&mut self) -> Option<Result<Token<'a>, LexError>> {
+        let started_position = self.current_position;
+
+        while self.peek_char().is_some_and(|c| c != '\n') {
+            self.get_next_and_increment();
+        }
+
+        let comment = &self.input[started_position..self.current_position];
+
+        Some(Ok(Token::SingleLineComment(comment.to_string())))
     }
 
-    fn cache(&mut self, c: Option<char>) {
-        if self.cache.is_some() {
-            panic!("Cache is already full");
+    fn multi_line_comment_started(&mut self) -> Option<Result<Token<'a>, LexError>> {
+        let started_position = self.current_position;
+
+        loop {
+            match self.get_next_and_increment() {
+                Some('*') if self.next_if('/') => break,
+                Some(_) => continue,
+                None => return Some(Err(LexError::UnterminatedComment)),
+            }
         }
 
-        self.cache = c;
+        let comment = &self.input[started_position..self.current_position - 2];
+
+        Some(Ok(Token::MultiLineComment(comment.to_string())))
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphabetic() || c.is_numeric() || c == '_'
+}
+
+/// Names not in double quotes are not case sensitive; they are kept in
+/// lower case.
+fn unquoted(name: &str) -> Cow<'_, str> {
+    match name.chars().any(char::is_uppercase) {
+        true => Cow::Owned(name.to_lowercase()),
+        false => Cow::Borrowed(name),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::parser::lexer::{LexError, Lexer, Token};
+    use crate::parser::Span;
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
+    fn qualified(names: &[&'static str]) -> Token<'static> {
+        Token::Identifier {
+            first_name: names[0].into(),
+            second_name: names.get(1).map(|&name| name.into()),
+            third_name: names.get(2).map(|&name| name.into()),
+        }
+    }
+
     #[test]
     fn lex_empty_input() {
         let input = "";
@@ -480,11 +645,11 @@ mod tests {
 
     #[test]
     fn lex_error() {
-        let input = "**";
+        let input = "#";
         let lexer = Lexer::new(input);
         let actual: Vec<Result<Token, LexError>> = lexer.collect();
 
-        let expected = vec![Err(LexError::InvalidCharacter('*'))];
+        let expected = vec![Err(LexError::InvalidCharacter('#'))];
 
         assert_eq!(actual, expected);
     }
@@ -507,7 +672,7 @@ mod tests {
 
     #[test]
     fn lex_single_chars() {
-        let input = "* , ; ( ) = < >   + - / % |";
+        let input = "* , ; ( ) = < >   + - / %";
         let lexer = Lexer::new(input);
         let actual: Vec<Result<Token, LexError>> = lexer.collect();
 
@@ -524,7 +689,6 @@ mod tests {
             Ok(Token::Minus),
             Ok(Token::Slash),
             Ok(Token::Percent),
-            Ok(Token::Concat),
         ];
 
         assert_eq!(actual, expected);
@@ -532,7 +696,7 @@ mod tests {
 
     #[test]
     fn lex_longer_tokens() {
-        let input = "<= >= <>";
+        let input = "<= >= <> ||";
         let lexer = Lexer::new(input);
         let actual: Vec<Result<Token, LexError>> = lexer.collect();
 
@@ -540,11 +704,48 @@ mod tests {
             Ok(Token::LessThanOrEquals),
             Ok(Token::GreaterThanOrEquals),
             Ok(Token::NotEquals),
+            Ok(Token::Concat),
         ];
 
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn single_pipe() {
+        let actual: Vec<Result<Token, LexError>> = Lexer::new("a | b").collect();
+
+        assert_eq!(actual[1], Err(LexError::InvalidCharacter('|')));
+    }
+
+    #[rstest]
+    #[case("a=1", vec![Ok(Token::identifier("a")), Ok(Token::Equals), Ok(Token::NumericLiteral("1".to_string()))])]
+    #[case("a>=b", vec![Ok(Token::identifier("a")), Ok(Token::GreaterThanOrEquals), Ok(Token::identifier("b"))])]
+    #[case("a<>b", vec![Ok(Token::identifier("a")), Ok(Token::NotEquals), Ok(Token::identifier("b"))])]
+    #[case("(a)", vec![Ok(Token::OpenParen), Ok(Token::identifier("a")), Ok(Token::CloseParen)])]
+    #[case("f(x)", vec![Ok(Token::identifier("f")), Ok(Token::OpenParen), Ok(Token::identifier("x")), Ok(Token::CloseParen)])]
+    #[case("1+2", vec![Ok(Token::NumericLiteral("1".to_string())), Ok(Token::Plus), Ok(Token::NumericLiteral("2".to_string()))])]
+    #[case("1-2", vec![Ok(Token::NumericLiteral("1".to_string())), Ok(Token::Minus), Ok(Token::NumericLiteral("2".to_string()))])]
+    #[case("a||'b'", vec![Ok(Token::identifier("a")), Ok(Token::Concat), Ok(Token::StringLiteral("b".to_string()))])]
+    #[case("*/**/*", vec![Ok(Token::Asterisk), Ok(Token::Asterisk)])]
+    #[case("$1,:a)", vec![Ok(Token::Parameter("$1")), Ok(Token::Comma), Ok(Token::Parameter(":a")), Ok(Token::CloseParen)])]
+    #[case("café=1", vec![Ok(Token::identifier("café")), Ok(Token::Equals), Ok(Token::NumericLiteral("1".to_string()))])]
+    fn tokens_without_whitespace(
+        #[case] input: &str,
+        #[case] expected: Vec<Result<Token, LexError>>,
+    ) {
+        let actual: Vec<Result<Token, LexError>> = Lexer::new(input).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn unterminated_comment() {
+        assert_eq!(
+            Lexer::new("/* abc *").next(),
+            Some(Err(LexError::UnterminatedComment))
+        );
+    }
+
     #[test]
     fn lex_keywords() {
         let input = "select from where insert into values update set delete create table primary key foreign references drop alter add column constraint index join inner left right full outer on group by order asc desc union all distinct limit offset having as and or not null is in between like exists any case when then else end default";
@@ -613,6 +814,248 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn lex_returning() {
+        let input = "delete from table1 returning *";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::Delete),
+            Ok(Token::From),
+            Ok(Token::identifier("table1")),
+            Ok(Token::Returning),
+            Ok(Token::Asterisk),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lex_upsert_keywords() {
+        let input = "on conflict do nothing unique";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::On),
+            Ok(Token::Conflict),
+            Ok(Token::Do),
+            Ok(Token::Nothing),
+            Ok(Token::Unique),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lex_merge_keywords() {
+        let input = "merge into table1 using table2 on when not matched then";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::Merge),
+            Ok(Token::Into),
+            Ok(Token::identifier("table1")),
+            Ok(Token::Using),
+            Ok(Token::identifier("table2")),
+            Ok(Token::On),
+            Ok(Token::When),
+            Ok(Token::Not),
+            Ok(Token::Matched),
+            Ok(Token::Then),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lex_with_recursive() {
+        let input = "with recursive t as";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::With),
+            Ok(Token::Recursive),
+            Ok(Token::identifier("t")),
+            Ok(Token::As),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lex_window_keywords() {
+        let input = "rank() over (partition by a rows between unbounded preceding and current row)";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::identifier("rank")),
+            Ok(Token::OpenParen),
+            Ok(Token::CloseParen),
+            Ok(Token::Over),
+            Ok(Token::OpenParen),
+            Ok(Token::Partition),
+            Ok(Token::By),
+            Ok(Token::identifier("a")),
+            Ok(Token::Rows),
+            Ok(Token::Between),
+            Ok(Token::Unbounded),
+            Ok(Token::Preceding),
+            Ok(Token::And),
+            Ok(Token::Current),
+            Ok(Token::Row),
+            Ok(Token::CloseParen),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lex_view_keywords() {
+        let input = "create or replace view v drop view v cascade restrict";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::Create),
+            Ok(Token::Or),
+            Ok(Token::Replace),
+            Ok(Token::View),
+            Ok(Token::identifier("v")),
+            Ok(Token::Drop),
+            Ok(Token::View),
+            Ok(Token::identifier("v")),
+            Ok(Token::Cascade),
+            Ok(Token::Restrict),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lex_if_exists() {
+        let input = "drop table if exists t";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::Drop),
+            Ok(Token::Table),
+            Ok(Token::If),
+            Ok(Token::Exists),
+            Ok(Token::identifier("t")),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lex_transaction_keywords() {
+        let input = "begin transaction isolation level read committed savepoint s rollback to s release commit";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::Begin),
+            Ok(Token::Transaction),
+            Ok(Token::Isolation),
+            Ok(Token::Level),
+            Ok(Token::Read),
+            Ok(Token::Committed),
+            Ok(Token::Savepoint),
+            Ok(Token::identifier("s")),
+            Ok(Token::Rollback),
+            Ok(Token::To),
+            Ok(Token::identifier("s")),
+            Ok(Token::Release),
+            Ok(Token::Commit),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lex_explain_analyze() {
+        let input = "explain analyze select";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![Ok(Token::Explain), Ok(Token::Analyze), Ok(Token::Select)];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn parameters() {
+        let input = "values (?, ?) where a = $12 and b = :name_1;";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::Values),
+            Ok(Token::OpenParen),
+            Ok(Token::Parameter("?")),
+            Ok(Token::Comma),
+            Ok(Token::Parameter("?")),
+            Ok(Token::CloseParen),
+            Ok(Token::Where),
+            Ok(Token::identifier("a")),
+            Ok(Token::Equals),
+            Ok(Token::Parameter("$12")),
+            Ok(Token::And),
+            Ok(Token::identifier("b")),
+            Ok(Token::Equals),
+            Ok(Token::Parameter(":name_1")),
+            Ok(Token::Semicolon),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert_eq!(
+            Lexer::new("$a").next(),
+            Some(Err(LexError::InvalidCharacter('$')))
+        );
+        assert_eq!(
+            Lexer::new(": a").next(),
+            Some(Err(LexError::InvalidCharacter(':')))
+        );
+    }
+
+    #[test]
+    fn rest_starts_at_next_token() {
+        let mut lexer = Lexer::new("view v as(select 1)");
+
+        lexer.next();
+        lexer.next();
+        lexer.next();
+
+        assert_eq!(lexer.rest(), "(select 1)");
+    }
+
+    #[test]
+    fn qualified_identifier_before_punctuation() {
+        let input = "(t.a, t.b)";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::OpenParen),
+            Ok(qualified(&["t", "a"])),
+            Ok(Token::Comma),
+            Ok(qualified(&["t", "b"])),
+            Ok(Token::CloseParen),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn string_literals() {
         let input = "'hello' 'world'";
@@ -638,6 +1081,91 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn doubled_quotes() {
+        let input = r#"'it''s' '''' "a ""b""""#;
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::StringLiteral("it's".to_string())),
+            Ok(Token::StringLiteral("'".to_string())),
+            Ok(Token::identifier("a \"b\"")),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn escape_string_literals() {
+        let input = r"E'a\tb\n' e'\'\\' 'c\n'";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::StringLiteral("a\tb\n".to_string())),
+            Ok(Token::StringLiteral("'\\".to_string())),
+            Ok(Token::StringLiteral("c\\n".to_string())),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn quoted_identifiers() {
+        let input = r#"select "Order Items", "select" from e"#;
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::Select),
+            Ok(Token::identifier("Order Items")),
+            Ok(Token::Comma),
+            Ok(Token::identifier("select")),
+            Ok(Token::From),
+            Ok(Token::identifier("e")),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn identifier_case() {
+        let input = r#"Orders "Orders" S."Order Items".Qty "a"."b""#;
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected = vec![
+            Ok(Token::identifier("orders")),
+            Ok(Token::identifier("Orders")),
+            Ok(qualified(&["s", "Order Items", "qty"])),
+            Ok(qualified(&["a", "b"])),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("a.b.c.d")]
+    #[case("a.")]
+    #[case(r#""a".1"#)]
+    fn invalid_qualified_names(#[case] input: &str) {
+        assert!(matches!(
+            Lexer::new(input).next(),
+            Some(Err(LexError::InvalidCharacter(_)))
+        ));
+    }
+
+    #[rstest]
+    #[case("'abc", LexError::UnterminatedString)]
+    #[case("'it''", LexError::UnterminatedString)]
+    #[case(r"E'abc\'", LexError::UnterminatedString)]
+    #[case(r#""abc"#, LexError::UnterminatedIdentifier)]
+    #[case(r#""""#, LexError::InvalidCharacter('"'))]
+    fn unterminated_quotes(#[case] input: &str, #[case] error: LexError) {
+        assert_eq!(Lexer::new(input).next(), Some(Err(error)));
+    }
+
     #[test]
     fn numeric_literals() {
         let input = "123 456.789 -123 -456.789";
@@ -647,13 +1175,43 @@ mod tests {
         let expected = vec![
             Ok(Token::NumericLiteral("123".to_string())),
             Ok(Token::NumericLiteral("456.789".to_string())),
-            Ok(Token::NumericLiteral("-123".to_string())),
-            Ok(Token::NumericLiteral("-456.789".to_string())),
+            Ok(Token::Minus),
+            Ok(Token::NumericLiteral("123".to_string())),
+            Ok(Token::Minus),
+            Ok(Token::NumericLiteral("456.789".to_string())),
         ];
 
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn numeric_literal_forms() {
+        let input = "1e-3 2.5E+10 .5 7. 0xFF 0x1a 9223372036854775808";
+        let lexer = Lexer::new(input);
+        let actual: Vec<Result<Token, LexError>> = lexer.collect();
+
+        let expected: Vec<Result<Token, LexError>> = input
+            .split(' ')
+            .map(|literal| Ok(Token::NumericLiteral(literal.to_string())))
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("1.2.3", LexError::InvalidCharacter('.'))]
+    #[case("12abc", LexError::InvalidCharacter('a'))]
+    #[case("1e", LexError::InvalidCharacter('e'))]
+    #[case("0xg", LexError::InvalidCharacter('g'))]
+    #[case(
+        "0x1000000000000000000000000000000000",
+        LexError::NumericOverflow("0x1000000000000000000000000000000000".to_string())
+    )]
+    #[case("1e999", LexError::NumericOverflow("1e999".to_string()))]
+    fn invalid_numeric_literals(#[case] input: &str, #[case] error: LexError) {
+        assert_eq!(Lexer::new(input).next(), Some(Err(error)));
+    }
+
     #[test]
     fn boolean_literals() {
         let input = "true false";
@@ -713,7 +1271,7 @@ mod tests {
     #[test]
     fn single_line_comment() {
         let input = "-- this is a comment";
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::with_trivia(input);
         let actual: Vec<Result<Token, LexError>> = lexer.collect();
 
         let expected = vec![Ok(Token::SingleLineComment(
@@ -732,7 +1290,6 @@ mod tests {
         let expected = vec![
             Ok(Token::Select),
             Ok(Token::Asterisk),
-            Ok(Token::SingleLineComment(" this is a comment".to_string())),
             Ok(Token::From),
             Ok(Token::identifier("table1")),
         ];
@@ -743,7 +1300,7 @@ mod tests {
     #[test]
     fn multi_line_comment() {
         let input = "/* this is a comment */";
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::with_trivia(input);
         let actual: Vec<Result<Token, LexError>> = lexer.collect();
 
         let expected = vec![Ok(Token::MultiLineComment(
@@ -756,7 +1313,7 @@ mod tests {
     #[test]
     fn really_multi_line_comment() {
         let input = "/* this is \n a comment */";
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::with_trivia(input);
         let actual: Vec<Result<Token, LexError>> = lexer.collect();
 
         let expected = vec![Ok(Token::MultiLineComment(
@@ -766,6 +1323,20 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn lossless_trivia() {
+        let input = "select *,--all\n\t1/* one */ from \"T\" ;  ";
+        let mut lexer = Lexer::with_trivia(input);
+        let mut text = String::new();
+
+        while let Some(token) = lexer.next() {
+            token.unwrap();
+            text.push_str(lexer.text());
+        }
+
+        assert_eq!(text, input);
+    }
+
     #[test]
     fn multi_line_comment_with_other_tokens() {
         let input = "select * /* this is a comment */ from table1";
@@ -775,7 +1346,6 @@ mod tests {
         let expected = vec![
             Ok(Token::Select),
             Ok(Token::Asterisk),
-            Ok(Token::MultiLineComment(" this is a comment ".to_string())),
             Ok(Token::From),
             Ok(Token::identifier("table1")),
         ];
@@ -792,22 +1362,10 @@ mod tests {
         let expected = vec![
             Ok(Token::identifier("table1")),
             Ok(Token::identifier("column1")),
-            Ok(Token::Identifier {
-                first_name: "PUBLIC",
-                second_name: Some("table2"),
-                third_name: None,
-            }),
+            Ok(qualified(&["public", "table2"])),
             Ok(Token::identifier("my_col_3")),
-            Ok(Token::Identifier {
-                first_name: "PUBLIC_4",
-                second_name: Some("table_5"),
-                third_name: None,
-            }),
-            Ok(Token::Identifier {
-                first_name: "public",
-                second_name: Some("t6able"),
-                third_name: Some("column_7"),
-            }),
+            Ok(qualified(&["public_4", "table_5"])),
+            Ok(qualified(&["public", "t6able", "column_7"])),
         ];
 
         assert_eq!(actual, expected);
@@ -839,7 +1397,8 @@ mod tests {
             Ok(Token::OpenParen),
             Ok(Token::identifier("column5")),
             Ok(Token::Equals),
-            Ok(Token::NumericLiteral("-456.789".to_string())),
+            Ok(Token::Minus),
+            Ok(Token::NumericLiteral("456.789".to_string())),
             Ok(Token::Or),
             Ok(Token::identifier("column7")),
             Ok(Token::Equals),
@@ -952,12 +1511,14 @@ mod tests {
             Ok(Token::And),
             Ok(Token::identifier("column4")),
             Ok(Token::Equals),
-            Ok(Token::NumericLiteral("-123".to_string())),
+            Ok(Token::Minus),
+            Ok(Token::NumericLiteral("123".to_string())),
             Ok(Token::Or),
             Ok(Token::OpenParen),
             Ok(Token::identifier("column5")),
             Ok(Token::Equals),
-            Ok(Token::NumericLiteral("-456.789".to_string())),
+            Ok(Token::Minus),
+            Ok(Token::NumericLiteral("456.789".to_string())),
             Ok(Token::Or),
             Ok(Token::identifier("column7")),
             Ok(Token::Equals),
@@ -978,10 +1539,6 @@ mod tests {
             Ok(Token::NumericLiteral("1".to_string())),
             Ok(Token::Equals),
             Ok(Token::NumericLiteral("2".to_string())),
-            Ok(Token::SingleLineComment(" this is a comment ".to_string())),
-            Ok(Token::MultiLineComment(
-                " this is a multi-line\n               comment ".to_string(),
-            )),
             Ok(Token::Semicolon),
         ];
 
@@ -1043,4 +1600,22 @@ mod tests {
         let expected = None;
         assert_eq!(actual, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn spans_of_tokens() {
+        let mut lexer = Lexer::new("select  a -- the a\n+ 'b'");
+
+        lexer.next();
+        assert_eq!(lexer.span(), Span { start: 0, end: 6 });
+
+        lexer.next();
+        lexer.peek();
+        assert_eq!(lexer.span(), Span { start: 8, end: 9 });
+
+        lexer.next();
+        assert_eq!(lexer.span(), Span { start: 19, end: 20 });
+
+        lexer.next();
+        assert_eq!(lexer.span(), Span { start: 21, end: 24 });
+    }
+}
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 #[derive(Debug, PartialEq, Clone)]
 
 pub enum Token<'a> {
@@ -50,6 +52,7 @@ pub enum Token<'a> {
     Between,
     Like,
     Exists,
+    If,
     Any,
     Case,
     When,
@@ -57,6 +60,44 @@ pub enum Token<'a> {
     Else,
     End,
     Default,
+    Returning,
+    Unique,
+    Conflict,
+    Do,
+    Nothing,
+    Merge,
+    Using,
+    Matched,
+    With,
+    Recursive,
+    Over,
+    Partition,
+    Rows,
+    Row,
+    Unbounded,
+    Preceding,
+    Following,
+    Current,
+    View,
+    Replace,
+    Cascade,
+    Restrict,
+    Begin,
+    Transaction,
+    Commit,
+    Rollback,
+    Savepoint,
+    Release,
+    To,
+    Isolation,
+    Level,
+    Read,
+    Committed,
+    Uncommitted,
+    Repeatable,
+    Serializable,
+    Explain,
+    Analyze,
 
     Int,
     Integer,
@@ -75,6 +116,7 @@ pub enum Token<'a> {
     DateTime,
     Time,
     Timestamp,
+    Interval,
     Boolean,
 
     Asterisk,
@@ -93,29 +135,70 @@ pub enum Token<'a> {
     Slash,
     Percent,
     Concat,
-    SingleQuote,
-    DoubleQuote,
 
+    /// A name of up to three parts. Unquoted parts are lower case; parts
+    /// written in double quotes are kept as written, without the quotes.
     Identifier {
-        first_name: &'a str,
-        second_name: Option<&'a str>,
-        third_name: Option<&'a str>,
+        first_name: Cow<'a, str>,
+        second_name: Option<Cow<'a, str>>,
+        third_name: Option<Cow<'a, str>>,
     },
 
     StringLiteral(String),
     NumericLiteral(String),
     BooleanLiteral(bool),
+    /// A placeholder as written: `?`, `$1` or `:name`.
+    Parameter(&'a str),
 
     SingleLineComment(String),
     MultiLineComment(String),
+    /// Only returned by a lexer made with `Lexer::with_trivia`, like
+    /// the comments.
+    Whitespace(&'a str),
 }
 
 impl<'a> Token<'a> {
     pub fn identifier(first_name: &'a str) -> Token<'a> {
         Token::Identifier {
-            first_name,
+            first_name: Cow::Borrowed(first_name),
             second_name: None,
             third_name: None,
         }
     }
-}
\ No newline at end of file
+
+    /// Keywords that only mean something in their own clauses, like `level`
+    /// in `ISOLATION LEVEL`. Anywhere a name can stand, the parser reads
+    /// them as one.
+    pub fn is_non_reserved(&self) -> bool {
+        matches!(
+            self,
+            Token::If
+                | Token::Conflict
+                | Token::Do
+                | Token::Nothing
+                | Token::Matched
+                | Token::Recursive
+                | Token::Over
+                | Token::Partition
+                | Token::Rows
+                | Token::Row
+                | Token::Unbounded
+                | Token::Preceding
+                | Token::Following
+                | Token::Current
+                | Token::View
+                | Token::Replace
+                | Token::Cascade
+                | Token::Restrict
+                | Token::Transaction
+                | Token::To
+                | Token::Isolation
+                | Token::Level
+                | Token::Read
+                | Token::Committed
+                | Token::Uncommitted
+                | Token::Repeatable
+                | Token::Serializable
+        )
+    }
+}
@@ -1,77 +1,317 @@
 #![allow(dead_code)]
 
+mod error;
+pub mod format;
 pub mod lexer;
+mod unparse;
+pub mod visitor;
 
+pub use error::{ParseError, ParseErrorKind};
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::temporal::{Date, Interval, TemporalError, Time, Timestamp};
 use lexer::tokens::Token;
+use lexer::LexError;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Literal {
-    Numeric(i32),
+    Numeric(i64),
     String(String),
     Identifier {
         first_name: String,
         second_name: Option<String>,
         third_name: Option<String>,
     },
-    Float(f32),
+    /// A number with an exponent, such as `1e-3`.
+    Float(f64),
+    /// A number with a decimal point, or an integer too large for
+    /// `Numeric`.
+    Decimal(Decimal),
     Bool(bool),
+    Date(Date),
+    Time(Time),
+    Timestamp(Timestamp),
+    Interval(Interval),
+    Null,
+    Default,
+    Wildcard,
+    Parameter(Parameter),
 }
 
-impl Literal {
-    // Метод для извлечения строки из варианта Literal::String
-    pub fn get_first_name_as_string(&self) -> String {
-        match self {
-            Literal::Identifier {
-                first_name,
-                second_name: None,
-                third_name: None,
-            } => first_name.to_string(),
-            _ => panic!("Unexpected literal: {:?}", self),
+/// A placeholder for a value supplied when a prepared statement runs.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Parameter {
+    /// `$n`, or the n-th `?` of the statement; counted from 1.
+    Positional(usize),
+    /// `:name`.
+    Named(String),
+}
+
+/// An exact number, `mantissa` times ten to the power of `-scale`. The
+/// scale is the number of digits written after the decimal point.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Decimal {
+    pub mantissa: i128,
+    pub scale: u32,
+}
+
+/// Digits after the point of a quotient, unless an operand has more.
+const DIVISION_SCALE: u32 = 6;
+
+impl Decimal {
+    pub fn to_f64(self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// The same number with `scale` digits after the point. `None` if that
+    /// would drop digits or does not fit.
+    fn rescale(self, scale: u32) -> Option<Decimal> {
+        let factor = 10i128.checked_pow(scale.checked_sub(self.scale)?)?;
+
+        Some(Decimal {
+            mantissa: self.mantissa.checked_mul(factor)?,
+            scale,
+        })
+    }
+
+    /// The mantissas of both numbers at the larger of their scales, and that
+    /// scale.
+    fn aligned(self, other: Decimal) -> Option<(i128, i128, u32)> {
+        let scale = self.scale.max(other.scale);
+
+        Some((
+            self.rescale(scale)?.mantissa,
+            other.rescale(scale)?.mantissa,
+            scale,
+        ))
+    }
+
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        let (lhs, rhs, scale) = self.aligned(other)?;
+
+        Some(Decimal {
+            mantissa: lhs.checked_add(rhs)?,
+            scale,
+        })
+    }
+
+    pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+        let (lhs, rhs, scale) = self.aligned(other)?;
+
+        Some(Decimal {
+            mantissa: lhs.checked_sub(rhs)?,
+            scale,
+        })
+    }
+
+    pub fn checked_mul(self, other: Decimal) -> Option<Decimal> {
+        Some(Decimal {
+            mantissa: self.mantissa.checked_mul(other.mantissa)?,
+            scale: self.scale.checked_add(other.scale)?,
+        })
+    }
+
+    /// The quotient rounded half away from zero to `DIVISION_SCALE` digits,
+    /// or to the scale of the operand with more. `None` on division by zero.
+    pub fn checked_div(self, other: Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale).max(DIVISION_SCALE);
+        let dividend = self.rescale(scale.checked_add(other.scale)?)?.mantissa;
+
+        let quotient = dividend.checked_div(other.mantissa)?;
+        let remainder = dividend.checked_rem(other.mantissa)?.unsigned_abs();
+        let divisor = other.mantissa.unsigned_abs();
+
+        let quotient = match remainder >= divisor - remainder {
+            true if (dividend < 0) != (other.mantissa < 0) => quotient.checked_sub(1)?,
+            true => quotient.checked_add(1)?,
+            false => quotient,
+        };
+
+        Some(Decimal {
+            mantissa: quotient,
+            scale,
+        })
+    }
+
+    /// The remainder, with the sign of `self`. `None` on division by zero.
+    pub fn checked_rem(self, other: Decimal) -> Option<Decimal> {
+        let (lhs, rhs, scale) = self.aligned(other)?;
+
+        Some(Decimal {
+            mantissa: lhs.checked_rem(rhs)?,
+            scale,
+        })
+    }
+
+    pub fn checked_neg(self) -> Option<Decimal> {
+        Some(Decimal {
+            mantissa: self.mantissa.checked_neg()?,
+            scale: self.scale,
+        })
+    }
+
+    /// Numeric order, whatever the scales.
+    pub fn compare(self, other: Decimal) -> Ordering {
+        match self.aligned(other) {
+            Some((lhs, rhs, _)) => lhs.cmp(&rhs),
+            // Too far apart to align exactly, so floating point tells them
+            // apart.
+            None => self
+                .to_f64()
+                .partial_cmp(&other.to_f64())
+                .unwrap_or(Ordering::Equal),
+        }
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(i: i64) -> Self {
+        Decimal {
+            mantissa: i as i128,
+            scale: 0,
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+
+        if self.mantissa < 0 {
+            write!(f, "-")?;
+        }
+
+        match fraction.is_empty() {
+            true => write!(f, "{}", integer),
+            false => write!(f, "{}.{}", integer, fraction),
         }
     }
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Type {
     Int,
+    Float,
+    /// Exact numbers, see `Decimal`.
+    Decimal,
     String,
     Bool,
+    Date,
+    Time,
+    Timestamp,
+    Interval,
 }
 
 impl Literal {
-    fn numeric(i: String) -> Literal {
-        Literal::Numeric(i.parse().unwrap())
+    /// The literal for a number as written: decimal digits with
+    /// an optional point and exponent, or `0x` and hexadecimal digits,
+    /// either with a leading `-`. `None` if it is out of range.
+    fn numeric(i: &str) -> Option<Literal> {
+        let (negative, digits) = match i.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, i),
+        };
+
+        let mantissa = if let Some(hex) = digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"))
+        {
+            i128::from_str_radix(hex, 16).ok()?
+        } else if digits.contains(['e', 'E']) {
+            let float: f64 = i.parse().ok()?;
+            return float.is_finite().then_some(Literal::Float(float));
+        } else if let Some((integer, fraction)) = digits.split_once('.') {
+            let mantissa: i128 = format!("{}{}", integer, fraction).parse().ok()?;
+            let mantissa = if negative { -mantissa } else { mantissa };
+
+            return Some(Literal::Decimal(Decimal {
+                mantissa,
+                scale: fraction.len() as u32,
+            }));
+        } else {
+            digits.parse().ok()?
+        };
+
+        let mantissa = if negative { -mantissa } else { mantissa };
+
+        Some(match i64::try_from(mantissa) {
+            Ok(i) => Literal::Numeric(i),
+            Err(_) => Literal::Decimal(Decimal { mantissa, scale: 0 }),
+        })
+    }
+
+    /// The negated number, for a `-` written before a numeric literal.
+    /// `None` for any other literal.
+    fn negated(&self) -> Option<Literal> {
+        match self {
+            Literal::Numeric(i) => Some(match i.checked_neg() {
+                Some(i) => Literal::Numeric(i),
+                None => Literal::Decimal(Decimal {
+                    mantissa: -(*i as i128),
+                    scale: 0,
+                }),
+            }),
+            Literal::Float(x) => Some(Literal::Float(-x)),
+            Literal::Decimal(Decimal { mantissa, scale: 0 })
+                if i64::try_from(-mantissa).is_ok() =>
+            {
+                Some(Literal::Numeric(-mantissa as i64))
+            }
+            Literal::Decimal(d) => Some(Literal::Decimal(Decimal {
+                mantissa: -d.mantissa,
+                scale: d.scale,
+            })),
+            _ => None,
+        }
     }
 
     fn string(s: String) -> Literal {
         Literal::String(s)
     }
 
-    fn identifier(identifier: &str) -> Literal {
-        let parts: Vec<&str> = identifier.split('.').collect();
-
-        match parts.len() {
-            1 => Literal::Identifier {
-                first_name: parts[0].to_string(),
-                second_name: None,
-                third_name: None,
-            },
-            2 => Literal::Identifier {
-                first_name: parts[0].to_string(),
-                second_name: Some(parts[1].to_string()),
-                third_name: None,
-            },
-            3 => Literal::Identifier {
-                first_name: parts[0].to_string(),
-                second_name: Some(parts[1].to_string()),
-                third_name: Some(parts[2].to_string()),
-            },
-            _ => panic!("Invalid identifier: {}", identifier),
+    /// A name of one part.
+    fn name(name: String) -> Literal {
+        Literal::Identifier {
+            first_name: name,
+            second_name: None,
+            third_name: None,
         }
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Node {
     Leaf(Literal),
     LeafType(Type),
@@ -91,12 +331,12 @@ impl Node {
         }
     }
 
-    pub fn children(&self) -> Vec<Node> {
+    pub fn children(&self) -> &[Node] {
         match self {
-            Node::Infix(_, children) => children.to_vec(),
-            Node::Prefix(_, children) => children.to_vec(),
-            Node::Postfix(_, children) => children.to_vec(),
-            _ => vec![],
+            Node::Infix(_, children) => children,
+            Node::Prefix(_, children) => children,
+            Node::Postfix(_, children) => children,
+            _ => &[],
         }
     }
 
@@ -108,11 +348,73 @@ impl Node {
     }
 }
 
+/// Byte offsets of a part of the input, from `start` up to `end`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Where the expressions, names and statements of a parsed statement were
+/// written, for pointing at them in errors. Nodes are told apart by their
+/// path, the indexes of the children leading to them from the statement, so
+/// that equal expressions written twice each have their own span.
+#[derive(Debug, Default, Clone)]
+pub struct Spans {
+    spans: HashMap<Vec<usize>, Span>,
+}
+
+impl Spans {
+    /// The span of a node of `statement`, the node the spans were parsed
+    /// with. Nodes of any other tree have none, even when equal to one of
+    /// it.
+    pub fn get(&self, statement: &Node, node: &Node) -> Option<Span> {
+        let mut path = vec![];
+
+        match find_path(statement, node, &mut path) {
+            true => self.spans.get(&path).copied(),
+            false => None,
+        }
+    }
+}
+
+/// Appends the path from `root` to `node` itself, not just to an equal node,
+/// telling whether there is one.
+fn find_path(root: &Node, node: &Node, path: &mut Vec<usize>) -> bool {
+    if std::ptr::eq(root, node) {
+        return true;
+    }
+
+    for (index, child) in root.children().iter().enumerate() {
+        path.push(index);
+
+        if find_path(child, node, path) {
+            return true;
+        }
+
+        path.pop();
+    }
+
+    false
+}
+
 pub struct Parser<'a> {
     lexer: lexer::Lexer<'a>,
+    /// Number of `?` placeholders seen so far.
+    anonymous_parameters: usize,
+    /// The path of the node being parsed.
+    path: Vec<usize>,
+    /// The paths and spans of the nodes parsed so far, in the order they
+    /// were parsed.
+    spans: Vec<(Vec<usize>, Span)>,
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Op {
     And,
     Or,
@@ -121,6 +423,8 @@ pub enum Op {
     Minus,
     Multiply,
     Divide,
+    Modulo,
+    Concat,
 
     Equals,
     NotEquals,
@@ -137,56 +441,344 @@ pub enum Op {
     Where,
 
     CreateTable,
+    CreateTableAs,
     DropTable,
+    CreateIndex,
+    DropIndex,
     InsertInto,
 
     ColumnDefinition,
     ColumnList,
     Values,
+    Row,
+
+    Update,
+    Set,
+    Delete,
+    Returning,
+
+    PrimaryKey,
+    Unique,
+    ForeignKey,
+    References,
+    NotNull,
+    Default,
+    IfExists,
+    IfNotExists,
+    OnConflict,
+    DoNothing,
+    DoUpdate,
+
+    Merge,
+    WhenMatched,
+    WhenNotMatched,
+
+    Union,
+    UnionAll,
+    With,
+    WithRecursive,
+    Cte,
+
+    FunctionCall,
+    Arguments,
+    Over,
+    PartitionBy,
+    OrderBy,
+    Asc,
+    Desc,
+    Rows,
+    Preceding,
+    Following,
+    CurrentRow,
+
+    CreateView,
+    DropView,
+    OrReplace,
+    Cascade,
+
+    Begin,
+    Commit,
+    Rollback,
+    RollbackTo,
+    Savepoint,
+    Release,
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+
+    Explain,
+    ExplainAnalyze,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(lexer: lexer::Lexer<'a>) -> Self {
-        Parser { lexer }
+        Parser {
+            lexer,
+            anonymous_parameters: 0,
+            path: vec![],
+            spans: vec![],
+        }
+    }
+
+    /// The spans of the nodes parsed so far. An expression in parentheses
+    /// is recorded without them first, and keeps that span.
+    pub fn into_spans(self) -> Spans {
+        let mut spans = HashMap::new();
+
+        for (path, span) in self.spans {
+            spans.entry(path).or_insert(span);
+        }
+
+        Spans { spans }
     }
 
-    pub fn parse(&mut self) -> Node {
-        self.parse_bp(0)
+    /// Records that the node being parsed spans from `start` to the end of
+    /// the last token read.
+    fn spanned(&mut self, node: Node, start: usize) -> Node {
+        let span = Span {
+            start,
+            end: self.lexer.span().end,
+        };
+
+        self.spans.push((self.path.clone(), span));
+
+        node
     }
 
-    fn parse_bp(&mut self, min_bp: u8) -> Node {
-        let mut lhs = match self.lexer.next() {
-            Some(Ok(Token::NumericLiteral(i))) => Node::Leaf(Literal::numeric(i)),
-            Some(Ok(Token::StringLiteral(s))) => Node::Leaf(Literal::string(s)),
-            Some(Ok(Token::Identifier {
-                        first_name,
-                        second_name: None,
-                        third_name: None,
-                    })) => Node::Leaf(Literal::Identifier {
-                first_name: first_name.to_string(),
+    /// Parses a node that goes at `path` below the node being parsed.
+    fn at<T, F>(&mut self, path: &[usize], parse: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ParseError>,
+    {
+        let depth = self.path.len();
+        self.path.extend(path);
+
+        let result = parse(self);
+
+        self.path.truncate(depth);
+        result
+    }
+
+    /// Moves the nodes recorded from `first` on down to the first child of
+    /// the node being parsed, which they become part of when an operator
+    /// follows them.
+    fn nest(&mut self, first: usize) {
+        let depth = self.path.len();
+
+        for (path, _) in &mut self.spans[first..] {
+            path.insert(depth, 0);
+        }
+    }
+
+    /// The error for the last token read, which cannot come where it is, or
+    /// for the end of the input when there was none.
+    fn unexpected(&self, token: Option<Result<Token, LexError>>) -> ParseError {
+        let span = self.lexer.span();
+
+        let kind = match token {
+            Some(Ok(_)) => ParseErrorKind::UnexpectedToken(self.lexer.slice(span).to_string()),
+            Some(Err(error)) => ParseErrorKind::Lex(error),
+            None => ParseErrorKind::UnexpectedEnd,
+        };
+
+        ParseError { kind, span }
+    }
+
+    /// The next token, with a non-reserved keyword read as the name it
+    /// spells, for where a name can stand.
+    fn next_name(&mut self) -> Option<Result<Token<'a>, LexError>> {
+        match self.lexer.next() {
+            Some(Ok(token)) if token.is_non_reserved() => Some(Ok(Token::Identifier {
+                first_name: Cow::Owned(self.lexer.slice(self.lexer.span()).to_lowercase()),
                 second_name: None,
                 third_name: None,
+            })),
+            token => token,
+        }
+    }
+
+    /// An error at the last token read.
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            kind,
+            span: self.lexer.span(),
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Node, ParseError> {
+        let node = self.parse_statement()?;
+
+        if let Some(Ok(Token::Semicolon)) = self.lexer.peek() {
+            self.lexer.next();
+        }
+
+        match self.lexer.next() {
+            None => Ok(node),
+            s => Err(self.unexpected(s)),
+        }
+    }
+
+    /// Parses a statement, or an expression standing on its own. Statement
+    /// keywords are not expressions, so anywhere else only a query in
+    /// parentheses can start with one.
+    fn parse_statement(&mut self) -> Result<Node, ParseError> {
+        match self.lexer.peek() {
+            Some(Ok(Token::Select))
+            | Some(Ok(Token::Create))
+            | Some(Ok(Token::Drop))
+            | Some(Ok(Token::Insert))
+            | Some(Ok(Token::Update))
+            | Some(Ok(Token::Delete))
+            | Some(Ok(Token::Merge))
+            | Some(Ok(Token::With))
+            | Some(Ok(Token::Explain))
+            | Some(Ok(Token::Begin))
+            | Some(Ok(Token::Commit))
+            | Some(Ok(Token::Rollback))
+            | Some(Ok(Token::Savepoint))
+            | Some(Ok(Token::Release)) => {}
+            _ => return self.parse_bp(0),
+        }
+
+        let token = self.lexer.next();
+        let start = self.lexer.span().start;
+
+        let node = match token {
+            Some(Ok(Token::Select)) => self.parse_select(0)?,
+            Some(Ok(Token::Create)) => self.parse_create(0)?,
+            Some(Ok(Token::Drop)) => self.parse_drop(0)?,
+            Some(Ok(Token::Insert)) => self.parse_insert(0)?,
+            Some(Ok(Token::Update)) => self.parse_update(0)?,
+            Some(Ok(Token::Delete)) => self.parse_delete(0)?,
+            Some(Ok(Token::Merge)) => self.parse_merge(0)?,
+            Some(Ok(Token::With)) => self.parse_with(0)?,
+            Some(Ok(Token::Explain)) => {
+                let op = match self.lexer.peek() {
+                    Some(Ok(Token::Analyze)) => {
+                        self.lexer.next();
+                        Op::ExplainAnalyze
+                    }
+                    _ => Op::Explain,
+                };
+
+                Node::Prefix(op, vec![self.at(&[0], Self::parse_statement)?])
+            }
+            Some(Ok(Token::Begin)) => self.parse_begin()?,
+            Some(Ok(Token::Commit)) => {
+                self.parse_optional_transaction();
+                Node::Prefix(Op::Commit, vec![])
+            }
+            Some(Ok(Token::Rollback)) => self.parse_rollback()?,
+            Some(Ok(Token::Savepoint)) => Node::Prefix(
+                Op::Savepoint,
+                vec![Node::Leaf(self.at(&[0], Self::parse_name)?)],
+            ),
+            Some(Ok(Token::Release)) => {
+                if let Some(Ok(Token::Savepoint)) = self.lexer.peek() {
+                    self.lexer.next();
+                }
+
+                Node::Prefix(
+                    Op::Release,
+                    vec![Node::Leaf(self.at(&[0], Self::parse_name)?)],
+                )
+            }
+            s => return Err(self.unexpected(s)),
+        };
+
+        Ok(self.spanned(node, start))
+    }
+
+    /// Parses a single expression, stopping before a top-level comma.
+    fn parse_expression(&mut self) -> Result<Node, ParseError> {
+        let (_, r_bp) = Self::infix_operator_bp(&Op::Comma).unwrap();
+
+        self.parse_bp(r_bp)
+    }
+
+    fn parse_bp(&mut self, min_bp: u8) -> Result<Node, ParseError> {
+        let token = self.next_name();
+        let start = self.lexer.span().start;
+        let first = self.spans.len();
+
+        let lhs = match token {
+            Some(Ok(Token::NumericLiteral(i))) => Node::Leaf(self.numeric(&i)?),
+            Some(Ok(Token::StringLiteral(s))) => Node::Leaf(Literal::string(s)),
+            Some(Ok(Token::BooleanLiteral(b))) => Node::Leaf(Literal::Bool(b)),
+            Some(Ok(Token::Null)) => Node::Leaf(Literal::Null),
+            Some(Ok(Token::Parameter(parameter))) => {
+                Node::Leaf(Literal::Parameter(self.parameter(parameter)?))
+            }
+            Some(Ok(Token::Asterisk)) => Node::Leaf(Literal::Wildcard),
+            Some(Ok(Token::Date)) => {
+                Node::Leaf(self.parse_typed_string(|s| Date::parse(s).map(Literal::Date))?)
+            }
+            Some(Ok(Token::Time)) => {
+                Node::Leaf(self.parse_typed_string(|s| Time::parse(s).map(Literal::Time))?)
+            }
+            Some(Ok(Token::Timestamp)) | Some(Ok(Token::DateTime)) => Node::Leaf(
+                self.parse_typed_string(|s| Timestamp::parse(s).map(Literal::Timestamp))?,
+            ),
+            Some(Ok(Token::Interval)) => {
+                Node::Leaf(self.parse_typed_string(|s| Interval::parse(s).map(Literal::Interval))?)
+            }
+            Some(Ok(Token::Identifier {
+                first_name,
+                second_name,
+                third_name,
+            })) => Node::Leaf(Literal::Identifier {
+                first_name: first_name.into_owned(),
+                second_name: second_name.map(Cow::into_owned),
+                third_name: third_name.map(Cow::into_owned),
             }),
             Some(Ok(Token::Not)) => {
-                let ((), r_bp) = Self::prefix_operator_bp(&Op::Not);
-                let rhs = self.parse_bp(r_bp);
+                let ((), r_bp) = Self::prefix_operator_bp(&Op::Not).unwrap();
+                let rhs = self.at(&[0], |parser| parser.parse_bp(r_bp))?;
                 Node::Prefix(Op::Not, vec![rhs])
             }
+            Some(Ok(Token::Minus)) => {
+                let ((), r_bp) = Self::prefix_operator_bp(&Op::Minus).unwrap();
+
+                let rhs = self.at(&[0], |parser| parser.parse_bp(r_bp))?;
+
+                match rhs.literal().and_then(|literal| literal.negated()) {
+                    Some(literal) => {
+                        // `-1` is one number, with no node for the `1`.
+                        self.spans.truncate(first);
+                        Node::Leaf(literal)
+                    }
+                    None => Node::Prefix(Op::Minus, vec![rhs]),
+                }
+            }
+            Some(Ok(Token::Plus)) => {
+                let ((), r_bp) = Self::prefix_operator_bp(&Op::Plus).unwrap();
+                let rhs = self.at(&[0], |parser| parser.parse_bp(r_bp))?;
+                Node::Prefix(Op::Plus, vec![rhs])
+            }
             Some(Ok(Token::OpenParen)) => {
-                let lhs = self.parse_bp(0);
+                let lhs = match self.lexer.peek() {
+                    Some(Ok(Token::Select)) => {
+                        self.lexer.next();
+                        self.parse_select(0)?
+                    }
+                    Some(Ok(Token::With)) => {
+                        self.lexer.next();
+                        self.parse_with(0)?
+                    }
+                    _ => self.parse_bp(0)?,
+                };
 
                 match self.lexer.next() {
                     Some(Ok(Token::CloseParen)) => lhs,
-                    s => panic!("Unexpected token: {:?}", s),
+                    s => return Err(self.unexpected(s)),
                 }
             }
-            Some(Ok(Token::Select)) => self.parse_select(min_bp),
-            Some(Ok(Token::Create)) => self.parse_create(min_bp),
-            Some(Ok(Token::Drop)) => self.parse_drop(min_bp),
-            Some(Ok(Token::Insert)) => self.parse_insert(min_bp),
-            s => panic!("Unexpected token: {:?}", s),
+            s => return Err(self.unexpected(s)),
         };
 
+        let mut lhs = self.spanned(lhs, start);
+
         loop {
             let op = match self.lexer.peek() {
                 Some(Ok(Token::And)) => Op::And,
@@ -195,6 +787,8 @@ impl<'a> Parser<'a> {
                 Some(Ok(Token::Minus)) => Op::Minus,
                 Some(Ok(Token::Asterisk)) => Op::Multiply,
                 Some(Ok(Token::Slash)) => Op::Divide,
+                Some(Ok(Token::Percent)) => Op::Modulo,
+                Some(Ok(Token::Concat)) => Op::Concat,
                 Some(Ok(Token::Equals)) => Op::Equals,
                 Some(Ok(Token::NotEquals)) => Op::NotEquals,
                 Some(Ok(Token::LessThan)) => Op::LessThan,
@@ -203,12 +797,20 @@ impl<'a> Parser<'a> {
                 Some(Ok(Token::GreaterThanOrEquals)) => Op::GreaterThanOrEquals,
                 Some(Ok(Token::CloseParen)) => Op::CloseParen,
                 Some(Ok(Token::Comma)) => Op::Comma,
+                Some(Ok(Token::OpenParen)) => Op::FunctionCall,
                 _ => break,
             };
 
-            // postfix bp
-            if let Some((_l_bp, ())) = Self::postfix_operator_bp(&op) {
-                // operate with postfix
+            if let Some((l_bp, ())) = Self::postfix_operator_bp(&op) {
+                if l_bp < min_bp {
+                    break;
+                }
+                self.lexer.next();
+                self.nest(first);
+
+                let call = self.parse_function_call(lhs)?;
+                lhs = self.spanned(call, start);
+
                 continue;
             }
 
@@ -217,10 +819,11 @@ impl<'a> Parser<'a> {
                     break;
                 }
                 self.lexer.next();
+                self.nest(first);
 
-                let rhs = self.parse_bp(r_bp);
+                let rhs = self.at(&[1], |parser| parser.parse_bp(r_bp))?;
 
-                lhs = Node::Infix(op, vec![lhs, rhs]);
+                lhs = self.spanned(Node::Infix(op, vec![lhs, rhs]), start);
 
                 continue;
             }
@@ -228,504 +831,2563 @@ impl<'a> Parser<'a> {
             break;
         }
 
-        lhs
+        Ok(lhs)
     }
 
-    fn parse_drop(&mut self, min_bp: u8) -> Node {
-        match self.lexer.next() {
-            Some(Ok(Token::Table)) => self.parse_drop_table(min_bp),
-            s => panic!("Unexpected token: {:?}", s),
-        }
+    /// The literal for a number the lexer has read. Only numbers too large
+    /// for any literal have none.
+    fn numeric(&self, i: &str) -> Result<Literal, ParseError> {
+        Literal::numeric(i).ok_or_else(|| {
+            self.error(ParseErrorKind::Lex(LexError::NumericOverflow(
+                i.to_string(),
+            )))
+        })
     }
 
-    fn parse_drop_table(&mut self, _min_bp: u8) -> Node {
-        let lhs = match self.lexer.next() {
-            Some(Ok(Token::Identifier {
-                        first_name,
-                        second_name: None,
-                        third_name: None,
-                    })) => Literal::identifier(first_name),
-            s => panic!("Unexpected token: {:?}", s),
-        };
-
-        Node::Prefix(Op::DropTable, vec![Node::Leaf(lhs)])
+    /// The string after a type name, as in `DATE '2024-01-31'`.
+    fn parse_typed_string(
+        &mut self,
+        literal: fn(&str) -> Result<Literal, TemporalError>,
+    ) -> Result<Literal, ParseError> {
+        match self.lexer.next() {
+            Some(Ok(Token::StringLiteral(s))) => {
+                literal(&s).map_err(|error| self.error(ParseErrorKind::Temporal(error)))
+            }
+            s => Err(self.unexpected(s)),
+        }
     }
 
-    fn parse_create(&mut self, min_bp: u8) -> Node {
-        match self.lexer.next() {
-            Some(Ok(Token::Table)) => self.parse_create_table(min_bp),
-            s => panic!("Unexpected token: {:?}", s),
+    fn parameter(&mut self, parameter: &str) -> Result<Parameter, ParseError> {
+        match parameter.split_at(1) {
+            ("?", _) => {
+                self.anonymous_parameters += 1;
+                Ok(Parameter::Positional(self.anonymous_parameters))
+            }
+            ("$", number) => match number.parse() {
+                Ok(number) if number > 0 => Ok(Parameter::Positional(number)),
+                _ => Err(self.error(ParseErrorKind::InvalidParameter(parameter.to_string()))),
+            },
+            (_, name) => Ok(Parameter::Named(name.to_string())),
         }
     }
 
-    fn parse_create_table(&mut self, _min_bp: u8) -> Node {
-        let lhs = match self.lexer.next() {
-            Some(Ok(Token::Identifier {
-                        first_name,
-                        second_name: None,
-                        third_name: None,
-                    })) => Literal::identifier(first_name),
-            s => panic!("Unexpected token: {:?}", s),
-        };
+    /// `BEGIN [TRANSACTION] [ISOLATION LEVEL level]`.
+    fn parse_begin(&mut self) -> Result<Node, ParseError> {
+        self.parse_optional_transaction();
+
+        if let Some(Ok(Token::Isolation)) = self.lexer.peek() {
+            self.lexer.next();
+            self.expect(Token::Level)?;
+
+            let level = match self.lexer.next() {
+                Some(Ok(Token::Read)) => match self.lexer.next() {
+                    Some(Ok(Token::Uncommitted)) => Op::ReadUncommitted,
+                    Some(Ok(Token::Committed)) => Op::ReadCommitted,
+                    s => return Err(self.unexpected(s)),
+                },
+                Some(Ok(Token::Repeatable)) => {
+                    self.expect(Token::Read)?;
+                    Op::RepeatableRead
+                }
+                Some(Ok(Token::Serializable)) => Op::Serializable,
+                s => return Err(self.unexpected(s)),
+            };
 
-        match self.lexer.next() {
-            Some(Ok(Token::OpenParen)) => {
-                let mut columns = vec![];
+            return Ok(Node::Prefix(Op::Begin, vec![Node::Prefix(level, vec![])]));
+        }
 
-                loop {
-                    let column_name = match self.lexer.next() {
-                        Some(Ok(Token::Identifier {
-                                    first_name,
-                                    second_name: None,
-                                    third_name: None,
-                                })) => Literal::identifier(first_name),
-                        Some(Ok(Token::CloseParen)) => break,
-                        s => panic!("Unexpected token: {:?}", s),
-                    };
+        Ok(Node::Prefix(Op::Begin, vec![]))
+    }
 
-                    match self.lexer.next() {
-                        Some(Ok(Token::Int)) => {
-                            columns.push(Node::Infix(
-                                Op::ColumnDefinition,
-                                vec![Node::Leaf(column_name), Node::LeafType(Type::Int)],
-                            ));
-                        }
-                        s => panic!("Unexpected token: {:?}", s),
-                    }
+    /// `ROLLBACK [TRANSACTION]` or `ROLLBACK [TRANSACTION] TO [SAVEPOINT] name`.
+    fn parse_rollback(&mut self) -> Result<Node, ParseError> {
+        self.parse_optional_transaction();
 
-                    match self.lexer.next() {
-                        Some(Ok(Token::Comma)) => continue,
-                        Some(Ok(Token::CloseParen)) => break,
-                        s => panic!("Unexpected token: {:?}", s),
-                    }
-                }
+        match self.lexer.peek() {
+            Some(Ok(Token::To)) => {
+                self.lexer.next();
 
-                if columns.len() == 1 {
-                    Node::Prefix(
-                        Op::CreateTable,
-                        vec![Node::Leaf(lhs), columns.pop().unwrap()],
-                    )
-                } else {
-                    Node::Prefix(
-                        Op::CreateTable,
-                        vec![Node::Leaf(lhs), Node::Infix(Op::Comma, columns)],
-                    )
+                if let Some(Ok(Token::Savepoint)) = self.lexer.peek() {
+                    self.lexer.next();
                 }
+
+                Ok(Node::Prefix(
+                    Op::RollbackTo,
+                    vec![Node::Leaf(self.at(&[0], Self::parse_name)?)],
+                ))
             }
-            s => panic!("Unexpected token: {:?}", s),
+            _ => Ok(Node::Prefix(Op::Rollback, vec![])),
         }
     }
 
-    fn parse_select(&mut self, min_bp: u8) -> Node {
-        let rhs = self.parse_bp(0);
-
-        match self.lexer.next() {
-            Some(Ok(Token::From)) => Node::Prefix(Op::Select, vec![rhs, self.parse_from(min_bp)]),
-            None => Node::Prefix(Op::Select, vec![rhs]),
-            s => panic!("Unexpected token: {:?}", s),
+    fn parse_optional_transaction(&mut self) {
+        if let Some(Ok(Token::Transaction)) = self.lexer.peek() {
+            self.lexer.next();
         }
     }
 
-    fn parse_from(&mut self, min_bp: u8) -> Node {
-        let rhs = self.parse_bp(0);
-
+    fn parse_drop(&mut self, min_bp: u8) -> Result<Node, ParseError> {
         match self.lexer.next() {
-            Some(Ok(Token::Where)) => Node::Prefix(Op::From, vec![rhs, self.parse_where(min_bp)]),
-            None => Node::Prefix(Op::From, vec![rhs]),
-            s => panic!("Unexpected token: {:?}", s),
+            Some(Ok(Token::Table)) => self.parse_drop_table(min_bp),
+            Some(Ok(Token::View)) => self.parse_drop_view(min_bp),
+            Some(Ok(Token::Index)) => self.parse_drop_index(),
+            s => Err(self.unexpected(s)),
         }
     }
 
-    fn parse_where(&mut self, _min_bp: u8) -> Node {
-        let rhs = self.parse_bp(0);
+    fn parse_drop_table(&mut self, _min_bp: u8) -> Result<Node, ParseError> {
+        let if_exists = self.parse_optional_if_exists()?;
 
-        match self.lexer.next() {
-            None => Node::Prefix(Op::Where, vec![rhs]),
-            s => panic!("Unexpected token: {:?}", s),
+        let mut children = vec![Node::Leaf(self.at(&[0], Self::parse_name)?)];
+
+        while let Some(Ok(Token::Comma)) = self.lexer.peek() {
+            self.lexer.next();
+            children.push(Node::Leaf(self.at(&[children.len()], Self::parse_name)?));
         }
+
+        if if_exists {
+            children.push(Node::Prefix(Op::IfExists, vec![]));
+        }
+
+        children.extend(self.parse_optional_cascade());
+
+        Ok(Node::Prefix(Op::DropTable, children))
     }
 
-    fn parse_insert(&mut self, min_bp: u8) -> Node {
-        match self.lexer.next() {
-            Some(Ok(Token::Into)) => self.parse_insert_into(min_bp),
-            s => panic!("Unexpected token: {:?}", s),
+    /// `IF EXISTS`, as in `DROP TABLE IF EXISTS`.
+    fn parse_optional_if_exists(&mut self) -> Result<bool, ParseError> {
+        match self.lexer.peek() {
+            Some(Ok(Token::If)) => {
+                self.lexer.next();
+                self.expect(Token::Exists)?;
+                Ok(true)
+            }
+            _ => Ok(false),
         }
     }
 
-    fn parse_insert_into(&mut self, _min_bp: u8) -> Node {
-        let lhs = match self.lexer.next() {
-            Some(Ok(Token::Identifier {
-                        first_name,
-                        second_name: None,
-                        third_name: None,
-                    })) => Literal::identifier(first_name),
-            s => panic!("Unexpected token: {:?}", s),
-        };
+    fn parse_drop_view(&mut self, _min_bp: u8) -> Result<Node, ParseError> {
+        let mut children = vec![Node::Leaf(self.at(&[0], Self::parse_name)?)];
+        children.extend(self.parse_optional_cascade());
 
-        match self.lexer.next() {
-            Some(Ok(Token::OpenParen)) => {
-                let mut columns = vec![];
+        Ok(Node::Prefix(Op::DropView, children))
+    }
 
-                loop {
-                    let column_name = match self.lexer.next() {
-                        Some(Ok(Token::Identifier {
-                                    first_name,
-                                    second_name: None,
-                                    third_name: None,
-                                })) => Literal::identifier(first_name),
-                        Some(Ok(Token::Comma)) => continue,
-                        Some(Ok(Token::CloseParen)) => break,
-                        s => panic!("Unexpected token: {:?}", s),
-                    };
+    fn parse_drop_index(&mut self) -> Result<Node, ParseError> {
+        let if_exists = self.parse_optional_if_exists()?;
 
-                    columns.push(Node::Leaf(column_name));
-                }
+        let mut children = vec![Node::Leaf(self.at(&[0], Self::parse_name)?)];
 
-                let values = match self.lexer.next() {
-                    Some(Ok(Token::Values)) => self.parse_values(),
-                    s => panic!("Unexpected token: {:?}", s),
-                };
+        if if_exists {
+            children.push(Node::Prefix(Op::IfExists, vec![]));
+        }
 
-                Node::Prefix(
-                    Op::InsertInto,
-                    vec![
-                        Node::Leaf(lhs),
-                        Node::Prefix(Op::ColumnList, columns),
-                        Node::Prefix(Op::Values, values),
-                    ],
-                )
+        Ok(Node::Prefix(Op::DropIndex, children))
+    }
+
+    /// `CASCADE` or `RESTRICT`, the default, which leaves no node.
+    fn parse_optional_cascade(&mut self) -> Option<Node> {
+        match self.lexer.peek() {
+            Some(Ok(Token::Cascade)) => {
+                self.lexer.next();
+                Some(Node::Prefix(Op::Cascade, vec![]))
+            }
+            Some(Ok(Token::Restrict)) => {
+                self.lexer.next();
+                None
             }
-            s => panic!("Unexpected token: {:?}", s),
+            _ => None,
         }
     }
 
-    fn parse_values(&mut self) -> Vec<Node> {
+    fn parse_create(&mut self, min_bp: u8) -> Result<Node, ParseError> {
         match self.lexer.next() {
-            Some(Ok(Token::OpenParen)) => {
-                let mut values = vec![];
+            Some(Ok(Token::Table)) => self.parse_create_table(min_bp),
+            Some(Ok(Token::View)) => self.parse_create_view(false),
+            Some(Ok(Token::Index)) => self.parse_create_index(false),
+            Some(Ok(Token::Unique)) => {
+                self.expect(Token::Index)?;
+                self.parse_create_index(true)
+            }
+            Some(Ok(Token::Or)) => {
+                self.expect(Token::Replace)?;
+                self.expect(Token::View)?;
+                self.parse_create_view(true)
+            }
+            s => Err(self.unexpected(s)),
+        }
+    }
+
+    /// `CREATE [UNIQUE] INDEX [IF NOT EXISTS] name ON table (columns)`.
+    fn parse_create_index(&mut self, unique: bool) -> Result<Node, ParseError> {
+        let if_not_exists = self.parse_optional_if_not_exists()?;
+
+        let name = self.at(&[0], Self::parse_name)?;
+        self.expect(Token::On)?;
+        let table = self.at(&[1], Self::parse_name)?;
+        let columns = self.at(&[2], Self::parse_column_list)?;
+
+        let mut children = vec![
+            Node::Leaf(name),
+            Node::Leaf(table),
+            Node::Prefix(Op::ColumnList, columns),
+        ];
+
+        if unique {
+            children.push(Node::Prefix(Op::Unique, vec![]));
+        }
+
+        if if_not_exists {
+            children.push(Node::Prefix(Op::IfNotExists, vec![]));
+        }
+
+        Ok(Node::Prefix(Op::CreateIndex, children))
+    }
+
+    /// `IF NOT EXISTS`, as in `CREATE TABLE IF NOT EXISTS`.
+    fn parse_optional_if_not_exists(&mut self) -> Result<bool, ParseError> {
+        match self.lexer.peek() {
+            Some(Ok(Token::If)) => {
+                self.lexer.next();
+                self.expect(Token::Not)?;
+                self.expect(Token::Exists)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// The view keeps the text of its query next to the parsed query, so it
+    /// can be stored and analyzed again whenever the view is used.
+    fn parse_create_view(&mut self, or_replace: bool) -> Result<Node, ParseError> {
+        let name = self.at(&[0], Self::parse_name)?;
+
+        let columns = match self.lexer.peek() {
+            Some(Ok(Token::OpenParen)) => self.at(&[1], Self::parse_column_list)?,
+            _ => vec![],
+        };
+
+        self.expect(Token::As)?;
+
+        let text = self
+            .lexer
+            .rest()
+            .trim()
+            .trim_end_matches(';')
+            .trim_end()
+            .to_string();
+
+        self.expect(Token::Select)?;
+        let query = self.at(&[3], |parser| parser.parse_select(0))?;
+
+        let mut children = vec![
+            Node::Leaf(name),
+            Node::Prefix(Op::ColumnList, columns),
+            Node::Leaf(Literal::String(text)),
+            query,
+        ];
+
+        if or_replace {
+            children.push(Node::Prefix(Op::OrReplace, vec![]));
+        }
+
+        Ok(Node::Prefix(Op::CreateView, children))
+    }
+
+    fn parse_create_table(&mut self, _min_bp: u8) -> Result<Node, ParseError> {
+        let if_not_exists = self.parse_optional_if_not_exists()?;
+
+        let lhs = self.at(&[0], Self::parse_name)?;
+
+        let options = match if_not_exists {
+            true => vec![Node::Prefix(Op::IfNotExists, vec![])],
+            false => vec![],
+        };
+
+        match self.lexer.next() {
+            Some(Ok(Token::As)) => {
+                self.expect(Token::Select)?;
+
+                let mut children = vec![
+                    Node::Leaf(lhs),
+                    self.at(&[1], |parser| parser.parse_select(0))?,
+                ];
+                children.extend(options);
+
+                Ok(Node::Prefix(Op::CreateTableAs, children))
+            }
+            Some(Ok(Token::OpenParen)) => {
+                let first = self.spans.len();
+                let mut columns = vec![];
 
                 loop {
-                    let value = match self.lexer.next() {
-                        Some(Ok(Token::NumericLiteral(i))) => Node::Leaf(Literal::numeric(i)),
-                        Some(Ok(Token::CloseParen)) => break,
+                    let column_name = match self.next_name() {
+                        Some(Ok(Token::Identifier {
+                            first_name,
+                            second_name: None,
+                            third_name: None,
+                        })) => Literal::name(first_name.into_owned()),
+                        Some(Ok(Token::Primary)) => {
+                            self.expect(Token::Key)?;
+                            let path = [1, columns.len()];
+                            columns.push(Node::Prefix(
+                                Op::PrimaryKey,
+                                self.at(&path, Self::parse_column_list)?,
+                            ));
+                            continue;
+                        }
+                        Some(Ok(Token::Unique)) => {
+                            let path = [1, columns.len()];
+                            columns.push(Node::Prefix(
+                                Op::Unique,
+                                self.at(&path, Self::parse_column_list)?,
+                            ));
+                            continue;
+                        }
+                        Some(Ok(Token::Foreign)) => {
+                            self.expect(Token::Key)?;
+                            let index = columns.len();
+                            let referencing = Node::Prefix(
+                                Op::ColumnList,
+                                self.at(&[1, index, 0], Self::parse_column_list)?,
+                            );
+                            self.expect(Token::References)?;
+                            let references = self.at(&[1, index, 1], Self::parse_references)?;
+                            columns
+                                .push(Node::Prefix(Op::ForeignKey, vec![referencing, references]));
+                            continue;
+                        }
                         Some(Ok(Token::Comma)) => continue,
-                        s => panic!("Unexpected token: {:?}", s),
+                        Some(Ok(Token::CloseParen)) => break,
+                        s => return Err(self.unexpected(s)),
                     };
 
-                    values.push(value);
+                    let column_type = self.parse_type()?;
+
+                    let mut definition = vec![Node::Leaf(column_name), Node::LeafType(column_type)];
+
+                    loop {
+                        match self.lexer.peek() {
+                            Some(Ok(Token::Primary)) => {
+                                self.lexer.next();
+                                self.expect(Token::Key)?;
+                                definition.push(Node::Prefix(Op::PrimaryKey, vec![]));
+                            }
+                            Some(Ok(Token::Unique)) => {
+                                self.lexer.next();
+                                definition.push(Node::Prefix(Op::Unique, vec![]));
+                            }
+                            Some(Ok(Token::References)) => {
+                                self.lexer.next();
+                                let path = [1, columns.len(), definition.len()];
+                                definition.push(self.at(&path, Self::parse_references)?);
+                            }
+                            Some(Ok(Token::Not)) => {
+                                self.lexer.next();
+                                self.expect(Token::Null)?;
+                                definition.push(Node::Prefix(Op::NotNull, vec![]));
+                            }
+                            // Columns are nullable unless declared otherwise.
+                            Some(Ok(Token::Null)) => {
+                                self.lexer.next();
+                            }
+                            Some(Ok(Token::Default)) => {
+                                self.lexer.next();
+                                let (_, r_bp) = Self::infix_operator_bp(&Op::Comma).unwrap();
+                                let path = [1, columns.len(), definition.len(), 0];
+                                let value = self.at(&path, |parser| parser.parse_bp(r_bp))?;
+                                definition.push(Node::Prefix(Op::Default, vec![value]));
+                            }
+                            _ => break,
+                        }
+                    }
+
+                    columns.push(Node::Infix(Op::ColumnDefinition, definition));
+
+                    match self.lexer.next() {
+                        Some(Ok(Token::Comma)) => continue,
+                        Some(Ok(Token::CloseParen)) => break,
+                        s => return Err(self.unexpected(s)),
+                    }
                 }
 
-                values
+                let columns = if columns.len() == 1 {
+                    // A single column is not wrapped in a list.
+                    let depth = self.path.len() + 1;
+
+                    for (path, _) in &mut self.spans[first..] {
+                        path.remove(depth);
+                    }
+
+                    columns.pop().unwrap()
+                } else {
+                    Node::Infix(Op::Comma, columns)
+                };
+
+                let mut children = vec![Node::Leaf(lhs), columns];
+                children.extend(options);
+
+                Ok(Node::Prefix(Op::CreateTable, children))
             }
-            s => panic!("Unexpected token: {:?}", s),
+            s => Err(self.unexpected(s)),
         }
     }
 
-    fn prefix_operator_bp(op: &Op) -> ((), u8) {
-        match op {
-            Op::Not => ((), 7),
-            _ => panic!("Unexpected prefix operator: {:?}", op),
+    /// The target of a foreign key, after `REFERENCES`: a table and an
+    /// optional column list, left empty to mean its primary key.
+    fn parse_references(&mut self) -> Result<Node, ParseError> {
+        let table = self.at(&[0], Self::parse_name)?;
+
+        let columns = match self.lexer.peek() {
+            Some(Ok(Token::OpenParen)) => self.at(&[1], Self::parse_column_list)?,
+            _ => vec![],
+        };
+
+        Ok(Node::Prefix(
+            Op::References,
+            vec![Node::Leaf(table), Node::Prefix(Op::ColumnList, columns)],
+        ))
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        match self.lexer.next() {
+            Some(Ok(token)) if token == expected => Ok(()),
+            s => Err(self.unexpected(s)),
         }
     }
 
-    fn postfix_operator_bp(op: &Op) -> Option<(u8, ())> {
-        match op {
-            _ => None,
+    fn parse_column_list(&mut self) -> Result<Vec<Node>, ParseError> {
+        self.expect(Token::OpenParen)?;
+
+        let mut columns = vec![];
+
+        loop {
+            columns.push(Node::Leaf(self.at(&[columns.len()], Self::parse_name)?));
+
+            match self.lexer.next() {
+                Some(Ok(Token::Comma)) => continue,
+                Some(Ok(Token::CloseParen)) => break,
+                s => return Err(self.unexpected(s)),
+            }
         }
+
+        Ok(columns)
     }
 
-    fn infix_operator_bp(op: &Op) -> Option<(u8, u8)> {
-        match op {
-            Op::Or => Some((1, 2)),
-            Op::And => Some((3, 4)),
+    fn parse_type(&mut self) -> Result<Type, ParseError> {
+        match self.lexer.next() {
+            Some(Ok(Token::Int))
+            | Some(Ok(Token::Integer))
+            | Some(Ok(Token::SmallInt))
+            | Some(Ok(Token::TinyInt))
+            | Some(Ok(Token::BigInt)) => Ok(Type::Int),
+            Some(Ok(Token::Float)) | Some(Ok(Token::Real)) | Some(Ok(Token::Double)) => {
+                Ok(Type::Float)
+            }
+            Some(Ok(Token::Decimal)) | Some(Ok(Token::Numeric)) => Ok(Type::Decimal),
+            Some(Ok(Token::VarChar)) | Some(Ok(Token::Char)) | Some(Ok(Token::Text)) => {
+                Ok(Type::String)
+            }
+            Some(Ok(Token::Boolean)) => Ok(Type::Bool),
+            Some(Ok(Token::Date)) => Ok(Type::Date),
+            Some(Ok(Token::Time)) => Ok(Type::Time),
+            Some(Ok(Token::Timestamp)) | Some(Ok(Token::DateTime)) => Ok(Type::Timestamp),
+            Some(Ok(Token::Interval)) => Ok(Type::Interval),
+            s => Err(self.unexpected(s)),
+        }
+    }
 
-            Op::Equals => Some((4, 5)),
-            Op::NotEquals => Some((4, 5)),
-            Op::LessThan => Some((4, 5)),
-            Op::GreaterThan => Some((4, 5)),
-            Op::LessThanOrEquals => Some((4, 5)),
-            Op::GreaterThanOrEquals => Some((4, 5)),
+    /// Parses a SELECT, with the `SELECT` keyword already consumed, followed
+    /// by any number of `UNION [ALL] SELECT ...`.
+    fn parse_select(&mut self, min_bp: u8) -> Result<Node, ParseError> {
+        let first = self.spans.len();
+        let mut lhs = self.parse_simple_select(min_bp)?;
 
-            Op::Comma => Some((4, 5)),
-            Op::Plus => Some((6, 7)),
-            Op::Minus => Some((6, 7)),
+        while let Some(Ok(Token::Union)) = self.lexer.peek() {
+            self.lexer.next();
 
-            Op::Multiply => Some((8, 9)),
-            Op::Divide => Some((8, 9)),
+            let op = match self.lexer.peek() {
+                Some(Ok(Token::All)) => {
+                    self.lexer.next();
+                    Op::UnionAll
+                }
+                _ => Op::Union,
+            };
+
+            self.expect(Token::Select)?;
+            self.nest(first);
+
+            lhs = Node::Infix(
+                op,
+                vec![
+                    lhs,
+                    self.at(&[1], |parser| parser.parse_simple_select(min_bp))?,
+                ],
+            );
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_simple_select(&mut self, min_bp: u8) -> Result<Node, ParseError> {
+        let rhs = self.at(&[0], |parser| parser.parse_bp(0))?;
+
+        match self.lexer.peek() {
+            Some(Ok(Token::From)) => {
+                self.lexer.next();
+                Ok(Node::Prefix(
+                    Op::Select,
+                    vec![rhs, self.at(&[1], |parser| parser.parse_from(min_bp))?],
+                ))
+            }
+            _ => Ok(Node::Prefix(Op::Select, vec![rhs])),
+        }
+    }
+
+    fn parse_with(&mut self, _min_bp: u8) -> Result<Node, ParseError> {
+        let op = match self.lexer.peek() {
+            Some(Ok(Token::Recursive)) => {
+                self.lexer.next();
+                Op::WithRecursive
+            }
+            _ => Op::With,
+        };
+
+        let mut children = vec![];
+
+        loop {
+            let index = children.len();
+            let name = self.at(&[index, 0], Self::parse_name)?;
+
+            let columns = match self.lexer.peek() {
+                Some(Ok(Token::OpenParen)) => self.at(&[index, 1], Self::parse_column_list)?,
+                _ => vec![],
+            };
+
+            self.expect(Token::As)?;
+            self.expect(Token::OpenParen)?;
+            self.expect(Token::Select)?;
+
+            let query = self.at(&[index, 2], |parser| parser.parse_select(0))?;
+
+            self.expect(Token::CloseParen)?;
+
+            children.push(Node::Prefix(
+                Op::Cte,
+                vec![
+                    Node::Leaf(name),
+                    Node::Prefix(Op::ColumnList, columns),
+                    query,
+                ],
+            ));
+
+            match self.lexer.peek() {
+                Some(Ok(Token::Comma)) => {
+                    self.lexer.next();
+                }
+                _ => break,
+            }
+        }
+
+        self.expect(Token::Select)?;
+        children.push(self.at(&[children.len()], |parser| parser.parse_select(0))?);
+
+        Ok(Node::Prefix(op, children))
+    }
+
+    /// Parses the arguments of a call to `name`, with the opening parenthesis
+    /// already consumed, and an optional `OVER (...)` window.
+    fn parse_function_call(&mut self, name: Node) -> Result<Node, ParseError> {
+        match name {
+            Node::Leaf(Literal::Identifier {
+                second_name: None, ..
+            }) => {}
+            // Only plain names can be called; the parenthesis is where
+            // that shows.
+            _ => return Err(self.error(ParseErrorKind::UnexpectedToken("(".to_string()))),
+        }
+
+        let mut arguments = vec![];
+
+        if let Some(Ok(Token::CloseParen)) = self.lexer.peek() {
+            self.lexer.next();
+        } else {
+            loop {
+                arguments.push(self.at(&[1, arguments.len()], Self::parse_expression)?);
+
+                match self.lexer.next() {
+                    Some(Ok(Token::Comma)) => continue,
+                    Some(Ok(Token::CloseParen)) => break,
+                    s => return Err(self.unexpected(s)),
+                }
+            }
+        }
+
+        let mut children = vec![name, Node::Prefix(Op::Arguments, arguments)];
+
+        if let Some(Ok(Token::Over)) = self.lexer.peek() {
+            self.lexer.next();
+            children.push(self.at(&[2], Self::parse_window)?);
+        }
+
+        Ok(Node::Postfix(Op::FunctionCall, children))
+    }
+
+    fn parse_window(&mut self) -> Result<Node, ParseError> {
+        self.expect(Token::OpenParen)?;
+
+        let mut children = vec![];
+
+        if let Some(Ok(Token::Partition)) = self.lexer.peek() {
+            self.lexer.next();
+            self.expect(Token::By)?;
+
+            let index = children.len();
+            let mut expressions = vec![self.at(&[index, 0], Self::parse_expression)?];
+
+            while let Some(Ok(Token::Comma)) = self.lexer.peek() {
+                self.lexer.next();
+                expressions.push(self.at(&[index, expressions.len()], Self::parse_expression)?);
+            }
+
+            children.push(Node::Prefix(Op::PartitionBy, expressions));
+        }
+
+        if let Some(Ok(Token::Order)) = self.lexer.peek() {
+            self.lexer.next();
+            self.expect(Token::By)?;
+
+            let mut keys = vec![];
+
+            loop {
+                let expression =
+                    self.at(&[children.len(), keys.len(), 0], Self::parse_expression)?;
+
+                let op = match self.lexer.peek() {
+                    Some(Ok(Token::Desc)) => {
+                        self.lexer.next();
+                        Op::Desc
+                    }
+                    Some(Ok(Token::Asc)) => {
+                        self.lexer.next();
+                        Op::Asc
+                    }
+                    _ => Op::Asc,
+                };
+
+                keys.push(Node::Prefix(op, vec![expression]));
+
+                match self.lexer.peek() {
+                    Some(Ok(Token::Comma)) => {
+                        self.lexer.next();
+                    }
+                    _ => break,
+                }
+            }
+
+            children.push(Node::Prefix(Op::OrderBy, keys));
+        }
+
+        if let Some(Ok(Token::Rows)) = self.lexer.peek() {
+            self.lexer.next();
+
+            let bounds = match self.lexer.peek() {
+                Some(Ok(Token::Between)) => {
+                    self.lexer.next();
+
+                    let start = self.parse_frame_bound()?;
+                    self.expect(Token::And)?;
+
+                    vec![start, self.parse_frame_bound()?]
+                }
+                _ => vec![
+                    self.parse_frame_bound()?,
+                    Node::Prefix(Op::CurrentRow, vec![]),
+                ],
+            };
+
+            children.push(Node::Prefix(Op::Rows, bounds));
+        }
+
+        self.expect(Token::CloseParen)?;
+
+        Ok(Node::Prefix(Op::Over, children))
+    }
+
+    /// `UNBOUNDED PRECEDING`, `n PRECEDING`, `CURRENT ROW`, `n FOLLOWING` or
+    /// `UNBOUNDED FOLLOWING`. An unbounded side has no offset child.
+    fn parse_frame_bound(&mut self) -> Result<Node, ParseError> {
+        let offset = match self.lexer.next() {
+            Some(Ok(Token::Current)) => {
+                self.expect(Token::Row)?;
+                return Ok(Node::Prefix(Op::CurrentRow, vec![]));
+            }
+            Some(Ok(Token::Unbounded)) => vec![],
+            Some(Ok(Token::NumericLiteral(i))) => vec![Node::Leaf(self.numeric(&i)?)],
+            s => return Err(self.unexpected(s)),
+        };
+
+        match self.lexer.next() {
+            Some(Ok(Token::Preceding)) => Ok(Node::Prefix(Op::Preceding, offset)),
+            Some(Ok(Token::Following)) => Ok(Node::Prefix(Op::Following, offset)),
+            s => Err(self.unexpected(s)),
+        }
+    }
+
+    fn parse_from(&mut self, min_bp: u8) -> Result<Node, ParseError> {
+        let rhs = self.at(&[0], |parser| parser.parse_bp(0))?;
+
+        match self.at(&[1], |parser| parser.parse_optional_where(min_bp))? {
+            Some(where_node) => Ok(Node::Prefix(Op::From, vec![rhs, where_node])),
+            None => Ok(Node::Prefix(Op::From, vec![rhs])),
+        }
+    }
+
+    fn parse_optional_where(&mut self, min_bp: u8) -> Result<Option<Node>, ParseError> {
+        match self.lexer.peek() {
+            Some(Ok(Token::Where)) => {
+                self.lexer.next();
+                Ok(Some(self.parse_where(min_bp)?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_where(&mut self, _min_bp: u8) -> Result<Node, ParseError> {
+        let rhs = self.at(&[0], |parser| parser.parse_bp(0))?;
+
+        Ok(Node::Prefix(Op::Where, vec![rhs]))
+    }
+
+    fn parse_optional_returning(&mut self) -> Result<Option<Node>, ParseError> {
+        match self.lexer.peek() {
+            Some(Ok(Token::Returning)) => {
+                self.lexer.next();
+                Ok(Some(Node::Prefix(
+                    Op::Returning,
+                    vec![self.at(&[0], |parser| parser.parse_bp(0))?],
+                )))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<Literal, ParseError> {
+        let name = match self.next_name() {
+            Some(Ok(Token::Identifier {
+                first_name,
+                second_name: None,
+                third_name: None,
+            })) => Literal::name(first_name.into_owned()),
+            s => return Err(self.unexpected(s)),
+        };
+
+        self.spans.push((self.path.clone(), self.lexer.span()));
+
+        Ok(name)
+    }
+
+    fn parse_update(&mut self, min_bp: u8) -> Result<Node, ParseError> {
+        let table = self.at(&[0], Self::parse_name)?;
+
+        self.expect(Token::Set)?;
+
+        let mut children = vec![Node::Leaf(table), self.at(&[1], Self::parse_assignments)?];
+        children.extend(self.at(&[children.len()], |parser| {
+            parser.parse_optional_where(min_bp)
+        })?);
+        children.extend(self.at(&[children.len()], Self::parse_optional_returning)?);
+
+        Ok(Node::Prefix(Op::Update, children))
+    }
+
+    fn parse_assignments(&mut self) -> Result<Node, ParseError> {
+        let mut assignments = vec![];
+
+        loop {
+            let index = assignments.len();
+            let column_name = self.at(&[index, 0], Self::parse_name)?;
+
+            self.expect(Token::Equals)?;
+
+            assignments.push(Node::Infix(
+                Op::Equals,
+                vec![
+                    Node::Leaf(column_name),
+                    self.at(&[index, 1], Self::parse_expression)?,
+                ],
+            ));
+
+            match self.lexer.peek() {
+                Some(Ok(Token::Comma)) => {
+                    self.lexer.next();
+                }
+                _ => break,
+            }
+        }
+
+        Ok(Node::Prefix(Op::Set, assignments))
+    }
+
+    fn parse_optional_on_conflict(&mut self, min_bp: u8) -> Result<Option<Node>, ParseError> {
+        match self.lexer.peek() {
+            Some(Ok(Token::On)) => {
+                self.lexer.next();
+            }
+            _ => return Ok(None),
+        }
+
+        self.expect(Token::Conflict)?;
+
+        let columns = match self.lexer.peek() {
+            Some(Ok(Token::OpenParen)) => self.at(&[0], Self::parse_column_list)?,
+            _ => vec![],
+        };
+
+        self.expect(Token::Do)?;
+
+        let action = match self.lexer.next() {
+            Some(Ok(Token::Nothing)) => Node::Prefix(Op::DoNothing, vec![]),
+            Some(Ok(Token::Update)) => {
+                self.expect(Token::Set)?;
+
+                let mut children = vec![self.at(&[1, 0], Self::parse_assignments)?];
+                children.extend(self.at(&[1, 1], |parser| parser.parse_optional_where(min_bp))?);
+
+                Node::Prefix(Op::DoUpdate, children)
+            }
+            s => return Err(self.unexpected(s)),
+        };
+
+        Ok(Some(Node::Prefix(
+            Op::OnConflict,
+            vec![Node::Prefix(Op::ColumnList, columns), action],
+        )))
+    }
+
+    fn parse_delete(&mut self, min_bp: u8) -> Result<Node, ParseError> {
+        match self.lexer.next() {
+            Some(Ok(Token::From)) => {}
+            s => return Err(self.unexpected(s)),
+        }
+
+        let mut children = vec![Node::Leaf(self.at(&[0], Self::parse_name)?)];
+        children.extend(self.at(&[children.len()], |parser| {
+            parser.parse_optional_where(min_bp)
+        })?);
+        children.extend(self.at(&[children.len()], Self::parse_optional_returning)?);
+
+        Ok(Node::Prefix(Op::Delete, children))
+    }
+
+    fn parse_insert(&mut self, min_bp: u8) -> Result<Node, ParseError> {
+        match self.lexer.next() {
+            Some(Ok(Token::Into)) => self.parse_insert_into(min_bp),
+            s => Err(self.unexpected(s)),
+        }
+    }
+
+    fn parse_insert_into(&mut self, min_bp: u8) -> Result<Node, ParseError> {
+        let lhs = self.at(&[0], Self::parse_name)?;
+
+        let columns = match self.lexer.peek() {
+            Some(Ok(Token::OpenParen)) => self.at(&[1], Self::parse_column_list)?,
+            _ => vec![],
+        };
+
+        let source = match self.lexer.next() {
+            Some(Ok(Token::Values)) => Node::Prefix(Op::Values, self.at(&[2], Self::parse_values)?),
+            Some(Ok(Token::Select)) => self.at(&[2], |parser| parser.parse_select(0))?,
+            s => return Err(self.unexpected(s)),
+        };
+
+        let mut children = vec![
+            Node::Leaf(lhs),
+            Node::Prefix(Op::ColumnList, columns),
+            source,
+        ];
+        children.extend(self.at(&[children.len()], |parser| {
+            parser.parse_optional_on_conflict(min_bp)
+        })?);
+        children.extend(self.at(&[children.len()], Self::parse_optional_returning)?);
+
+        Ok(Node::Prefix(Op::InsertInto, children))
+    }
+
+    fn parse_merge(&mut self, _min_bp: u8) -> Result<Node, ParseError> {
+        self.expect(Token::Into)?;
+        let target = self.at(&[0], Self::parse_name)?;
+
+        // Only a table or view can be merged, not a subquery.
+        self.expect(Token::Using)?;
+        let source = self.at(&[1], Self::parse_name)?;
+
+        self.expect(Token::On)?;
+        let condition = self.at(&[2], |parser| parser.parse_bp(0))?;
+
+        let mut children = vec![Node::Leaf(target), Node::Leaf(source), condition];
+
+        while let Some(Ok(Token::When)) = self.lexer.peek() {
+            self.lexer.next();
+            children.push(self.at(&[children.len()], Self::parse_merge_clause)?);
+        }
+
+        if children.len() == 3 {
+            let token = self.lexer.next();
+            return Err(self.unexpected(token));
+        }
+
+        Ok(Node::Prefix(Op::Merge, children))
+    }
+
+    fn parse_merge_clause(&mut self) -> Result<Node, ParseError> {
+        let op = match self.lexer.next() {
+            Some(Ok(Token::Matched)) => Op::WhenMatched,
+            Some(Ok(Token::Not)) => {
+                self.expect(Token::Matched)?;
+                Op::WhenNotMatched
+            }
+            s => return Err(self.unexpected(s)),
+        };
+
+        let condition = match self.lexer.peek() {
+            Some(Ok(Token::And)) => {
+                self.lexer.next();
+                // The condition is written first but goes after the action.
+                Some(Node::Prefix(
+                    Op::Where,
+                    vec![self.at(&[1, 0], |parser| parser.parse_bp(0))?],
+                ))
+            }
+            _ => None,
+        };
+
+        self.expect(Token::Then)?;
+
+        let action = match (op, self.lexer.next()) {
+            (Op::WhenMatched, Some(Ok(Token::Update))) => {
+                self.expect(Token::Set)?;
+                self.at(&[0], Self::parse_assignments)?
+            }
+            (Op::WhenMatched, Some(Ok(Token::Delete))) => Node::Prefix(Op::Delete, vec![]),
+            (Op::WhenNotMatched, Some(Ok(Token::Insert))) => {
+                let columns = match self.lexer.peek() {
+                    Some(Ok(Token::OpenParen)) => self.at(&[0, 0], Self::parse_column_list)?,
+                    _ => vec![],
+                };
+
+                self.expect(Token::Values)?;
+
+                Node::Prefix(
+                    Op::InsertInto,
+                    vec![
+                        Node::Prefix(Op::ColumnList, columns),
+                        self.at(&[0, 1], Self::parse_row)?,
+                    ],
+                )
+            }
+            (_, Some(Ok(Token::Do))) => {
+                self.expect(Token::Nothing)?;
+                Node::Prefix(Op::DoNothing, vec![])
+            }
+            (_, s) => return Err(self.unexpected(s)),
+        };
+
+        let mut children = vec![action];
+        children.extend(condition);
+
+        Ok(Node::Prefix(op, children))
+    }
+
+    fn parse_values(&mut self) -> Result<Vec<Node>, ParseError> {
+        let mut rows = vec![self.at(&[0], Self::parse_row)?];
+
+        while let Some(Ok(Token::Comma)) = self.lexer.peek() {
+            self.lexer.next();
+            rows.push(self.at(&[rows.len()], Self::parse_row)?);
+        }
+
+        Ok(rows)
+    }
+
+    fn parse_row(&mut self) -> Result<Node, ParseError> {
+        match self.lexer.next() {
+            Some(Ok(Token::OpenParen)) => {
+                let start = self.lexer.span().start;
+                let mut values = vec![];
+
+                loop {
+                    let value = match self.lexer.peek() {
+                        Some(Ok(Token::Default)) => {
+                            self.lexer.next();
+                            Node::Leaf(Literal::Default)
+                        }
+                        _ => self.at(&[values.len()], Self::parse_expression)?,
+                    };
+
+                    values.push(value);
+
+                    match self.lexer.next() {
+                        Some(Ok(Token::Comma)) => continue,
+                        Some(Ok(Token::CloseParen)) => break,
+                        s => return Err(self.unexpected(s)),
+                    }
+                }
+
+                Ok(self.spanned(Node::Prefix(Op::Row, values), start))
+            }
+            s => Err(self.unexpected(s)),
+        }
+    }
+
+    fn prefix_operator_bp(op: &Op) -> Option<((), u8)> {
+        match op {
+            Op::Not => Some(((), 7)),
+            Op::Minus | Op::Plus => Some(((), 10)),
+            _ => None,
+        }
+    }
+
+    fn postfix_operator_bp(op: &Op) -> Option<(u8, ())> {
+        match op {
+            Op::FunctionCall => Some((11, ())),
+            _ => None,
+        }
+    }
+
+    fn infix_operator_bp(op: &Op) -> Option<(u8, u8)> {
+        match op {
+            Op::Comma => Some((1, 2)),
+
+            Op::Or => Some((2, 3)),
+            Op::And => Some((3, 4)),
+
+            Op::Equals => Some((4, 5)),
+            Op::NotEquals => Some((4, 5)),
+            Op::LessThan => Some((4, 5)),
+            Op::GreaterThan => Some((4, 5)),
+            Op::LessThanOrEquals => Some((4, 5)),
+            Op::GreaterThanOrEquals => Some((4, 5)),
+
+            Op::Concat => Some((5, 6)),
+
+            Op::Plus => Some((6, 7)),
+            Op::Minus => Some((6, 7)),
+
+            Op::Multiply => Some((8, 9)),
+            Op::Divide => Some((8, 9)),
+            Op::Modulo => Some((8, 9)),
+
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use crate::parser::{
+        Decimal, Literal, Node, Op, Parameter, ParseError, ParseErrorKind, Parser, Span,
+    };
+
+    use super::{lexer::LexError, lexer::Lexer, Type};
+    use crate::temporal::{Date, Interval, TemporalError, Timestamp};
+    use pretty_assertions::assert_eq;
+
+    fn id(identifier: &str) -> Literal {
+        let mut parts = identifier.splitn(3, '.').map(str::to_string);
+
+        Literal::Identifier {
+            first_name: parts.next().unwrap(),
+            second_name: parts.next(),
+            third_name: parts.next(),
+        }
+    }
+
+    fn num(i: i64) -> Literal {
+        Literal::Numeric(i)
+    }
+
+    fn string(i: &str) -> Literal {
+        Literal::String(i.to_string())
+    }
+
+    fn quoted(name: &str) -> Literal {
+        Literal::name(name.to_string())
+    }
+
+    fn leaf(literal: Literal) -> Node {
+        Node::Leaf(literal)
+    }
+
+    fn leaf_type(typ: Type) -> Node {
+        Node::LeafType(typ)
+    }
+
+    fn infix(op: Op, lhs: Node, rhs: Node) -> Node {
+        Node::Infix(op, vec![lhs, rhs])
+    }
+
+    fn infix_vec(op: Op, nodes: Vec<Node>) -> Node {
+        Node::Infix(op, nodes)
+    }
+
+    fn prefix_vec(op: Op, nodes: Vec<Node>) -> Node {
+        Node::Prefix(op, nodes)
+    }
+
+    fn prefix(op: Op, lhs: Node) -> Node {
+        Node::Prefix(op, vec![lhs])
+    }
+
+    fn prefix_chain(op: Op, lhs: Node, chain_node: Node) -> Node {
+        Node::Prefix(op, vec![lhs, chain_node])
+    }
+
+    fn postfix(op: Op, rhs: Node) -> Node {
+        Node::Postfix(op, vec![rhs])
+    }
+
+    /// Also checks that the SQL written for the result parses back to it.
+    fn parse(input: &str) -> Node {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let ast = parser.parse().unwrap();
+        let sql = ast.to_sql();
+
+        assert_eq!(
+            Parser::new(Lexer::new(&sql)).parse(),
+            Ok(ast.clone()),
+            "{}",
+            sql
+        );
+
+        ast
+    }
+
+    fn parse_error(input: &str) -> ParseError {
+        Parser::new(Lexer::new(input)).parse().unwrap_err()
+    }
+
+    #[test]
+    fn mininmal_expression_parser() {
+        assert_eq!(parse("1"), leaf(num(1)));
+    }
+
+    #[test]
+    fn mininmal_expression_parser_str() {
+        assert_eq!(parse("'I am a string!'"), leaf(string("I am a string!")));
+    }
+
+    #[test]
+    fn expression_parser() {
+        assert_eq!(parse("1 + 2"), infix(Op::Plus, leaf(num(1)), leaf(num(2))));
+    }
+
+    #[test]
+    fn expression_parser_with_precedence() {
+        assert_eq!(
+            parse("1 + 2 * 3"),
+            infix(
+                Op::Plus,
+                leaf(num(1)),
+                infix(Op::Multiply, leaf(num(2)), leaf(num(3)))
+            )
+        );
+    }
+
+    #[test]
+    fn parse_reverse() {
+        assert_eq!(
+            parse("2 * 3 + 4"),
+            infix(
+                Op::Plus,
+                infix(Op::Multiply, leaf(num(2)), leaf(num(3))),
+                leaf(num(4))
+            )
+        );
+    }
+
+    #[test]
+    fn parse_more() {
+        assert_eq!(
+            parse("1 + 2 * 3 * 4 + 5"),
+            infix(
+                Op::Plus,
+                infix(
+                    Op::Plus,
+                    leaf(num(1)),
+                    infix(
+                        Op::Multiply,
+                        infix(Op::Multiply, leaf(num(2)), leaf(num(3))),
+                        leaf(num(4))
+                    )
+                ),
+                leaf(num(5))
+            )
+        );
+    }
+
+    #[test]
+    fn simple_where_expr() {
+        assert_eq!(
+            parse("1 = 1"),
+            infix(Op::Equals, leaf(num(1)), leaf(num(1)))
+        );
+    }
+
+    #[test]
+    fn where_expr() {
+        assert_eq!(
+            parse("1 = 1 and 2 >= 3 * 1 or 4 < 5 + 6"),
+            infix(
+                Op::Or,
+                infix(
+                    Op::And,
+                    infix(Op::Equals, leaf(num(1)), leaf(num(1))),
+                    infix(
+                        Op::GreaterThanOrEquals,
+                        leaf(num(2)),
+                        infix(Op::Multiply, leaf(num(3)), leaf(num(1)))
+                    )
+                ),
+                infix(
+                    Op::LessThan,
+                    leaf(num(4)),
+                    infix(Op::Plus, leaf(num(5)), leaf(num(6)))
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn prefix_operator_not() {
+        assert_eq!(
+            parse("not 1 = 1"),
+            infix(Op::Equals, prefix(Op::Not, leaf(num(1))), leaf(num(1)))
+        );
+    }
+
+    #[test]
+    fn where_expr_with_parentheses() {
+        assert_eq!(
+            parse("(1 + 2) * 3"),
+            infix(
+                Op::Multiply,
+                infix(Op::Plus, leaf(num(1)), leaf(num(2))),
+                leaf(num(3))
+            )
+        );
+    }
+
+    #[test]
+    fn postfix_function_call() {
+        assert_eq!(
+            parse("func(1, 2) + f()"),
+            infix(
+                Op::Plus,
+                Node::Postfix(
+                    Op::FunctionCall,
+                    vec![
+                        leaf(id("func")),
+                        prefix_vec(Op::Arguments, vec![leaf(num(1)), leaf(num(2))])
+                    ]
+                ),
+                Node::Postfix(
+                    Op::FunctionCall,
+                    vec![leaf(id("f")), prefix_vec(Op::Arguments, vec![])]
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn where_expr_with_parentheses_2() {
+        assert_eq!(
+            parse("(1 = 1 and 2 >= 3) * 1 or 4 < 5 + 6"),
+            infix(
+                Op::Or,
+                infix(
+                    Op::Multiply,
+                    infix(
+                        Op::And,
+                        infix(Op::Equals, leaf(num(1)), leaf(num(1))),
+                        infix(Op::GreaterThanOrEquals, leaf(num(2)), leaf(num(3)))
+                    ),
+                    leaf(num(1))
+                ),
+                infix(
+                    Op::LessThan,
+                    leaf(num(4)),
+                    infix(Op::Plus, leaf(num(5)), leaf(num(6)))
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn select_query_without_from() {
+        assert_eq!(parse("select 1"), prefix(Op::Select, leaf(num(1))));
+    }
+
+    #[test]
+    fn select_query_with_from() {
+        assert_eq!(
+            parse("select 1 from table1"),
+            prefix_chain(
+                Op::Select,
+                leaf(num(1)),
+                prefix(Op::From, leaf(id("table1")))
+            )
+        );
+    }
+
+    #[test]
+    fn select_query_many_columns() {
+        assert_eq!(
+            parse("select col1, col2 from table1"),
+            prefix_chain(
+                Op::Select,
+                infix(Op::Comma, leaf(id("col1")), leaf(id("col2"))),
+                prefix(Op::From, leaf(id("table1")))
+            )
+        );
+    }
+
+    #[test]
+    fn select_query_many_commas() {
+        assert_eq!(
+            parse("select col1, col2, 1 + 1 from table1, table2"),
+            prefix_chain(
+                Op::Select,
+                infix(
+                    Op::Comma,
+                    infix(Op::Comma, leaf(id("col1")), leaf(id("col2"))),
+                    infix(Op::Plus, leaf(num(1)), leaf(num(1)))
+                ),
+                prefix(
+                    Op::From,
+                    infix(Op::Comma, leaf(id("table1")), leaf(id("table2")))
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn select_query_with_where() {
+        assert_eq!(
+            parse("select col1 from table1 where col1 = 1"),
+            prefix_chain(
+                Op::Select,
+                leaf(id("col1")),
+                prefix_chain(
+                    Op::From,
+                    leaf(id("table1")),
+                    prefix(
+                        Op::Where,
+                        infix(Op::Equals, leaf(id("col1")), leaf(num(1)),)
+                    )
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn select_query_with_where_and_and() {
+        let input = r"
+        select col1, col2, col3 
+        from table1, table2 
+        where (col1 = 1 or col2 = 2) and col3 >= 3";
+
+        assert_eq!(
+            parse(input),
+            prefix_chain(
+                Op::Select,
+                infix(
+                    Op::Comma,
+                    infix(Op::Comma, leaf(id("col1")), leaf(id("col2"))),
+                    leaf(id("col3"))
+                ),
+                prefix_chain(
+                    Op::From,
+                    infix(Op::Comma, leaf(id("table1")), leaf(id("table2"))),
+                    prefix(
+                        Op::Where,
+                        infix(
+                            Op::And,
+                            infix(
+                                Op::Or,
+                                infix(Op::Equals, leaf(id("col1")), leaf(num(1))),
+                                infix(Op::Equals, leaf(id("col2")), leaf(num(2)))
+                            ),
+                            infix(Op::GreaterThanOrEquals, leaf(id("col3")), leaf(num(3)))
+                        )
+                    )
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn create_table_with_single_column() {
+        let input = r"
+        create table table1 (
+            col1 int
+        )";
+
+        assert_eq!(
+            dbg!(parse(input)),
+            prefix_chain(
+                Op::CreateTable,
+                leaf(id("table1")),
+                infix(Op::ColumnDefinition, leaf(id("col1")), leaf_type(Type::Int))
+            )
+        );
+    }
+
+    #[test]
+    fn create_table_two_columns() {
+        let input = r"
+        create table table1 (
+            col1 int,
+            col2 int
+        )";
+
+        assert_eq!(
+            parse(input),
+            prefix_chain(
+                Op::CreateTable,
+                leaf(id("table1")),
+                infix(
+                    Op::Comma,
+                    infix(Op::ColumnDefinition, leaf(id("col1")), leaf_type(Type::Int)),
+                    infix(Op::ColumnDefinition, leaf(id("col2")), leaf_type(Type::Int))
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn create_table_three_columns() {
+        let input = r"
+        create table table1 (
+            col1 int,
+            col2 int,
+            col3 int
+        )";
+
+        assert_eq!(
+            parse(input),
+            prefix_chain(
+                Op::CreateTable,
+                leaf(id("table1")),
+                infix_vec(
+                    Op::Comma,
+                    vec![
+                        infix(Op::ColumnDefinition, leaf(id("col1")), leaf_type(Type::Int)),
+                        infix(Op::ColumnDefinition, leaf(id("col2")), leaf_type(Type::Int)),
+                        infix(Op::ColumnDefinition, leaf(id("col3")), leaf_type(Type::Int))
+                    ]
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn drop_table() {
+        assert_eq!(
+            parse("drop table table1"),
+            prefix(Op::DropTable, leaf(id("table1")),)
+        );
+    }
+
+    #[test]
+    fn insert_into_single_column() {
+        let input = r"
+        insert into table1 (col1) values (1)";
+
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::InsertInto,
+                vec![
+                    leaf(id("table1")),
+                    prefix_vec(Op::ColumnList, vec![leaf(id("col1"))]),
+                    prefix(Op::Values, prefix_vec(Op::Row, vec![leaf(num(1))]))
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn insert_into() {
+        let input = r"
+        insert into table1 (col1, col2) values (1, 2)";
+
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::InsertInto,
+                vec![
+                    leaf(id("table1")),
+                    prefix_vec(Op::ColumnList, vec![leaf(id("col1")), leaf(id("col2"))]),
+                    prefix(
+                        Op::Values,
+                        prefix_vec(Op::Row, vec![leaf(num(1)), leaf(num(2))])
+                    )
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn insert_into_many_rows_of_mixed_values() {
+        let input = r"
+        insert into table1 (col1, col2) values ('x', 1.5), (NULL, DEFAULT), (true, -3)";
+
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::InsertInto,
+                vec![
+                    leaf(id("table1")),
+                    prefix_vec(Op::ColumnList, vec![leaf(id("col1")), leaf(id("col2"))]),
+                    prefix_vec(
+                        Op::Values,
+                        vec![
+                            prefix_vec(
+                                Op::Row,
+                                vec![
+                                    leaf(string("x")),
+                                    leaf(Literal::Decimal(Decimal {
+                                        mantissa: 15,
+                                        scale: 1
+                                    }))
+                                ]
+                            ),
+                            prefix_vec(Op::Row, vec![leaf(Literal::Null), leaf(Literal::Default)]),
+                            prefix_vec(Op::Row, vec![leaf(Literal::Bool(true)), leaf(num(-3))]),
+                        ]
+                    )
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn insert_into_without_column_list() {
+        let input = r"
+        insert into table1 values (1 + 2, 'a')";
+
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::InsertInto,
+                vec![
+                    leaf(id("table1")),
+                    prefix_vec(Op::ColumnList, vec![]),
+                    prefix(
+                        Op::Values,
+                        prefix_vec(
+                            Op::Row,
+                            vec![
+                                infix(Op::Plus, leaf(num(1)), leaf(num(2))),
+                                leaf(string("a"))
+                            ]
+                        )
+                    )
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn create_table_with_various_types() {
+        let input = r"
+        create table table1 (
+            col1 integer,
+            col2 text,
+            col3 boolean,
+            col4 float
+        )";
+
+        assert_eq!(
+            parse(input),
+            prefix_chain(
+                Op::CreateTable,
+                leaf(id("table1")),
+                infix_vec(
+                    Op::Comma,
+                    vec![
+                        infix(Op::ColumnDefinition, leaf(id("col1")), leaf_type(Type::Int)),
+                        infix(
+                            Op::ColumnDefinition,
+                            leaf(id("col2")),
+                            leaf_type(Type::String)
+                        ),
+                        infix(
+                            Op::ColumnDefinition,
+                            leaf(id("col3")),
+                            leaf_type(Type::Bool)
+                        ),
+                        infix(
+                            Op::ColumnDefinition,
+                            leaf(id("col4")),
+                            leaf_type(Type::Float)
+                        )
+                    ]
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn select_wildcard() {
+        assert_eq!(
+            parse("select * from table1"),
+            prefix_chain(
+                Op::Select,
+                leaf(Literal::Wildcard),
+                prefix(Op::From, leaf(id("table1")))
+            )
+        );
+    }
+
+    #[test]
+    fn insert_into_select() {
+        let input = r"
+        insert into table1 (col1) select col2 from table2 where col2 > 1";
+
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::InsertInto,
+                vec![
+                    leaf(id("table1")),
+                    prefix_vec(Op::ColumnList, vec![leaf(id("col1"))]),
+                    prefix_chain(
+                        Op::Select,
+                        leaf(id("col2")),
+                        prefix_chain(
+                            Op::From,
+                            leaf(id("table2")),
+                            prefix(
+                                Op::Where,
+                                infix(Op::GreaterThan, leaf(id("col2")), leaf(num(1)))
+                            )
+                        )
+                    )
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn insert_into_returning() {
+        let input = r"
+        insert into table1 (col1) values (1) returning col1, col2";
+
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::InsertInto,
+                vec![
+                    leaf(id("table1")),
+                    prefix_vec(Op::ColumnList, vec![leaf(id("col1"))]),
+                    prefix(Op::Values, prefix_vec(Op::Row, vec![leaf(num(1))])),
+                    prefix(
+                        Op::Returning,
+                        infix(Op::Comma, leaf(id("col1")), leaf(id("col2")))
+                    )
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn update() {
+        let input = r"
+        update table1 set col1 = col1 + 1, col2 = 'x' where col1 > 2 returning *";
+
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::Update,
+                vec![
+                    leaf(id("table1")),
+                    prefix_vec(
+                        Op::Set,
+                        vec![
+                            infix(
+                                Op::Equals,
+                                leaf(id("col1")),
+                                infix(Op::Plus, leaf(id("col1")), leaf(num(1)))
+                            ),
+                            infix(Op::Equals, leaf(id("col2")), leaf(string("x")))
+                        ]
+                    ),
+                    prefix(
+                        Op::Where,
+                        infix(Op::GreaterThan, leaf(id("col1")), leaf(num(2)))
+                    ),
+                    prefix(Op::Returning, leaf(Literal::Wildcard))
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn delete() {
+        assert_eq!(
+            parse("delete from table1"),
+            prefix(Op::Delete, leaf(id("table1")))
+        );
+    }
+
+    #[test]
+    fn delete_with_where_and_returning() {
+        assert_eq!(
+            parse("delete from table1 where col1 = 1 returning col2;"),
+            prefix_vec(
+                Op::Delete,
+                vec![
+                    leaf(id("table1")),
+                    prefix(Op::Where, infix(Op::Equals, leaf(id("col1")), leaf(num(1)))),
+                    prefix(Op::Returning, leaf(id("col2")))
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn trailing_tokens_are_rejected() {
+        assert_eq!(
+            parse_error("select col1 from table1 where col1 = 1 col2"),
+            ParseError {
+                kind: ParseErrorKind::UnexpectedToken("col2".to_string()),
+                span: Span { start: 39, end: 43 }
+            }
+        );
+    }
+
+    #[test]
+    fn equal_expressions_have_their_own_spans() {
+        let mut parser = Parser::new(Lexer::new("select b, (x + -1) * b from t"));
+        let statement = parser.parse().unwrap();
+        let spans = parser.into_spans();
+
+        let columns = &statement.children()[0];
+        let first = &columns.children()[0];
+        let product = &columns.children()[1];
+        let second = &product.children()[1];
+
+        assert_eq!(first, second);
+        assert_eq!(
+            spans.get(&statement, first),
+            Some(Span { start: 7, end: 8 })
+        );
+        assert_eq!(
+            spans.get(&statement, second),
+            Some(Span { start: 21, end: 22 })
+        );
+        assert_eq!(
+            spans.get(&statement, product),
+            Some(Span { start: 10, end: 22 })
+        );
+        assert_eq!(
+            spans.get(&statement, &product.children()[0]),
+            Some(Span { start: 11, end: 17 })
+        );
+        assert_eq!(
+            spans.get(&statement, &product.children()[0].children()[1]),
+            Some(Span { start: 15, end: 17 })
+        );
+        assert_eq!(
+            spans.get(&statement, &statement.children()[1].children()[0]),
+            Some(Span { start: 28, end: 29 })
+        );
+        assert_eq!(spans.get(&statement, &first.clone()), None);
+    }
+
+    #[test]
+    fn spans_of_nodes_moved_into_place() {
+        let input = "create table t (a int default 1 + 2)";
+        let mut parser = Parser::new(Lexer::new(input));
+        let statement = parser.parse().unwrap();
+        let spans = parser.into_spans();
 
-            Op::CloseParen => None,
+        let default = &statement.children()[1].children()[2].children()[0];
 
-            _ => panic!("Unexpected infix operator: {:?}", op),
-        }
-    }
-}
+        assert_eq!(
+            spans.get(&statement, default),
+            Some(Span { start: 30, end: 35 })
+        );
 
-#[cfg(test)]
-mod tests {
-    use crate::parser::{Literal, Node, Op, Parser};
+        let input =
+            "merge into t using s on t.a = s.a when matched and s.b then update set c = s.c";
+        let mut parser = Parser::new(Lexer::new(input));
+        let statement = parser.parse().unwrap();
+        let spans = parser.into_spans();
 
-    use super::{lexer::Lexer, Type};
-    use pretty_assertions::assert_eq;
+        let clause = &statement.children()[3];
+        let condition = &clause.children()[1].children()[0];
+        let value = &clause.children()[0].children()[0].children()[1];
 
-    fn id(identifier: &str) -> Literal {
-        Literal::identifier(identifier)
+        assert_eq!(
+            spans.get(&statement, condition),
+            Some(Span { start: 51, end: 54 })
+        );
+        assert_eq!(
+            spans.get(&statement, value),
+            Some(Span { start: 75, end: 78 })
+        );
     }
 
-    fn num(i: i32) -> Literal {
-        Literal::Numeric(i)
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(
+            parse_error("select (1"),
+            ParseError {
+                kind: ParseErrorKind::UnexpectedEnd,
+                span: Span { start: 9, end: 9 }
+            }
+        );
+        assert_eq!(
+            parse_error("insert into t values (1, #)"),
+            ParseError {
+                kind: ParseErrorKind::Lex(LexError::InvalidCharacter('#')),
+                span: Span { start: 25, end: 26 }
+            }
+        );
+        assert_eq!(
+            parse_error("select $0"),
+            ParseError {
+                kind: ParseErrorKind::InvalidParameter("$0".to_string()),
+                span: Span { start: 7, end: 9 }
+            }
+        );
+        assert_eq!(
+            parse_error("select t.f(1)"),
+            ParseError {
+                kind: ParseErrorKind::UnexpectedToken("(".to_string()),
+                span: Span { start: 10, end: 11 }
+            }
+        );
+        assert_eq!(
+            parse_error("select from").to_string(),
+            "Unexpected token: from at 7..11"
+        );
     }
 
-    fn string(i: &str) -> Literal {
-        Literal::String(i.to_string())
-    }
+    #[test]
+    fn create_table_with_constraints() {
+        let input = r"
+        create table table1 (
+            col1 int primary key,
+            col2 int unique,
+            col3 int,
+            unique (col2, col3)
+        )";
 
-    fn leaf(literal: Literal) -> Node {
-        Node::Leaf(literal)
+        assert_eq!(
+            parse(input),
+            prefix_chain(
+                Op::CreateTable,
+                leaf(id("table1")),
+                infix_vec(
+                    Op::Comma,
+                    vec![
+                        infix_vec(
+                            Op::ColumnDefinition,
+                            vec![
+                                leaf(id("col1")),
+                                leaf_type(Type::Int),
+                                prefix_vec(Op::PrimaryKey, vec![])
+                            ]
+                        ),
+                        infix_vec(
+                            Op::ColumnDefinition,
+                            vec![
+                                leaf(id("col2")),
+                                leaf_type(Type::Int),
+                                prefix_vec(Op::Unique, vec![])
+                            ]
+                        ),
+                        infix(Op::ColumnDefinition, leaf(id("col3")), leaf_type(Type::Int)),
+                        prefix_vec(Op::Unique, vec![leaf(id("col2")), leaf(id("col3"))])
+                    ]
+                )
+            )
+        );
     }
 
-    fn leaf_type(typ: Type) -> Node {
-        Node::LeafType(typ)
-    }
+    #[test]
+    fn create_table_with_table_primary_key() {
+        let input = r"
+        create table table1 (col1 int, primary key (col1))";
 
-    fn infix(op: Op, lhs: Node, rhs: Node) -> Node {
-        Node::Infix(op, vec![lhs, rhs])
+        assert_eq!(
+            parse(input),
+            prefix_chain(
+                Op::CreateTable,
+                leaf(id("table1")),
+                infix_vec(
+                    Op::Comma,
+                    vec![
+                        infix(Op::ColumnDefinition, leaf(id("col1")), leaf_type(Type::Int)),
+                        prefix_vec(Op::PrimaryKey, vec![leaf(id("col1"))])
+                    ]
+                )
+            )
+        );
     }
 
-    fn infix_vec(op: Op, nodes: Vec<Node>) -> Node {
-        Node::Infix(op, nodes)
+    #[test]
+    fn qualified_identifier() {
+        assert_eq!(
+            parse("excluded.col1 + 1"),
+            infix(Op::Plus, leaf(id("excluded.col1")), leaf(num(1)))
+        );
     }
 
-    fn prefix_vec(op: Op, nodes: Vec<Node>) -> Node {
-        Node::Prefix(op, nodes)
-    }
+    #[test]
+    fn insert_on_conflict_do_nothing() {
+        let input = r"
+        insert into table1 values (1) on conflict do nothing";
 
-    fn prefix(op: Op, lhs: Node) -> Node {
-        Node::Prefix(op, vec![lhs])
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::InsertInto,
+                vec![
+                    leaf(id("table1")),
+                    prefix_vec(Op::ColumnList, vec![]),
+                    prefix(Op::Values, prefix_vec(Op::Row, vec![leaf(num(1))])),
+                    prefix_vec(
+                        Op::OnConflict,
+                        vec![
+                            prefix_vec(Op::ColumnList, vec![]),
+                            prefix_vec(Op::DoNothing, vec![])
+                        ]
+                    )
+                ]
+            )
+        );
     }
 
-    fn prefix_chain(op: Op, lhs: Node, chain_node: Node) -> Node {
-        Node::Prefix(op, vec![lhs, chain_node])
-    }
+    #[test]
+    fn insert_on_conflict_do_update() {
+        let input = r"
+        insert into table1 (col1, col2) values (1, 2)
+        on conflict (col1) do update set col2 = excluded.col2 where col2 < excluded.col2
+        returning col2";
 
-    fn postfix(op: Op, rhs: Node) -> Node {
-        Node::Postfix(op, vec![rhs])
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::InsertInto,
+                vec![
+                    leaf(id("table1")),
+                    prefix_vec(Op::ColumnList, vec![leaf(id("col1")), leaf(id("col2"))]),
+                    prefix(
+                        Op::Values,
+                        prefix_vec(Op::Row, vec![leaf(num(1)), leaf(num(2))])
+                    ),
+                    prefix_vec(
+                        Op::OnConflict,
+                        vec![
+                            prefix_vec(Op::ColumnList, vec![leaf(id("col1"))]),
+                            prefix_vec(
+                                Op::DoUpdate,
+                                vec![
+                                    prefix(
+                                        Op::Set,
+                                        infix(
+                                            Op::Equals,
+                                            leaf(id("col2")),
+                                            leaf(id("excluded.col2"))
+                                        )
+                                    ),
+                                    prefix(
+                                        Op::Where,
+                                        infix(
+                                            Op::LessThan,
+                                            leaf(id("col2")),
+                                            leaf(id("excluded.col2"))
+                                        )
+                                    )
+                                ]
+                            )
+                        ]
+                    ),
+                    prefix(Op::Returning, leaf(id("col2")))
+                ]
+            )
+        );
     }
 
-    fn parse(input: &str) -> Node {
-        let lexer = Lexer::new(input);
-        let mut parser = Parser::new(lexer);
+    #[test]
+    fn merge() {
+        let input = r"
+        merge into table1 using table2 on table1.col1 = table2.col1
+        when matched and table2.col2 = 0 then delete
+        when matched then update set col2 = table2.col2
+        when not matched then insert (col1, col2) values (table2.col1, default)";
 
-        parser.parse()
+        assert_eq!(
+            parse(input),
+            prefix_vec(
+                Op::Merge,
+                vec![
+                    leaf(id("table1")),
+                    leaf(id("table2")),
+                    infix(Op::Equals, leaf(id("table1.col1")), leaf(id("table2.col1"))),
+                    prefix_vec(
+                        Op::WhenMatched,
+                        vec![
+                            prefix_vec(Op::Delete, vec![]),
+                            prefix(
+                                Op::Where,
+                                infix(Op::Equals, leaf(id("table2.col2")), leaf(num(0)))
+                            )
+                        ]
+                    ),
+                    prefix(
+                        Op::WhenMatched,
+                        prefix(
+                            Op::Set,
+                            infix(Op::Equals, leaf(id("col2")), leaf(id("table2.col2")))
+                        )
+                    ),
+                    prefix(
+                        Op::WhenNotMatched,
+                        prefix_vec(
+                            Op::InsertInto,
+                            vec![
+                                prefix_vec(
+                                    Op::ColumnList,
+                                    vec![leaf(id("col1")), leaf(id("col2"))]
+                                ),
+                                prefix_vec(
+                                    Op::Row,
+                                    vec![leaf(id("table2.col1")), leaf(Literal::Default)]
+                                )
+                            ]
+                        )
+                    )
+                ]
+            )
+        );
     }
 
     #[test]
-    fn mininmal_expression_parser() {
-        assert_eq!(parse("1"), leaf(num(1)));
+    fn merge_do_nothing() {
+        assert_eq!(
+            parse("merge into table1 using table2 on col1 = col2 when not matched then do nothing"),
+            prefix_vec(
+                Op::Merge,
+                vec![
+                    leaf(id("table1")),
+                    leaf(id("table2")),
+                    infix(Op::Equals, leaf(id("col1")), leaf(id("col2"))),
+                    prefix(Op::WhenNotMatched, prefix_vec(Op::DoNothing, vec![]))
+                ]
+            )
+        );
     }
 
     #[test]
-    fn mininmal_expression_parser_str() {
-        assert_eq!(parse("'I am a string!'"), leaf(string("I am a string!")));
+    fn merge_requires_a_clause() {
+        assert_eq!(
+            parse_error("merge into table1 using table2 on col1 = col2"),
+            ParseError {
+                kind: ParseErrorKind::UnexpectedEnd,
+                span: Span { start: 45, end: 45 }
+            }
+        );
     }
 
     #[test]
-    fn expression_parser() {
-        assert_eq!(parse("1 + 2"), infix(Op::Plus, leaf(num(1)), leaf(num(2))));
+    fn merge_source_is_a_name() {
+        assert_eq!(
+            parse_error("merge into table1 using (select col2 from table2) s on col1 = col2")
+                .to_string(),
+            "Unexpected token: ( at 24..25"
+        );
     }
 
     #[test]
-    fn expression_parser_with_precedence() {
+    fn select_union() {
         assert_eq!(
-            parse("1 + 2 * 3"),
+            parse("select col1 from table1 union select col2 from table2 union all select 1"),
             infix(
-                Op::Plus,
-                leaf(num(1)),
-                infix(Op::Multiply, leaf(num(2)), leaf(num(3)))
+                Op::UnionAll,
+                infix(
+                    Op::Union,
+                    prefix_chain(
+                        Op::Select,
+                        leaf(id("col1")),
+                        prefix(Op::From, leaf(id("table1")))
+                    ),
+                    prefix_chain(
+                        Op::Select,
+                        leaf(id("col2")),
+                        prefix(Op::From, leaf(id("table2")))
+                    )
+                ),
+                prefix(Op::Select, leaf(num(1)))
             )
         );
     }
 
     #[test]
-    fn parse_reverse() {
+    fn with() {
+        let input = "with t1 (a) as (select col1 from table1), t2 as (select 1) select a from t1";
+
         assert_eq!(
-            parse("2 * 3 + 4"),
-            infix(
-                Op::Plus,
-                infix(Op::Multiply, leaf(num(2)), leaf(num(3))),
-                leaf(num(4))
+            parse(input),
+            prefix_vec(
+                Op::With,
+                vec![
+                    prefix_vec(
+                        Op::Cte,
+                        vec![
+                            leaf(id("t1")),
+                            prefix(Op::ColumnList, leaf(id("a"))),
+                            prefix_chain(
+                                Op::Select,
+                                leaf(id("col1")),
+                                prefix(Op::From, leaf(id("table1")))
+                            )
+                        ]
+                    ),
+                    prefix_vec(
+                        Op::Cte,
+                        vec![
+                            leaf(id("t2")),
+                            prefix_vec(Op::ColumnList, vec![]),
+                            prefix(Op::Select, leaf(num(1)))
+                        ]
+                    ),
+                    prefix_chain(Op::Select, leaf(id("a")), prefix(Op::From, leaf(id("t1"))))
+                ]
             )
         );
     }
 
     #[test]
-    fn parse_more() {
+    fn with_recursive() {
+        let input = r"
+        with recursive t (n) as (select 1 union all select n + 1 from t where n < 5)
+        select n from t";
+
         assert_eq!(
-            parse("1 + 2 * 3 * 4 + 5"),
-            infix(
-                Op::Plus,
-                infix(
-                    Op::Plus,
-                    leaf(num(1)),
-                    infix(
-                        Op::Multiply,
-                        infix(Op::Multiply, leaf(num(2)), leaf(num(3))),
-                        leaf(num(4))
-                    )
-                ),
-                leaf(num(5))
+            parse(input),
+            prefix_vec(
+                Op::WithRecursive,
+                vec![
+                    prefix_vec(
+                        Op::Cte,
+                        vec![
+                            leaf(id("t")),
+                            prefix(Op::ColumnList, leaf(id("n"))),
+                            infix(
+                                Op::UnionAll,
+                                prefix(Op::Select, leaf(num(1))),
+                                prefix_chain(
+                                    Op::Select,
+                                    infix(Op::Plus, leaf(id("n")), leaf(num(1))),
+                                    prefix_vec(
+                                        Op::From,
+                                        vec![
+                                            leaf(id("t")),
+                                            prefix(
+                                                Op::Where,
+                                                infix(Op::LessThan, leaf(id("n")), leaf(num(5)))
+                                            )
+                                        ]
+                                    )
+                                )
+                            )
+                        ]
+                    ),
+                    prefix_chain(Op::Select, leaf(id("n")), prefix(Op::From, leaf(id("t"))))
+                ]
             )
         );
     }
 
     #[test]
-    fn simple_where_expr() {
+    fn window_function() {
+        let input = r"
+        select sum(col2) over (partition by col1, col3 order by col2 desc, col4
+                               rows between 2 preceding and unbounded following)
+        from table1";
+
         assert_eq!(
-            parse("1 = 1"),
-            infix(Op::Equals, leaf(num(1)), leaf(num(1)))
+            parse(input),
+            prefix_chain(
+                Op::Select,
+                Node::Postfix(
+                    Op::FunctionCall,
+                    vec![
+                        leaf(id("sum")),
+                        prefix(Op::Arguments, leaf(id("col2"))),
+                        prefix_vec(
+                            Op::Over,
+                            vec![
+                                prefix_vec(
+                                    Op::PartitionBy,
+                                    vec![leaf(id("col1")), leaf(id("col3"))]
+                                ),
+                                prefix_vec(
+                                    Op::OrderBy,
+                                    vec![
+                                        prefix(Op::Desc, leaf(id("col2"))),
+                                        prefix(Op::Asc, leaf(id("col4")))
+                                    ]
+                                ),
+                                prefix_vec(
+                                    Op::Rows,
+                                    vec![
+                                        prefix(Op::Preceding, leaf(num(2))),
+                                        prefix_vec(Op::Following, vec![])
+                                    ]
+                                )
+                            ]
+                        )
+                    ]
+                ),
+                prefix(Op::From, leaf(id("table1")))
+            )
         );
     }
 
     #[test]
-    fn where_expr() {
+    fn window_with_single_frame_bound() {
         assert_eq!(
-            parse("1 = 1 and 2 >= 3 * 1 or 4 < 5 + 6"),
-            infix(
-                Op::Or,
-                infix(
-                    Op::And,
-                    infix(Op::Equals, leaf(num(1)), leaf(num(1))),
-                    infix(
-                        Op::GreaterThanOrEquals,
-                        leaf(num(2)),
-                        infix(Op::Multiply, leaf(num(3)), leaf(num(1)))
-                    )
-                ),
+            parse("select row_number() over (rows unbounded preceding), col1"),
+            prefix(
+                Op::Select,
                 infix(
-                    Op::LessThan,
-                    leaf(num(4)),
-                    infix(Op::Plus, leaf(num(5)), leaf(num(6)))
+                    Op::Comma,
+                    Node::Postfix(
+                        Op::FunctionCall,
+                        vec![
+                            leaf(id("row_number")),
+                            prefix_vec(Op::Arguments, vec![]),
+                            prefix(
+                                Op::Over,
+                                prefix_vec(
+                                    Op::Rows,
+                                    vec![
+                                        prefix_vec(Op::Preceding, vec![]),
+                                        prefix_vec(Op::CurrentRow, vec![])
+                                    ]
+                                )
+                            )
+                        ]
+                    ),
+                    leaf(id("col1"))
                 )
             )
         );
     }
 
     #[test]
-    fn prefix_operator_not() {
+    fn create_view() {
+        assert_eq!(
+            parse("create or replace view v (a) as select col1 from table1;"),
+            prefix_vec(
+                Op::CreateView,
+                vec![
+                    leaf(id("v")),
+                    prefix(Op::ColumnList, leaf(id("a"))),
+                    leaf(string("select col1 from table1")),
+                    prefix_chain(
+                        Op::Select,
+                        leaf(id("col1")),
+                        prefix(Op::From, leaf(id("table1")))
+                    ),
+                    prefix_vec(Op::OrReplace, vec![])
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn drop_view_and_table_cascade() {
+        assert_eq!(
+            parse("drop view v restrict"),
+            prefix(Op::DropView, leaf(id("v")))
+        );
+        assert_eq!(
+            parse("drop table table1 cascade"),
+            prefix_vec(
+                Op::DropTable,
+                vec![leaf(id("table1")), prefix_vec(Op::Cascade, vec![])]
+            )
+        );
+    }
+
+    #[test]
+    fn create_table_if_not_exists_with_foreign_keys() {
+        assert_eq!(
+            parse("create table if not exists t (a int references t2, b int, foreign key (b) references t3 (c))"),
+            prefix_vec(
+                Op::CreateTable,
+                vec![
+                    leaf(id("t")),
+                    infix_vec(
+                        Op::Comma,
+                        vec![
+                            infix_vec(
+                                Op::ColumnDefinition,
+                                vec![
+                                    leaf(id("a")),
+                                    Node::LeafType(Type::Int),
+                                    prefix_vec(Op::References, vec![leaf(id("t2")), prefix_vec(Op::ColumnList, vec![])])
+                                ]
+                            ),
+                            infix_vec(Op::ColumnDefinition, vec![leaf(id("b")), Node::LeafType(Type::Int)]),
+                            prefix_vec(
+                                Op::ForeignKey,
+                                vec![
+                                    prefix(Op::ColumnList, leaf(id("b"))),
+                                    prefix_vec(Op::References, vec![leaf(id("t3")), prefix(Op::ColumnList, leaf(id("c")))])
+                                ]
+                            )
+                        ]
+                    ),
+                    prefix_vec(Op::IfNotExists, vec![])
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn drop_tables_if_exists() {
         assert_eq!(
-            parse("not 1 = 1"),
-            infix(Op::Equals, prefix(Op::Not, leaf(num(1))), leaf(num(1)))
+            parse("drop table if exists a, b, c cascade"),
+            prefix_vec(
+                Op::DropTable,
+                vec![
+                    leaf(id("a")),
+                    leaf(id("b")),
+                    leaf(id("c")),
+                    prefix_vec(Op::IfExists, vec![]),
+                    prefix_vec(Op::Cascade, vec![])
+                ]
+            )
         );
     }
 
     #[test]
-    fn where_expr_with_parentheses() {
+    fn create_table_with_nullability_and_defaults() {
         assert_eq!(
-            parse("(1 + 2) * 3"),
-            infix(
-                Op::Multiply,
-                infix(Op::Plus, leaf(num(1)), leaf(num(2))),
-                leaf(num(3))
+            parse("create table t (a int not null default -1, b text null default 'x' || 'y', c date default date '2024-01-31' unique)"),
+            prefix_chain(
+                Op::CreateTable,
+                leaf(id("t")),
+                infix_vec(
+                    Op::Comma,
+                    vec![
+                        infix_vec(
+                            Op::ColumnDefinition,
+                            vec![
+                                leaf(id("a")),
+                                leaf_type(Type::Int),
+                                prefix_vec(Op::NotNull, vec![]),
+                                prefix(Op::Default, leaf(num(-1)))
+                            ]
+                        ),
+                        infix_vec(
+                            Op::ColumnDefinition,
+                            vec![
+                                leaf(id("b")),
+                                leaf_type(Type::String),
+                                prefix(Op::Default, infix(Op::Concat, leaf(string("x")), leaf(string("y"))))
+                            ]
+                        ),
+                        infix_vec(
+                            Op::ColumnDefinition,
+                            vec![
+                                leaf(id("c")),
+                                leaf_type(Type::Date),
+                                prefix(Op::Default, leaf(Literal::Date(Date::parse("2024-01-31").unwrap()))),
+                                prefix_vec(Op::Unique, vec![])
+                            ]
+                        )
+                    ]
+                )
             )
         );
     }
 
     #[test]
-    #[ignore]
-    // TODO: implement function call
-    fn postfix_function_call() {
-        let input = "func(1, 2)";
-
-        let lexer = Lexer::new(input);
-        let mut parser = Parser::new(lexer);
-
-        let _ = parser.parse();
+    fn create_and_drop_index() {
+        assert_eq!(
+            parse("create index i on t (a)"),
+            prefix_vec(
+                Op::CreateIndex,
+                vec![
+                    leaf(id("i")),
+                    leaf(id("t")),
+                    prefix(Op::ColumnList, leaf(id("a")))
+                ]
+            )
+        );
+        assert_eq!(
+            parse("create unique index if not exists i on t (a, b)"),
+            prefix_vec(
+                Op::CreateIndex,
+                vec![
+                    leaf(id("i")),
+                    leaf(id("t")),
+                    prefix_vec(Op::ColumnList, vec![leaf(id("a")), leaf(id("b"))]),
+                    prefix_vec(Op::Unique, vec![]),
+                    prefix_vec(Op::IfNotExists, vec![])
+                ]
+            )
+        );
+        assert_eq!(
+            parse("drop index if exists i"),
+            prefix_vec(
+                Op::DropIndex,
+                vec![leaf(id("i")), prefix_vec(Op::IfExists, vec![])]
+            )
+        );
     }
 
     #[test]
-    fn where_expr_with_parentheses_2() {
+    fn create_table_as() {
         assert_eq!(
-            parse("(1 = 1 and 2 >= 3) * 1 or 4 < 5 + 6"),
-            infix(
-                Op::Or,
-                infix(
-                    Op::Multiply,
-                    infix(
-                        Op::And,
-                        infix(Op::Equals, leaf(num(1)), leaf(num(1))),
-                        infix(Op::GreaterThanOrEquals, leaf(num(2)), leaf(num(3)))
+            parse("create table if not exists t as select col1 from table1"),
+            prefix_vec(
+                Op::CreateTableAs,
+                vec![
+                    leaf(id("t")),
+                    prefix_chain(
+                        Op::Select,
+                        leaf(id("col1")),
+                        prefix(Op::From, leaf(id("table1")))
                     ),
-                    leaf(num(1))
-                ),
-                infix(
-                    Op::LessThan,
-                    leaf(num(4)),
-                    infix(Op::Plus, leaf(num(5)), leaf(num(6)))
-                )
+                    prefix_vec(Op::IfNotExists, vec![])
+                ]
             )
         );
     }
 
     #[test]
-    fn select_query_without_from() {
-        assert_eq!(parse("select 1"), prefix(Op::Select, leaf(num(1))));
+    fn transaction_control() {
+        assert_eq!(parse("begin"), prefix_vec(Op::Begin, vec![]));
+        assert_eq!(
+            parse("begin transaction isolation level repeatable read"),
+            prefix(Op::Begin, prefix_vec(Op::RepeatableRead, vec![]))
+        );
+        assert_eq!(parse("commit transaction;"), prefix_vec(Op::Commit, vec![]));
+        assert_eq!(parse("rollback"), prefix_vec(Op::Rollback, vec![]));
+        assert_eq!(parse("savepoint s"), prefix(Op::Savepoint, leaf(id("s"))));
+        assert_eq!(
+            parse("rollback to savepoint s"),
+            prefix(Op::RollbackTo, leaf(id("s")))
+        );
+        assert_eq!(parse("release s"), prefix(Op::Release, leaf(id("s"))));
     }
 
     #[test]
-    fn select_query_with_from() {
+    fn non_reserved_keywords_are_names() {
         assert_eq!(
-            parse("select 1 from table1"),
-            prefix_chain(
-                Op::Select,
-                leaf(num(1)),
-                prefix(Op::From, leaf(id("table1")))
-            )
+            parse("create table t (level int, rows int, Current text)"),
+            parse(r#"create table t ("level" int, "rows" int, "current" text)"#)
+        );
+        assert_eq!(
+            parse("select level, rows - 1 from row where to = do and over > read"),
+            parse(r#"select "level", "rows" - 1 from "row" where "to" = "do" and "over" > "read""#)
+        );
+        assert_eq!(
+            parse("insert into view (level) values (1) on conflict (level) do nothing"),
+            parse(r#"insert into "view" ("level") values (1) on conflict ("level") do nothing"#)
+        );
+        assert_eq!(
+            parse("select sum(rows) over (partition by partition order by rows) from t"),
+            parse(r#"select sum("rows") over (partition by "partition" order by "rows") from t"#)
         );
     }
 
     #[test]
-    fn select_query_many_columns() {
+    fn statements_are_not_expressions() {
         assert_eq!(
-            parse("select col1, col2 from table1"),
-            prefix_chain(
-                Op::Select,
-                infix(Op::Comma, leaf(id("col1")), leaf(id("col2"))),
-                prefix(Op::From, leaf(id("table1")))
-            )
+            parse_error("select commit"),
+            ParseError {
+                kind: ParseErrorKind::UnexpectedToken("commit".to_string()),
+                span: Span { start: 7, end: 13 }
+            }
+        );
+        assert_eq!(
+            parse_error("select 1 from t where begin").to_string(),
+            "Unexpected token: begin at 22..27"
+        );
+        assert_eq!(
+            parse_error("insert into t values (rollback, 'x')").to_string(),
+            "Unexpected token: rollback at 22..30"
+        );
+        assert_eq!(
+            parse_error("select 1 + select 2").to_string(),
+            "Unexpected token: select at 11..17"
         );
     }
 
     #[test]
-    fn select_query_many_commas() {
+    fn explain() {
         assert_eq!(
-            parse("select col1, col2, 1 + 1 from table1, table2"),
-            prefix_chain(
-                Op::Select,
-                infix(
-                    Op::Comma,
-                    infix(Op::Comma, leaf(id("col1")), leaf(id("col2"))),
-                    infix(Op::Plus, leaf(num(1)), leaf(num(1)))
-                ),
-                prefix(
-                    Op::From,
-                    infix(Op::Comma, leaf(id("table1")), leaf(id("table2")))
+            parse("explain analyze select col1 from table1"),
+            prefix(
+                Op::ExplainAnalyze,
+                prefix_chain(
+                    Op::Select,
+                    leaf(id("col1")),
+                    prefix(Op::From, leaf(id("table1")))
                 )
             )
         );
     }
 
     #[test]
-    fn select_query_with_where() {
+    fn parameters() {
         assert_eq!(
-            parse("select col1 from table1 where col1 = 1"),
+            parse("select ? from table1 where col1 = $3 and col2 = ? or col3 = :name"),
             prefix_chain(
                 Op::Select,
-                leaf(id("col1")),
+                leaf(Literal::Parameter(Parameter::Positional(1))),
                 prefix_chain(
                     Op::From,
                     leaf(id("table1")),
                     prefix(
                         Op::Where,
-                        infix(Op::Equals, leaf(id("col1")), leaf(num(1)),)
+                        infix(
+                            Op::Or,
+                            infix(
+                                Op::And,
+                                infix(
+                                    Op::Equals,
+                                    leaf(id("col1")),
+                                    leaf(Literal::Parameter(Parameter::Positional(3)))
+                                ),
+                                infix(
+                                    Op::Equals,
+                                    leaf(id("col2")),
+                                    leaf(Literal::Parameter(Parameter::Positional(2)))
+                                )
+                            ),
+                            infix(
+                                Op::Equals,
+                                leaf(id("col3")),
+                                leaf(Literal::Parameter(Parameter::Named("name".to_string())))
+                            )
+                        )
                     )
                 )
             )
@@ -733,35 +3395,18 @@ mod tests {
     }
 
     #[test]
-    fn select_query_with_where_and_and() {
-        let input = r"
-        select col1, col2, col3 
-        from table1, table2 
-        where (col1 = 1 or col2 = 2) and col3 >= 3";
-
+    fn quoted_names_and_strings() {
         assert_eq!(
-            parse(input),
+            parse(r#"select "Order Items", "a.b" from "select" where col1 = 'it''s'"#),
             prefix_chain(
                 Op::Select,
-                infix(
-                    Op::Comma,
-                    infix(Op::Comma, leaf(id("col1")), leaf(id("col2"))),
-                    leaf(id("col3"))
-                ),
+                infix(Op::Comma, leaf(quoted("Order Items")), leaf(quoted("a.b"))),
                 prefix_chain(
                     Op::From,
-                    infix(Op::Comma, leaf(id("table1")), leaf(id("table2"))),
+                    leaf(quoted("select")),
                     prefix(
                         Op::Where,
-                        infix(
-                            Op::And,
-                            infix(
-                                Op::Or,
-                                infix(Op::Equals, leaf(id("col1")), leaf(num(1))),
-                                infix(Op::Equals, leaf(id("col2")), leaf(num(2)))
-                            ),
-                            infix(Op::GreaterThanOrEquals, leaf(id("col3")), leaf(num(3)))
-                        )
+                        infix(Op::Equals, leaf(id("col1")), leaf(string("it's")))
                     )
                 )
             )
@@ -769,111 +3414,185 @@ mod tests {
     }
 
     #[test]
-    fn create_table_with_single_column() {
-        let input = r"
-        create table table1 (
-            col1 int
-        )";
+    fn numeric_literal_values() {
+        let decimal = |mantissa, scale| Some(Literal::Decimal(Decimal { mantissa, scale }));
 
+        assert_eq!(Literal::numeric("42"), Some(num(42)));
         assert_eq!(
-            dbg!(parse(input)),
-            prefix_chain(
-                Op::CreateTable,
-                leaf(id("table1")),
-                infix(Op::ColumnDefinition, leaf(id("col1")), leaf_type(Type::Int))
-            )
+            Literal::numeric("-9223372036854775808"),
+            Some(num(i64::MIN))
+        );
+        assert_eq!(
+            Literal::numeric("9223372036854775808"),
+            decimal(9223372036854775808, 0)
+        );
+        assert_eq!(Literal::numeric("0xFF"), Some(num(255)));
+        assert_eq!(Literal::numeric("-0x10"), Some(num(-16)));
+        assert_eq!(Literal::numeric("1.50"), decimal(150, 2));
+        assert_eq!(Literal::numeric("-.5"), decimal(-5, 1));
+        assert_eq!(Literal::numeric("7."), decimal(7, 0));
+        assert_eq!(Literal::numeric("1e-3"), Some(Literal::Float(0.001)));
+        assert_eq!(Literal::numeric("1e999"), None);
+        assert_eq!(
+            Literal::numeric("340282366920938463463374607431768211456"),
+            None
         );
     }
 
     #[test]
-    fn create_table_two_columns() {
-        let input = r"
-        create table table1 (
-            col1 int,
-            col2 int
-        )";
+    fn decimal_display() {
+        let decimal = |mantissa, scale| Decimal { mantissa, scale }.to_string();
+
+        assert_eq!(decimal(150, 2), "1.50");
+        assert_eq!(decimal(-5, 3), "-0.005");
+        assert_eq!(decimal(7, 0), "7");
+    }
+
+    #[test]
+    fn decimal_arithmetic() {
+        let decimal = |mantissa, scale| Decimal { mantissa, scale };
 
         assert_eq!(
-            parse(input),
-            prefix_chain(
-                Op::CreateTable,
-                leaf(id("table1")),
+            decimal(1, 1).checked_add(decimal(2, 1)),
+            Some(decimal(3, 1))
+        );
+        assert_eq!(
+            decimal(150, 2).checked_sub(decimal(2, 0)),
+            Some(decimal(-50, 2))
+        );
+        assert_eq!(
+            decimal(15, 1).checked_mul(decimal(-3, 1)),
+            Some(decimal(-45, 2))
+        );
+        assert_eq!(
+            decimal(2, 0).checked_div(decimal(3, 0)),
+            Some(decimal(666667, 6))
+        );
+        assert_eq!(
+            decimal(-1, 0).checked_div(decimal(3, 0)),
+            Some(decimal(-333333, 6))
+        );
+        assert_eq!(
+            decimal(1, 0).checked_div(decimal(8, 7)),
+            Some(decimal(12500000000000, 7))
+        );
+        assert_eq!(
+            decimal(75, 1).checked_rem(decimal(-2, 0)),
+            Some(decimal(15, 1))
+        );
+        assert_eq!(decimal(1, 0).checked_div(decimal(0, 2)), None);
+        assert_eq!(decimal(i128::MAX, 0).checked_add(decimal(1, 0)), None);
+        assert_eq!(decimal(i128::MAX, 0).checked_add(decimal(1, 1)), None);
+        assert_eq!(decimal(i128::MIN, 0).checked_neg(), None);
+        assert_eq!(decimal(15, 1).compare(decimal(150, 2)), Ordering::Equal);
+        assert_eq!(
+            decimal(i128::MAX, 0).compare(decimal(1, 30)),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn temporal_literals() {
+        assert_eq!(
+            parse("select date '2024-01-31', timestamp '2024-01-31 12:00' - interval '3 days'"),
+            prefix(
+                Op::Select,
                 infix(
                     Op::Comma,
-                    infix(Op::ColumnDefinition, leaf(id("col1")), leaf_type(Type::Int)),
-                    infix(Op::ColumnDefinition, leaf(id("col2")), leaf_type(Type::Int))
+                    leaf(Literal::Date(Date::parse("2024-01-31").unwrap())),
+                    infix(
+                        Op::Minus,
+                        leaf(Literal::Timestamp(
+                            Timestamp::parse("2024-01-31 12:00").unwrap()
+                        )),
+                        leaf(Literal::Interval(Interval::parse("3 days").unwrap()))
+                    )
                 )
             )
         );
     }
 
     #[test]
-    fn create_table_three_columns() {
-        let input = r"
-        create table table1 (
-            col1 int,
-            col2 int,
-            col3 int
-        )";
+    fn comments() {
+        assert_eq!(
+            parse("-- first\nselect a, /* second */ b -- last"),
+            parse("select a, b")
+        );
+    }
 
+    #[test]
+    fn unary_modulo_and_concat() {
         assert_eq!(
-            parse(input),
-            prefix_chain(
-                Op::CreateTable,
-                leaf(id("table1")),
-                infix_vec(
+            parse("select - a * b % 3, + c || 'x' || d = 'y', - 5, - 2.5"),
+            prefix(
+                Op::Select,
+                infix(
                     Op::Comma,
-                    vec![
-                        infix(Op::ColumnDefinition, leaf(id("col1")), leaf_type(Type::Int)),
-                        infix(Op::ColumnDefinition, leaf(id("col2")), leaf_type(Type::Int)),
-                        infix(Op::ColumnDefinition, leaf(id("col3")), leaf_type(Type::Int))
-                    ]
+                    infix(
+                        Op::Comma,
+                        infix(
+                            Op::Comma,
+                            infix(
+                                Op::Modulo,
+                                infix(
+                                    Op::Multiply,
+                                    prefix(Op::Minus, leaf(id("a"))),
+                                    leaf(id("b"))
+                                ),
+                                leaf(num(3))
+                            ),
+                            infix(
+                                Op::Equals,
+                                infix(
+                                    Op::Concat,
+                                    infix(
+                                        Op::Concat,
+                                        prefix(Op::Plus, leaf(id("c"))),
+                                        leaf(string("x"))
+                                    ),
+                                    leaf(id("d"))
+                                ),
+                                leaf(string("y"))
+                            )
+                        ),
+                        leaf(num(-5))
+                    ),
+                    leaf(Literal::Decimal(Decimal {
+                        mantissa: -25,
+                        scale: 1
+                    }))
                 )
             )
         );
     }
 
     #[test]
-    fn drop_table() {
+    fn invalid_date_literal() {
         assert_eq!(
-            parse("drop table table1"),
-            prefix(Op::DropTable, leaf(id("table1")),)
+            parse_error("select date '2023-02-29'"),
+            ParseError {
+                kind: ParseErrorKind::Temporal(TemporalError::InvalidDate(
+                    "2023-02-29".to_string()
+                )),
+                span: Span { start: 12, end: 24 }
+            }
         );
-    }
-
-    #[test]
-    fn insert_into_single_column() {
-        let input = r"
-        insert into table1 (col1) values (1)";
-
         assert_eq!(
-            parse(input),
-            prefix_vec(
-                Op::InsertInto,
-                vec![
-                    leaf(id("table1")),
-                    prefix_vec(Op::ColumnList, vec![leaf(id("col1"))]),
-                    prefix_vec(Op::Values, vec![leaf(num(1))])
-                ]
-            )
+            parse_error("select date '2023-02-29'").to_string(),
+            "Invalid date: '2023-02-29' at 12..24"
         );
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn insert_into() {
-        let input = r"
-        insert into table1 (col1, col2) values (1, 2)";
+    fn serialize_to_json() {
+        let ast = parse("select a + 1, date '2024-01-31' from t where b = $1");
+        let json = serde_json::to_string(&ast).unwrap();
 
         assert_eq!(
-            parse(input),
-            prefix_vec(
-                Op::InsertInto,
-                vec![
-                    leaf(id("table1")),
-                    prefix_vec(Op::ColumnList, vec![leaf(id("col1")), leaf(id("col2"))]),
-                    prefix_vec(Op::Values, vec![leaf(num(1)), leaf(num(2))])
-                ]
-            )
+            json,
+            r#"{"prefix":["select",[{"infix":["comma",[{"infix":["plus",[{"leaf":{"identifier":{"first_name":"a","second_name":null,"third_name":null}}},{"leaf":{"numeric":1}}]]},{"leaf":{"date":"2024-01-31"}}]]},{"prefix":["from",[{"leaf":{"identifier":{"first_name":"t","second_name":null,"third_name":null}}},{"prefix":["where",[{"infix":["equals",[{"leaf":{"identifier":{"first_name":"b","second_name":null,"third_name":null}}},{"leaf":{"parameter":{"positional":1}}}]]}]]}]]}]]}"#
         );
+        assert_eq!(serde_json::from_str::<Node>(&json).unwrap(), ast);
     }
-}
\ No newline at end of file
+}
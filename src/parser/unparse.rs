@@ -0,0 +1,740 @@
+use std::fmt;
+
+use super::lexer::tokens::Token;
+use super::lexer::Lexer;
+use super::{Literal, Node, Op, Parameter, Parser, Type};
+
+impl Node {
+    /// SQL text that parses back to this node.
+    pub fn to_sql(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_node(f, self, 0)
+    }
+}
+
+/// Binding power of an expression, as the parser sees it: an expression
+/// written where the parser reads up to `min_bp` must bind at least that
+/// tightly, or be parenthesized.
+fn binding_power(node: &Node) -> u8 {
+    match node {
+        Node::Leaf(_) | Node::LeafType(_) | Node::Postfix(..) => u8::MAX,
+        Node::Prefix(op, _) => Parser::prefix_operator_bp(op).map_or(0, |((), bp)| bp),
+        Node::Infix(op, _) if is_expression_op(*op) => {
+            Parser::infix_operator_bp(op).map_or(0, |(bp, _)| bp)
+        }
+        // Statements are only allowed in parentheses inside expressions.
+        _ => 0,
+    }
+}
+
+fn is_expression_op(op: Op) -> bool {
+    !matches!(op, Op::Union | Op::UnionAll | Op::ColumnDefinition)
+}
+
+fn write_node(f: &mut fmt::Formatter<'_>, node: &Node, min_bp: u8) -> fmt::Result {
+    if binding_power(node) < min_bp {
+        write!(f, "(")?;
+        write_node(f, node, 0)?;
+        return write!(f, ")");
+    }
+
+    match node {
+        Node::Leaf(literal) => write_literal(f, literal),
+        Node::LeafType(typ) => write!(f, "{}", type_name(*typ)),
+        Node::Infix(op @ (Op::Union | Op::UnionAll), children) => {
+            let keyword = match op {
+                Op::Union => "UNION",
+                _ => "UNION ALL",
+            };
+
+            write!(f, "{} {} {}", children[0], keyword, children[1])
+        }
+        Node::Infix(op, children) => {
+            let (l_bp, r_bp) = Parser::infix_operator_bp(op).unwrap_or_default();
+
+            write_node(f, &children[0], l_bp)?;
+
+            match op {
+                Op::Comma => write!(f, ", ")?,
+                op => write!(f, " {} ", op_symbol(*op))?,
+            }
+
+            write_node(f, &children[1], r_bp)
+        }
+        Node::Prefix(Op::Not, children) => {
+            write!(f, "NOT ")?;
+            write_node(f, &children[0], binding_power(node))
+        }
+        Node::Prefix(op @ (Op::Minus | Op::Plus), children) => {
+            write!(f, "{}", op_symbol(*op))?;
+
+            // `- -a` must not become the start of a comment.
+            match &children[0] {
+                operand @ (Node::Prefix(..)
+                | Node::Leaf(
+                    Literal::Numeric(..) | Literal::Float(..) | Literal::Decimal(..),
+                )) => {
+                    write!(f, "(")?;
+                    write_node(f, operand, 0)?;
+                    write!(f, ")")
+                }
+                operand => write_node(f, operand, binding_power(node)),
+            }
+        }
+        Node::Postfix(Op::FunctionCall, children) => {
+            write!(f, "{}(", children[0])?;
+            write_list(f, children[1].children(), ", ")?;
+            write!(f, ")")?;
+
+            if let Some(window) = children.get(2) {
+                write!(f, " OVER (")?;
+                write_window(f, window)?;
+                write!(f, ")")?;
+            }
+
+            Ok(())
+        }
+        Node::Prefix(op, children) => write_statement(f, *op, children),
+        _ => panic!("Unexpected node: {:?}", node),
+    }
+}
+
+/// Items separated by `separator`, each written so that the parser reads
+/// it as a single expression.
+fn write_list(f: &mut fmt::Formatter<'_>, nodes: &[Node], separator: &str) -> fmt::Result {
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", separator)?;
+        }
+
+        write_node(f, node, 2)?;
+    }
+
+    Ok(())
+}
+
+/// `(a, b)`, or nothing for an empty `ColumnList`.
+fn write_column_list(f: &mut fmt::Formatter<'_>, columns: &Node) -> fmt::Result {
+    match columns.children() {
+        [] => Ok(()),
+        columns => {
+            write!(f, " (")?;
+            write_list(f, columns, ", ")?;
+            write!(f, ")")
+        }
+    }
+}
+
+fn write_statement(f: &mut fmt::Formatter<'_>, op: Op, children: &[Node]) -> fmt::Result {
+    match op {
+        Op::Select => {
+            write!(f, "SELECT ")?;
+            write_node(f, &children[0], 1)?;
+
+            if let Some(from) = children.get(1) {
+                let from = from.children();
+
+                write!(f, " FROM ")?;
+                write_node(f, &from[0], 1)?;
+
+                if let Some(where_node) = from.get(1) {
+                    write_where(f, where_node)?;
+                }
+            }
+
+            Ok(())
+        }
+        Op::With | Op::WithRecursive => {
+            write!(f, "WITH ")?;
+
+            if op == Op::WithRecursive {
+                write!(f, "RECURSIVE ")?;
+            }
+
+            let (query, ctes) = children.split_last().unwrap();
+
+            for (i, cte) in ctes.iter().enumerate() {
+                let cte = cte.children();
+
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+
+                write!(f, "{}", cte[0])?;
+                write_column_list(f, &cte[1])?;
+                write!(f, " AS ({})", cte[2])?;
+            }
+
+            write!(f, " {}", query)
+        }
+        Op::CreateTable | Op::CreateTableAs => {
+            write!(f, "CREATE TABLE ")?;
+
+            if children.contains(&Node::Prefix(Op::IfNotExists, vec![])) {
+                write!(f, "IF NOT EXISTS ")?;
+            }
+
+            write!(f, "{}", children[0])?;
+
+            if op == Op::CreateTableAs {
+                return write!(f, " AS {}", children[1]);
+            }
+
+            let columns = match &children[1] {
+                Node::Infix(Op::Comma, columns) => columns.clone(),
+                column => vec![column.clone()],
+            };
+
+            write!(f, " (")?;
+
+            for (i, column) in columns.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+
+                write_table_element(f, column)?;
+            }
+
+            write!(f, ")")
+        }
+        Op::DropTable | Op::DropView => {
+            let (names, options): (Vec<&Node>, Vec<&Node>) = children
+                .iter()
+                .partition(|child| matches!(child, Node::Leaf(_)));
+
+            match op {
+                Op::DropTable => write!(f, "DROP TABLE ")?,
+                _ => write!(f, "DROP VIEW ")?,
+            }
+
+            if options.contains(&&Node::Prefix(Op::IfExists, vec![])) {
+                write!(f, "IF EXISTS ")?;
+            }
+
+            for (i, name) in names.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+
+                write!(f, "{}", name)?;
+            }
+
+            if options.contains(&&Node::Prefix(Op::Cascade, vec![])) {
+                write!(f, " CASCADE")?;
+            }
+
+            Ok(())
+        }
+        Op::CreateIndex => {
+            write!(f, "CREATE ")?;
+
+            if children.contains(&Node::Prefix(Op::Unique, vec![])) {
+                write!(f, "UNIQUE ")?;
+            }
+
+            write!(f, "INDEX ")?;
+
+            if children.contains(&Node::Prefix(Op::IfNotExists, vec![])) {
+                write!(f, "IF NOT EXISTS ")?;
+            }
+
+            write!(f, "{} ON {} (", children[0], children[1])?;
+            write_list(f, children[2].children(), ", ")?;
+            write!(f, ")")
+        }
+        Op::DropIndex => {
+            write!(f, "DROP INDEX ")?;
+
+            if children.contains(&Node::Prefix(Op::IfExists, vec![])) {
+                write!(f, "IF EXISTS ")?;
+            }
+
+            write!(f, "{}", children[0])
+        }
+        // The view is written with the text of its query, which is what
+        // the parser keeps next to the parsed query.
+        Op::CreateView => {
+            write!(f, "CREATE ")?;
+
+            if children.get(4).is_some() {
+                write!(f, "OR REPLACE ")?;
+            }
+
+            write!(f, "VIEW {}", children[0])?;
+            write_column_list(f, &children[1])?;
+
+            match &children[2] {
+                Node::Leaf(Literal::String(text)) => write!(f, " AS {}", text),
+                node => panic!("Unexpected node: {:?}", node),
+            }
+        }
+        Op::InsertInto => {
+            write!(f, "INSERT INTO {}", children[0])?;
+            write_column_list(f, &children[1])?;
+
+            match &children[2] {
+                Node::Prefix(Op::Values, rows) => {
+                    write!(f, " VALUES ")?;
+
+                    for (i, row) in rows.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+
+                        write_row(f, row)?;
+                    }
+                }
+                query => write!(f, " {}", query)?,
+            }
+
+            for child in &children[3..] {
+                match child {
+                    Node::Prefix(Op::OnConflict, on_conflict) => {
+                        write!(f, " ON CONFLICT")?;
+                        write_column_list(f, &on_conflict[0])?;
+
+                        match &on_conflict[1] {
+                            Node::Prefix(Op::DoUpdate, action) => {
+                                write!(f, " DO UPDATE ")?;
+                                write_assignments(f, &action[0])?;
+
+                                if let Some(where_node) = action.get(1) {
+                                    write_where(f, where_node)?;
+                                }
+                            }
+                            _ => write!(f, " DO NOTHING")?,
+                        }
+                    }
+                    returning => write_returning(f, returning)?,
+                }
+            }
+
+            Ok(())
+        }
+        Op::Update => {
+            write!(f, "UPDATE {} ", children[0])?;
+            write_assignments(f, &children[1])?;
+            write_where_and_returning(f, &children[2..])
+        }
+        Op::Delete => {
+            write!(f, "DELETE FROM {}", children[0])?;
+            write_where_and_returning(f, &children[1..])
+        }
+        Op::Merge => {
+            write!(f, "MERGE INTO {} USING {} ON ", children[0], children[1])?;
+            write_node(f, &children[2], 1)?;
+
+            for clause in &children[3..] {
+                let (op, clause) = match clause {
+                    Node::Prefix(op, clause) => (*op, clause),
+                    node => panic!("Unexpected node: {:?}", node),
+                };
+
+                match op {
+                    Op::WhenMatched => write!(f, " WHEN MATCHED")?,
+                    _ => write!(f, " WHEN NOT MATCHED")?,
+                }
+
+                if let Some(condition) = clause.get(1) {
+                    write!(f, " AND ")?;
+                    write_node(f, &condition.children()[0], 1)?;
+                }
+
+                write!(f, " THEN ")?;
+
+                match &clause[0] {
+                    Node::Prefix(Op::Set, _) => {
+                        write!(f, "UPDATE ")?;
+                        write_assignments(f, &clause[0])?;
+                    }
+                    Node::Prefix(Op::Delete, _) => write!(f, "DELETE")?,
+                    Node::Prefix(Op::InsertInto, insert) => {
+                        write!(f, "INSERT")?;
+                        write_column_list(f, &insert[0])?;
+                        write!(f, " VALUES ")?;
+                        write_row(f, &insert[1])?;
+                    }
+                    _ => write!(f, "DO NOTHING")?,
+                }
+            }
+
+            Ok(())
+        }
+        Op::Begin => {
+            write!(f, "BEGIN")?;
+
+            match children.first().and_then(Node::op) {
+                Some(Op::ReadUncommitted) => write!(f, " ISOLATION LEVEL READ UNCOMMITTED"),
+                Some(Op::ReadCommitted) => write!(f, " ISOLATION LEVEL READ COMMITTED"),
+                Some(Op::RepeatableRead) => write!(f, " ISOLATION LEVEL REPEATABLE READ"),
+                Some(Op::Serializable) => write!(f, " ISOLATION LEVEL SERIALIZABLE"),
+                _ => Ok(()),
+            }
+        }
+        Op::Commit => write!(f, "COMMIT"),
+        Op::Rollback => write!(f, "ROLLBACK"),
+        Op::RollbackTo => write!(f, "ROLLBACK TO SAVEPOINT {}", children[0]),
+        Op::Savepoint => write!(f, "SAVEPOINT {}", children[0]),
+        Op::Release => write!(f, "RELEASE SAVEPOINT {}", children[0]),
+        Op::Explain => write!(f, "EXPLAIN {}", children[0]),
+        Op::ExplainAnalyze => write!(f, "EXPLAIN ANALYZE {}", children[0]),
+        op => panic!("Unexpected operator: {:?}", op),
+    }
+}
+
+/// A column definition or a table constraint of `CREATE TABLE`.
+fn write_table_element(f: &mut fmt::Formatter<'_>, element: &Node) -> fmt::Result {
+    match element {
+        Node::Infix(Op::ColumnDefinition, definition) => {
+            write!(f, "{} {}", definition[0], definition[1])?;
+
+            for constraint in &definition[2..] {
+                match constraint {
+                    Node::Prefix(Op::PrimaryKey, _) => write!(f, " PRIMARY KEY")?,
+                    Node::Prefix(Op::Unique, _) => write!(f, " UNIQUE")?,
+                    Node::Prefix(Op::NotNull, _) => write!(f, " NOT NULL")?,
+                    Node::Prefix(Op::Default, value) => {
+                        write!(f, " DEFAULT ")?;
+                        write_node(f, &value[0], 2)?;
+                    }
+                    references => write_references(f, references)?,
+                }
+            }
+
+            Ok(())
+        }
+        Node::Prefix(Op::PrimaryKey, columns) => {
+            write!(f, "PRIMARY KEY (")?;
+            write_list(f, columns, ", ")?;
+            write!(f, ")")
+        }
+        Node::Prefix(Op::Unique, columns) => {
+            write!(f, "UNIQUE (")?;
+            write_list(f, columns, ", ")?;
+            write!(f, ")")
+        }
+        Node::Prefix(Op::ForeignKey, children) => {
+            write!(f, "FOREIGN KEY")?;
+            write_column_list(f, &children[0])?;
+            write_references(f, &children[1])
+        }
+        node => panic!("Unexpected node: {:?}", node),
+    }
+}
+
+fn write_references(f: &mut fmt::Formatter<'_>, references: &Node) -> fmt::Result {
+    let references = references.children();
+
+    write!(f, " REFERENCES {}", references[0])?;
+    write_column_list(f, &references[1])
+}
+
+fn write_row(f: &mut fmt::Formatter<'_>, row: &Node) -> fmt::Result {
+    write!(f, "(")?;
+    write_list(f, row.children(), ", ")?;
+    write!(f, ")")
+}
+
+fn write_assignments(f: &mut fmt::Formatter<'_>, set: &Node) -> fmt::Result {
+    write!(f, "SET ")?;
+
+    for (i, assignment) in set.children().iter().enumerate() {
+        let assignment = assignment.children();
+
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+
+        write!(f, "{} = ", assignment[0])?;
+        write_node(f, &assignment[1], 2)?;
+    }
+
+    Ok(())
+}
+
+fn write_where(f: &mut fmt::Formatter<'_>, where_node: &Node) -> fmt::Result {
+    write!(f, " WHERE ")?;
+    write_node(f, &where_node.children()[0], 1)
+}
+
+fn write_returning(f: &mut fmt::Formatter<'_>, returning: &Node) -> fmt::Result {
+    write!(f, " RETURNING ")?;
+    write_node(f, &returning.children()[0], 1)
+}
+
+fn write_where_and_returning(f: &mut fmt::Formatter<'_>, children: &[Node]) -> fmt::Result {
+    for child in children {
+        match child {
+            Node::Prefix(Op::Where, _) => write_where(f, child)?,
+            _ => write_returning(f, child)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn write_window(f: &mut fmt::Formatter<'_>, window: &Node) -> fmt::Result {
+    for (i, clause) in window.children().iter().enumerate() {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+
+        match clause {
+            Node::Prefix(Op::PartitionBy, expressions) => {
+                write!(f, "PARTITION BY ")?;
+                write_list(f, expressions, ", ")?;
+            }
+            Node::Prefix(Op::OrderBy, keys) => {
+                write!(f, "ORDER BY ")?;
+
+                for (i, key) in keys.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write_node(f, &key.children()[0], 2)?;
+
+                    if key.op() == Some(Op::Desc) {
+                        write!(f, " DESC")?;
+                    }
+                }
+            }
+            Node::Prefix(Op::Rows, bounds) => {
+                write!(f, "ROWS BETWEEN ")?;
+                write_frame_bound(f, &bounds[0])?;
+                write!(f, " AND ")?;
+                write_frame_bound(f, &bounds[1])?;
+            }
+            node => panic!("Unexpected node: {:?}", node),
+        }
+    }
+
+    Ok(())
+}
+
+fn write_frame_bound(f: &mut fmt::Formatter<'_>, bound: &Node) -> fmt::Result {
+    let direction = match bound.op() {
+        Some(Op::Preceding) => "PRECEDING",
+        Some(Op::Following) => "FOLLOWING",
+        _ => return write!(f, "CURRENT ROW"),
+    };
+
+    match bound.children().first() {
+        Some(offset) => write!(f, "{} {}", offset, direction),
+        None => write!(f, "UNBOUNDED {}", direction),
+    }
+}
+
+fn write_literal(f: &mut fmt::Formatter<'_>, literal: &Literal) -> fmt::Result {
+    match literal {
+        Literal::Numeric(i) => write!(f, "{}", i),
+        // Written with an exponent, which is what makes it a float.
+        Literal::Float(x) => write!(f, "{:e}", x),
+        // An integer with a point, so that it is not read back as `Numeric`.
+        Literal::Decimal(d) if d.scale == 0 && i64::try_from(d.mantissa).is_ok() => {
+            write!(f, "{}.", d)
+        }
+        Literal::Decimal(d) => write!(f, "{}", d),
+        Literal::String(s) => write!(f, "'{}'", s.replace('\'', "''")),
+        Literal::Date(date) => write!(f, "DATE '{}'", date),
+        Literal::Time(time) => write!(f, "TIME '{}'", time),
+        Literal::Timestamp(timestamp) => write!(f, "TIMESTAMP '{}'", timestamp),
+        Literal::Interval(interval) => write!(f, "INTERVAL '{}'", interval),
+        Literal::Bool(true) => write!(f, "TRUE"),
+        Literal::Bool(false) => write!(f, "FALSE"),
+        Literal::Null => write!(f, "NULL"),
+        Literal::Default => write!(f, "DEFAULT"),
+        Literal::Wildcard => write!(f, "*"),
+        Literal::Identifier {
+            first_name,
+            second_name,
+            third_name,
+        } => {
+            write_name(f, first_name)?;
+
+            for name in second_name.iter().chain(third_name) {
+                write!(f, ".")?;
+                write_name(f, name)?;
+            }
+
+            Ok(())
+        }
+        Literal::Parameter(Parameter::Positional(number)) => write!(f, "${}", number),
+        Literal::Parameter(Parameter::Named(name)) => write!(f, ":{}", name),
+    }
+}
+
+/// A part of a name, in double quotes unless the lexer reads it back as
+/// the same name without them.
+fn write_name(f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+    let mut lexer = Lexer::new(name);
+
+    let is_plain = match lexer.next() {
+        Some(Ok(Token::Identifier {
+            first_name,
+            second_name: None,
+            ..
+        })) => first_name == name && lexer.next().is_none(),
+        Some(Ok(token)) if token.is_non_reserved() => {
+            name == name.to_lowercase() && lexer.next().is_none()
+        }
+        _ => false,
+    };
+
+    match is_plain {
+        true => write!(f, "{}", name),
+        false => write!(f, "\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+fn type_name(typ: Type) -> &'static str {
+    match typ {
+        Type::Int => "INT",
+        Type::Float => "FLOAT",
+        Type::Decimal => "DECIMAL",
+        Type::String => "TEXT",
+        Type::Bool => "BOOLEAN",
+        Type::Date => "DATE",
+        Type::Time => "TIME",
+        Type::Timestamp => "TIMESTAMP",
+        Type::Interval => "INTERVAL",
+    }
+}
+
+fn op_symbol(op: Op) -> &'static str {
+    match op {
+        Op::And => "AND",
+        Op::Or => "OR",
+        Op::Plus => "+",
+        Op::Minus => "-",
+        Op::Multiply => "*",
+        Op::Divide => "/",
+        Op::Modulo => "%",
+        Op::Concat => "||",
+        Op::Equals => "=",
+        Op::NotEquals => "<>",
+        Op::LessThan => "<",
+        Op::GreaterThan => ">",
+        Op::LessThanOrEquals => "<=",
+        Op::GreaterThanOrEquals => ">=",
+        op => panic!("Unexpected operator: {:?}", op),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::lexer::Lexer;
+    use crate::parser::{Decimal, Literal, Node, Op, Parser};
+    use pretty_assertions::assert_eq;
+
+    fn parse(input: &str) -> Node {
+        Parser::new(Lexer::new(input)).parse().unwrap()
+    }
+
+    fn round_trip(input: &str) -> String {
+        let ast = parse(input);
+        let sql = ast.to_sql();
+
+        assert_eq!(parse(&sql), ast, "{}", sql);
+
+        sql
+    }
+
+    #[test]
+    fn write_expressions() {
+        assert_eq!(
+            round_trip(
+                "select (a + b) * -c, a - (b - c), not (a = 1) and b or c, f(x, -(-y)) from t"
+            ),
+            "SELECT (a + b) * -c, a - (b - c), NOT (a = 1) AND b OR c, f(x, -(-y)) FROM t"
+        );
+        assert_eq!(
+            round_trip("select a * (not b), (not a) + 1, x || 'it''s' = 'y' from t"),
+            "SELECT a * (NOT b), NOT a + 1, x || 'it''s' = 'y' FROM t"
+        );
+    }
+
+    #[test]
+    fn write_literals() {
+        assert_eq!(
+            round_trip("select 1.50, 7., 1e-3, -2.5e10, -9223372036854775808, 99999999999999999999, $2, :name"),
+            "SELECT 1.50, 7., 1e-3, -2.5e10, -9223372036854775808, 99999999999999999999, $2, :name"
+        );
+        assert_eq!(
+            round_trip(
+                "select date '2024-01-31', interval '-1 year 2 days -00:00:01.5', time '12:30'"
+            ),
+            "SELECT DATE '2024-01-31', INTERVAL '-1 year 2 days -00:00:01.5', TIME '12:30:00'"
+        );
+    }
+
+    #[test]
+    fn write_names() {
+        assert_eq!(
+            round_trip(r#"select "Select", "a b".c, "say ""hi""", Users.ID from "Order""#),
+            r#"SELECT "Select", "a b".c, "say ""hi""", users.id FROM "Order""#
+        );
+        assert_eq!(
+            round_trip(r#"select level, "Rows" from "row""#),
+            r#"SELECT level, "Rows" FROM row"#
+        );
+    }
+
+    #[test]
+    fn write_statements() {
+        assert_eq!(
+            round_trip(
+                "with recursive r (n) as (select 1 union all select n + 1 from r where n < 3) \
+                 select * from (select n from r) where n = (select 2)"
+            ),
+            "WITH RECURSIVE r (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM r WHERE n < 3) \
+             SELECT * FROM (SELECT n FROM r) WHERE n = (SELECT 2)"
+        );
+        assert_eq!(
+            round_trip(
+                "insert into t (a, b) values (1, default), (?, 'x') \
+                 on conflict (a) do update set b = 'y' where a > 1 returning a"
+            ),
+            "INSERT INTO t (a, b) VALUES (1, DEFAULT), ($1, 'x') \
+             ON CONFLICT (a) DO UPDATE SET b = 'y' WHERE a > 1 RETURNING a"
+        );
+    }
+
+    #[test]
+    fn rewrite_and_write() {
+        let mut ast = parse("select a from orders");
+
+        if let Node::Prefix(Op::Select, children) = &mut ast {
+            children[1] = Node::Prefix(
+                Op::From,
+                vec![
+                    Node::Leaf(Literal::name("tenant_orders".to_string())),
+                    Node::Prefix(
+                        Op::Where,
+                        vec![Node::Infix(
+                            Op::Equals,
+                            vec![
+                                Node::Leaf(Literal::name("tenant".to_string())),
+                                Node::Leaf(Literal::Decimal(Decimal {
+                                    mantissa: 42,
+                                    scale: 0,
+                                })),
+                            ],
+                        )],
+                    ),
+                ],
+            );
+        }
+
+        assert_eq!(
+            ast.to_sql(),
+            "SELECT a FROM tenant_orders WHERE tenant = 42."
+        );
+        assert_eq!(parse(&ast.to_sql()), ast);
+    }
+}
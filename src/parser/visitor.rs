@@ -0,0 +1,245 @@
+use super::{Literal, Node, Op, Type};
+
+/// Walks a tree of nodes. Each method's default visits the children of
+/// what it is given; an implementation that overrides one calls the
+/// matching `walk_` function to keep walking below it.
+pub trait Visitor {
+    fn visit_node(&mut self, node: &Node) {
+        walk_node(self, node)
+    }
+
+    /// A statement, or a query nested in one.
+    fn visit_statement(&mut self, statement: &Node) {
+        walk_children(self, statement)
+    }
+
+    /// An operator or a function call.
+    fn visit_expression(&mut self, expression: &Node) {
+        walk_children(self, expression)
+    }
+
+    fn visit_literal(&mut self, _literal: &Literal) {}
+
+    fn visit_type(&mut self, _typ: &Type) {}
+}
+
+/// Like `Visitor`, for changing the nodes in place or replacing them.
+pub trait VisitorMut {
+    fn visit_node_mut(&mut self, node: &mut Node) {
+        walk_node_mut(self, node)
+    }
+
+    fn visit_statement_mut(&mut self, statement: &mut Node) {
+        walk_children_mut(self, statement)
+    }
+
+    fn visit_expression_mut(&mut self, expression: &mut Node) {
+        walk_children_mut(self, expression)
+    }
+
+    fn visit_literal_mut(&mut self, _literal: &mut Literal) {}
+
+    fn visit_type_mut(&mut self, _typ: &mut Type) {}
+}
+
+/// Calls the method of `visitor` for the kind of `node`. Other nodes, such
+/// as clauses and lists, have their children visited.
+pub fn walk_node<V: Visitor + ?Sized>(visitor: &mut V, node: &Node) {
+    match node {
+        Node::Leaf(literal) => visitor.visit_literal(literal),
+        Node::LeafType(typ) => visitor.visit_type(typ),
+        node if node.is_statement() => visitor.visit_statement(node),
+        node if node.is_expression() => visitor.visit_expression(node),
+        node => walk_children(visitor, node),
+    }
+}
+
+pub fn walk_children<V: Visitor + ?Sized>(visitor: &mut V, node: &Node) {
+    if let Node::Infix(_, children) | Node::Prefix(_, children) | Node::Postfix(_, children) = node
+    {
+        for child in children {
+            visitor.visit_node(child);
+        }
+    }
+}
+
+pub fn walk_node_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Node) {
+    match node {
+        Node::Leaf(literal) => visitor.visit_literal_mut(literal),
+        Node::LeafType(typ) => visitor.visit_type_mut(typ),
+        node if node.is_statement() => visitor.visit_statement_mut(node),
+        node if node.is_expression() => visitor.visit_expression_mut(node),
+        node => walk_children_mut(visitor, node),
+    }
+}
+
+pub fn walk_children_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Node) {
+    if let Node::Infix(_, children) | Node::Prefix(_, children) | Node::Postfix(_, children) = node
+    {
+        for child in children {
+            visitor.visit_node_mut(child);
+        }
+    }
+}
+
+impl Node {
+    /// Whether the node is a statement or a query, as opposed to a clause
+    /// of one. The actions of `MERGE` are not statements, though they share
+    /// their operators with `INSERT`, `UPDATE` and `DELETE`.
+    pub fn is_statement(&self) -> bool {
+        match self {
+            Node::Infix(Op::Union | Op::UnionAll, _) => true,
+            Node::Prefix(Op::InsertInto | Op::Delete, children) => {
+                matches!(children.first(), Some(Node::Leaf(_)))
+            }
+            Node::Prefix(op, _) => matches!(
+                op,
+                Op::Select
+                    | Op::With
+                    | Op::WithRecursive
+                    | Op::Update
+                    | Op::Merge
+                    | Op::CreateTable
+                    | Op::CreateTableAs
+                    | Op::DropTable
+                    | Op::CreateIndex
+                    | Op::DropIndex
+                    | Op::CreateView
+                    | Op::DropView
+                    | Op::Begin
+                    | Op::Commit
+                    | Op::Rollback
+                    | Op::RollbackTo
+                    | Op::Savepoint
+                    | Op::Release
+                    | Op::Explain
+                    | Op::ExplainAnalyze
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether the node applies an operator or calls a function. A list of
+    /// expressions separated by commas is not an expression itself.
+    pub fn is_expression(&self) -> bool {
+        match self {
+            Node::Infix(op, _) => matches!(
+                op,
+                Op::And
+                    | Op::Or
+                    | Op::Plus
+                    | Op::Minus
+                    | Op::Multiply
+                    | Op::Divide
+                    | Op::Modulo
+                    | Op::Concat
+                    | Op::Equals
+                    | Op::NotEquals
+                    | Op::LessThan
+                    | Op::GreaterThan
+                    | Op::LessThanOrEquals
+                    | Op::GreaterThanOrEquals
+            ),
+            Node::Prefix(op, _) => matches!(op, Op::Not | Op::Minus | Op::Plus),
+            Node::Postfix(op, _) => *op == Op::FunctionCall,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{walk_children, walk_children_mut, Visitor, VisitorMut};
+    use crate::parser::lexer::Lexer;
+    use crate::parser::{Literal, Node, Op, Parameter, Parser};
+
+    fn parse(input: &str) -> Node {
+        Parser::new(Lexer::new(input)).parse().unwrap()
+    }
+
+    /// Counts the `SELECT *` in a statement and its subqueries, but not
+    /// `count(*)`.
+    struct SelectStar(usize);
+
+    impl Visitor for SelectStar {
+        fn visit_statement(&mut self, statement: &Node) {
+            if let Node::Prefix(Op::Select, children) = statement {
+                let mut items = &children[0];
+
+                while let Node::Infix(Op::Comma, list) = items {
+                    if list[1] == Node::Leaf(Literal::Wildcard) {
+                        self.0 += 1;
+                    }
+
+                    items = &list[0];
+                }
+
+                if *items == Node::Leaf(Literal::Wildcard) {
+                    self.0 += 1;
+                }
+            }
+
+            walk_children(self, statement);
+        }
+    }
+
+    #[test]
+    fn visit_statements() {
+        let mut lint = SelectStar(0);
+
+        lint.visit_node(&parse(
+            "with c as (select * from t) select count(*), * from c where a = (select * from u)",
+        ));
+
+        assert_eq!(lint.0, 3);
+    }
+
+    /// Renames a table and makes every query of it filter by tenant.
+    struct Tenant;
+
+    impl VisitorMut for Tenant {
+        fn visit_statement_mut(&mut self, statement: &mut Node) {
+            walk_children_mut(self, statement);
+
+            if let Node::Prefix(Op::Select, children) = statement {
+                if let Some(Node::Prefix(Op::From, from)) = children.get_mut(1) {
+                    if from[0] != Node::Leaf(Literal::name("orders".to_string())) {
+                        return;
+                    }
+
+                    from[0] = Node::Leaf(Literal::name("tenant_orders".to_string()));
+
+                    let filter = Node::Infix(
+                        Op::Equals,
+                        vec![
+                            Node::Leaf(Literal::name("tenant".to_string())),
+                            Node::Leaf(Literal::Parameter(Parameter::Named("tenant".to_string()))),
+                        ],
+                    );
+
+                    match from.get_mut(1) {
+                        Some(Node::Prefix(Op::Where, predicate)) => {
+                            predicate[0] = Node::Infix(Op::And, vec![predicate[0].clone(), filter]);
+                        }
+                        _ => from.push(Node::Prefix(Op::Where, vec![filter])),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rewrite_statements() {
+        let mut ast = parse(
+            "select a from orders where a > 1 or b union select c from (select c from orders)",
+        );
+
+        Tenant.visit_node_mut(&mut ast);
+
+        assert_eq!(
+            ast.to_sql(),
+            "SELECT a FROM tenant_orders WHERE (a > 1 OR b) AND tenant = :tenant \
+             UNION SELECT c FROM (SELECT c FROM tenant_orders WHERE tenant = :tenant)"
+        );
+    }
+}
@@ -0,0 +1,1724 @@
+#![allow(dead_code)]
+
+use crate::analyzer::{
+    is_assignable, Analyzer, LogicalPlan, Operator, ParameterInfo, TransactionStatement,
+};
+use crate::catalog::Catalog;
+use crate::executor::value::Value;
+use crate::executor::{ExecutionError, Executor, QueryResult};
+use crate::parser::lexer::Lexer;
+use crate::parser::{Literal, Node, Parser, Spans};
+use crate::storage::Storage;
+use crate::transaction::{Snapshot, TransactionError, TransactionManager};
+
+/// Runs SQL statements against an in-memory database.
+pub struct Session {
+    catalog: Catalog,
+    storage: Storage,
+    transactions: TransactionManager,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Session {
+            catalog: Catalog::new(),
+            storage: Storage::new(),
+            transactions: TransactionManager::new(),
+        }
+    }
+
+    /// Executes a single statement. A statement that fails leaves no partial
+    /// changes behind; inside a transaction, the changes of the statements
+    /// before it stay.
+    pub fn execute(&mut self, sql: &str) -> Result<QueryResult, ExecutionError> {
+        let mut statement = self.prepare(sql)?;
+
+        self.execute_prepared(&mut statement, &[])
+    }
+
+    /// Parses and plans a statement that may contain parameters, to be run
+    /// any number of times with `execute_prepared`.
+    pub fn prepare(&self, sql: &str) -> Result<PreparedStatement, ExecutionError> {
+        let mut parser = Parser::new(Lexer::new(sql));
+        let ast = parser.parse().map_err(ExecutionError::Parse)?;
+
+        self.plan(ast, parser.into_spans())
+    }
+
+    /// Runs a prepared statement with one value per parameter, in parameter
+    /// order. The statement is planned again first if tables or views have
+    /// changed since it was planned.
+    pub fn execute_prepared(
+        &mut self,
+        statement: &mut PreparedStatement,
+        values: &[Value],
+    ) -> Result<QueryResult, ExecutionError> {
+        if statement.catalog_version != self.catalog.version() {
+            *statement = self.plan(statement.ast.clone(), statement.spans.clone())?;
+        }
+
+        if values.len() != statement.parameters.len() {
+            return Err(ExecutionError::ParameterCount {
+                expected: statement.parameters.len(),
+                actual: values.len(),
+            });
+        }
+
+        if values.is_empty() {
+            return self.execute_plan(&statement.plan);
+        }
+
+        let literals = values
+            .iter()
+            .zip(&statement.parameters)
+            .enumerate()
+            .map(|(index, (value, parameter))| bind_value(index, value, parameter))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.execute_plan(&statement.plan.bind(&literals))
+    }
+
+    /// Runs a prepared statement with `:name` parameters.
+    pub fn execute_named(
+        &mut self,
+        statement: &mut PreparedStatement,
+        values: &[(&str, Value)],
+    ) -> Result<QueryResult, ExecutionError> {
+        let values = statement
+            .parameters
+            .iter()
+            .map(|parameter| {
+                let name = parameter.name.as_deref().unwrap_or_default();
+
+                values
+                    .iter()
+                    .find(|(given, _)| *given == name)
+                    .map(|(_, value)| value.clone())
+                    .ok_or_else(|| ExecutionError::UnboundParameter(name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.execute_prepared(statement, &values)
+    }
+
+    fn plan(&self, ast: Node, spans: Spans) -> Result<PreparedStatement, ExecutionError> {
+        let analyzer = Analyzer::with_spans(&self.catalog, &ast, &spans);
+        let plan = analyzer.analyze(&ast).map_err(ExecutionError::Analyze)?;
+        let parameters = analyzer.parameters();
+
+        Ok(PreparedStatement {
+            ast,
+            spans,
+            plan,
+            parameters,
+            catalog_version: self.catalog.version(),
+        })
+    }
+
+    fn execute_plan(&mut self, plan: &LogicalPlan) -> Result<QueryResult, ExecutionError> {
+        if let Operator::Transaction(statement) = &plan.root.operator {
+            return self
+                .control_transaction(statement)
+                .map(|_| QueryResult::Done)
+                .map_err(ExecutionError::Transaction);
+        }
+
+        let catalog = self.catalog.clone();
+        let storage = self.storage.clone();
+
+        let result = Executor::new(&mut self.catalog, &mut self.storage).execute(plan);
+
+        if result.is_err() {
+            self.catalog = catalog;
+            self.storage = storage;
+        }
+
+        result
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.transactions.in_transaction()
+    }
+
+    fn control_transaction(
+        &mut self,
+        statement: &TransactionStatement,
+    ) -> Result<(), TransactionError> {
+        match statement {
+            TransactionStatement::Begin(isolation_level) => {
+                let start = self.snapshot();
+                self.transactions.begin(*isolation_level, start)
+            }
+            TransactionStatement::Commit => self.transactions.commit(),
+            TransactionStatement::Rollback => {
+                let snapshot = self.transactions.rollback()?;
+                self.restore(snapshot);
+                Ok(())
+            }
+            TransactionStatement::Savepoint(name) => {
+                let snapshot = self.snapshot();
+                self.transactions.savepoint(name, snapshot)
+            }
+            TransactionStatement::RollbackTo(name) => {
+                let snapshot = self.transactions.rollback_to(name)?;
+                self.restore(snapshot);
+                Ok(())
+            }
+            TransactionStatement::Release(name) => self.transactions.release(name),
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            catalog: self.catalog.clone(),
+            storage: self.storage.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.catalog = snapshot.catalog;
+        self.storage = snapshot.storage;
+    }
+}
+
+/// A statement planned once, to be run with different parameter values.
+pub struct PreparedStatement {
+    ast: Node,
+    spans: Spans,
+    plan: LogicalPlan,
+    parameters: Vec<ParameterInfo>,
+    catalog_version: u64,
+}
+
+impl PreparedStatement {
+    pub fn parameters(&self) -> &[ParameterInfo] {
+        &self.parameters
+    }
+}
+
+/// The literal to put in place of a parameter, after checking the value
+/// against the parameter's type.
+fn bind_value(
+    index: usize,
+    value: &Value,
+    parameter: &ParameterInfo,
+) -> Result<Literal, ExecutionError> {
+    let value = match (value.typ(), parameter.typ) {
+        (Some(typ), Some(expected)) if !is_assignable(typ, expected) => {
+            return Err(ExecutionError::TypeMismatch(format!(
+                "parameter ${}: expected {:?}, got {:?}",
+                index + 1,
+                expected,
+                typ
+            )))
+        }
+        (_, Some(expected)) => value.clone().coerce(expected),
+        (_, None) => value.clone(),
+    };
+
+    Ok(value.to_literal())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyzer::{AnalyzeError, AnalyzeErrorKind};
+    use crate::executor::value::Value;
+    use crate::executor::{ExecutionError, MergeResult, QueryResult, ResultSet};
+    use crate::parser::{Decimal, ParseError, ParseErrorKind, Span};
+    use crate::session::Session;
+    use crate::temporal::{Date, TemporalError, Timestamp};
+    use crate::transaction::TransactionError;
+    use pretty_assertions::assert_eq;
+
+    fn session() -> Session {
+        let mut session = Session::new();
+
+        session
+            .execute("create table table1 (col1 int, col2 text, col3 float)")
+            .unwrap();
+        session
+            .execute("create table table2 (col4 int, col5 text)")
+            .unwrap();
+
+        session
+    }
+
+    fn query(session: &mut Session, sql: &str) -> Vec<Vec<Value>> {
+        match session.execute(sql) {
+            Ok(QueryResult::Rows(ResultSet { rows, .. })) => rows,
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    fn int(i: i64) -> Value {
+        Value::Int(i)
+    }
+
+    fn text(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    #[test]
+    fn insert_values_and_select() {
+        let mut session = session();
+
+        assert_eq!(
+            session.execute("insert into table1 (col2, col1) values ('a', 1), (NULL, DEFAULT)"),
+            Ok(QueryResult::RowsAffected(2))
+        );
+        assert_eq!(
+            session.execute("select * from table1"),
+            Ok(QueryResult::Rows(ResultSet {
+                columns: vec!["col1".to_string(), "col2".to_string(), "col3".to_string()],
+                rows: vec![
+                    vec![int(1), text("a"), Value::Null],
+                    vec![Value::Null, Value::Null, Value::Null],
+                ],
+            }))
+        );
+    }
+
+    #[test]
+    fn insert_select() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (2, 'b'), (3, 'c')")
+            .unwrap();
+
+        assert_eq!(
+            session.execute(
+                "insert into table1 (col1, col2, col3) select col4, col5, col4 * 2 from table2 where col4 > 1"
+            ),
+            Ok(QueryResult::RowsAffected(2))
+        );
+        assert_eq!(
+            query(&mut session, "select col1, col2, col3 from table1"),
+            vec![
+                vec![int(2), text("b"), Value::Float(4.0)],
+                vec![int(3), text("c"), Value::Float(6.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn insert_select_from_same_table_reads_snapshot() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (2, 'b')")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("insert into table2 select col4 + 10, col5 from table2"),
+            Ok(QueryResult::RowsAffected(2))
+        );
+        assert_eq!(
+            query(&mut session, "select col4 from table2"),
+            vec![vec![int(1)], vec![int(2)], vec![int(11)], vec![int(12)]]
+        );
+    }
+
+    #[test]
+    fn failed_insert_select_leaves_no_rows() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (0, 'b')")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("insert into table1 (col1) select 10 / col4 from table2"),
+            Err(ExecutionError::DivisionByZero)
+        );
+        assert_eq!(
+            query(&mut session, "select col1 from table1"),
+            Vec::<Vec<Value>>::new()
+        );
+    }
+
+    #[test]
+    fn integer_overflow() {
+        let mut session = session();
+        session
+            .execute("insert into table1 (col1) values (1), (-1)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("select col1 + 9223372036854775807 from table1"),
+            Err(ExecutionError::NumericOverflow)
+        );
+        assert_eq!(
+            session.execute("select col1 - 9223372036854775807 - 2 from table1"),
+            Err(ExecutionError::NumericOverflow)
+        );
+        assert_eq!(
+            session.execute("select col1 * 4611686018427387904 * 2 from table1"),
+            Err(ExecutionError::NumericOverflow)
+        );
+        assert_eq!(
+            session.execute("select (0 - 9223372036854775807 - 1) / col1 from table1"),
+            Err(ExecutionError::NumericOverflow)
+        );
+        assert_eq!(
+            query(
+                &mut session,
+                "select col1 + 9223372036854775806 from table1 where col1 = 1"
+            ),
+            vec![vec![int(i64::MAX)]]
+        );
+    }
+
+    #[test]
+    fn select_without_from() {
+        let mut session = session();
+
+        assert_eq!(
+            query(&mut session, "select 1, 'a'"),
+            vec![vec![int(1), text("a")]]
+        );
+        assert_eq!(
+            session.execute("select 5 / 0"),
+            Err(ExecutionError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn insert_returning() {
+        let mut session = session();
+
+        assert_eq!(
+            session.execute(
+                "insert into table1 (col1, col2) values (1, 'a'), (2, 'b') returning col1 * 10, *"
+            ),
+            Ok(QueryResult::Rows(ResultSet {
+                columns: vec![
+                    "?column?".to_string(),
+                    "col1".to_string(),
+                    "col2".to_string(),
+                    "col3".to_string()
+                ],
+                rows: vec![
+                    vec![int(10), int(1), text("a"), Value::Null],
+                    vec![int(20), int(2), text("b"), Value::Null],
+                ],
+            }))
+        );
+    }
+
+    #[test]
+    fn update() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (2, 'b'), (3, 'c')")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("update table2 set col4 = col4 * 10, col5 = 'x' where col4 >= 2"),
+            Ok(QueryResult::RowsAffected(2))
+        );
+        assert_eq!(
+            query(&mut session, "select * from table2"),
+            vec![
+                vec![int(1), text("a")],
+                vec![int(20), text("x")],
+                vec![int(30), text("x")],
+            ]
+        );
+    }
+
+    #[test]
+    fn update_returning_new_rows() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (2, 'b')")
+            .unwrap();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "update table2 set col4 = col4 + 1 where col5 = 'b' returning col4"
+            ),
+            vec![vec![int(3)]]
+        );
+    }
+
+    #[test]
+    fn delete() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (2, 'b'), (3, 'c')")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("delete from table2 where col4 <> 2"),
+            Ok(QueryResult::RowsAffected(2))
+        );
+        assert_eq!(
+            query(&mut session, "select * from table2"),
+            vec![vec![int(2), text("b")]]
+        );
+    }
+
+    #[test]
+    fn delete_returning_old_rows() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (2, 'b')")
+            .unwrap();
+
+        assert_eq!(
+            query(&mut session, "delete from table2 returning col5, col4"),
+            vec![vec![text("a"), int(1)], vec![text("b"), int(2)]]
+        );
+        assert_eq!(
+            query(&mut session, "select * from table2"),
+            Vec::<Vec<Value>>::new()
+        );
+    }
+
+    fn keyed_session() -> Session {
+        let mut session = Session::new();
+
+        session
+            .execute("create table counters (name text primary key, hits int, tag text unique)")
+            .unwrap();
+        session
+            .execute("insert into counters values ('a', 1, 'x'), ('b', 1, 'y')")
+            .unwrap();
+
+        session
+    }
+
+    #[test]
+    fn insert_duplicate_key_fails_atomically() {
+        let mut session = keyed_session();
+
+        assert_eq!(
+            session.execute("insert into counters values ('c', 1, 'z'), ('a', 2, 'w')"),
+            Err(ExecutionError::UniqueViolation("counters".to_string()))
+        );
+        assert_eq!(query(&mut session, "select name from counters").len(), 2);
+    }
+
+    #[test]
+    fn update_to_duplicate_key_fails() {
+        let mut session = keyed_session();
+
+        assert_eq!(
+            session.execute("update counters set tag = 'x' where name = 'b'"),
+            Err(ExecutionError::UniqueViolation("counters".to_string()))
+        );
+    }
+
+    #[test]
+    fn null_keys_do_not_conflict() {
+        let mut session = keyed_session();
+
+        assert_eq!(
+            session.execute("insert into counters (name) values ('c'), ('d')"),
+            Ok(QueryResult::RowsAffected(2))
+        );
+    }
+
+    #[test]
+    fn on_conflict_do_nothing() {
+        let mut session = keyed_session();
+
+        assert_eq!(
+            session.execute("insert into counters values ('a', 5, 'z'), ('c', 5, 'x'), ('d', 5, 'w') on conflict do nothing"),
+            Ok(QueryResult::RowsAffected(1))
+        );
+        assert_eq!(
+            query(&mut session, "select name, hits from counters"),
+            vec![
+                vec![text("a"), int(1)],
+                vec![text("b"), int(1)],
+                vec![text("d"), int(5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn on_conflict_with_target_only_handles_that_key() {
+        let mut session = keyed_session();
+
+        assert_eq!(
+            session
+                .execute("insert into counters values ('c', 5, 'x') on conflict (name) do nothing"),
+            Err(ExecutionError::UniqueViolation("counters".to_string()))
+        );
+    }
+
+    #[test]
+    fn on_conflict_do_update() {
+        let mut session = keyed_session();
+
+        assert_eq!(
+            session.execute(
+                "insert into counters (name, hits) values ('a', 10), ('c', 10) \
+                 on conflict (name) do update set hits = counters.hits + excluded.hits \
+                 returning name, hits"
+            ),
+            Ok(QueryResult::Rows(ResultSet {
+                columns: vec!["name".to_string(), "hits".to_string()],
+                rows: vec![vec![text("a"), int(11)], vec![text("c"), int(10)]],
+            }))
+        );
+    }
+
+    #[test]
+    fn on_conflict_do_update_where() {
+        let mut session = keyed_session();
+        session
+            .execute("update counters set hits = 5 where name = 'b'")
+            .unwrap();
+
+        assert_eq!(
+            session.execute(
+                "insert into counters (name, hits) values ('a', 3), ('b', 3) \
+                 on conflict (name) do update set hits = excluded.hits where counters.hits < excluded.hits"
+            ),
+            Ok(QueryResult::RowsAffected(1))
+        );
+        assert_eq!(
+            query(&mut session, "select name, hits from counters"),
+            vec![vec![text("a"), int(3)], vec![text("b"), int(5)]]
+        );
+    }
+
+    #[test]
+    fn on_conflict_do_update_same_row_twice_fails() {
+        let mut session = keyed_session();
+
+        assert_eq!(
+            session.execute(
+                "insert into counters (name, hits) values ('a', 1), ('a', 2) \
+                 on conflict (name) do update set hits = excluded.hits"
+            ),
+            Err(ExecutionError::CardinalityViolation)
+        );
+        assert_eq!(
+            query(&mut session, "select hits from counters where name = 'a'"),
+            vec![vec![int(1)]]
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let mut session = keyed_session();
+        session
+            .execute("create table changes (name text, hits int)")
+            .unwrap();
+        session
+            .execute("insert into changes values ('a', 0), ('b', 7), ('c', 3)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute(
+                "merge into counters using changes on counters.name = changes.name \
+                 when matched and changes.hits = 0 then delete \
+                 when matched then update set hits = counters.hits + changes.hits \
+                 when not matched then insert (name, hits) values (changes.name, changes.hits)"
+            ),
+            Ok(QueryResult::Merged(MergeResult {
+                inserted: 1,
+                updated: 1,
+                deleted: 1,
+            }))
+        );
+        assert_eq!(
+            query(&mut session, "select name, hits from counters"),
+            vec![vec![text("b"), int(8)], vec![text("c"), int(3)]]
+        );
+    }
+
+    #[test]
+    fn merge_from_view() {
+        let mut session = keyed_session();
+        session
+            .execute("create table changes (name text, hits int)")
+            .unwrap();
+        session
+            .execute("insert into changes values ('a', 0), ('b', 7), ('c', 3)")
+            .unwrap();
+        session
+            .execute("create view hits as select name, hits from changes where hits > 0")
+            .unwrap();
+
+        assert_eq!(
+            session.execute(
+                "merge into counters using hits on counters.name = hits.name \
+                 when matched then update set hits = counters.hits + hits.hits \
+                 when not matched then insert (name, hits) values (hits.name, hits.hits)"
+            ),
+            Ok(QueryResult::Merged(MergeResult {
+                inserted: 1,
+                updated: 1,
+                deleted: 0,
+            }))
+        );
+        assert_eq!(
+            query(&mut session, "select name, hits from counters"),
+            vec![
+                vec![text("a"), int(1)],
+                vec![text("b"), int(8)],
+                vec![text("c"), int(3)]
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_with_duplicate_source_rows_fails() {
+        let mut session = keyed_session();
+        session
+            .execute("create table changes (name text, hits int)")
+            .unwrap();
+        session
+            .execute("insert into changes values ('a', 1), ('a', 2)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute(
+                "merge into counters using changes on counters.name = changes.name \
+                 when matched then update set hits = changes.hits"
+            ),
+            Err(ExecutionError::CardinalityViolation)
+        );
+        assert_eq!(
+            query(&mut session, "select hits from counters where name = 'a'"),
+            vec![vec![int(1)]]
+        );
+    }
+
+    #[test]
+    fn union() {
+        let mut session = session();
+        session
+            .execute("insert into table1 (col1) values (1), (2)")
+            .unwrap();
+        session
+            .execute("insert into table2 (col4) values (2), (3), (3)")
+            .unwrap();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "select col1 from table1 union select col4 from table2"
+            ),
+            vec![vec![int(1)], vec![int(2)], vec![int(3)]]
+        );
+        assert_eq!(
+            query(
+                &mut session,
+                "select col1 from table1 union all select col4 from table2"
+            )
+            .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn with() {
+        let mut session = session();
+        session
+            .execute("insert into table1 values (1, 'a', 1.0), (2, 'b', 2.0), (3, 'c', 3.0)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute(
+                "with big (id, label) as (select col1, col2 from table1 where col1 > 1), \
+                 biggest as (select id from big where id > 2) \
+                 select label from big, biggest where big.id = biggest.id"
+            ),
+            Ok(QueryResult::Rows(ResultSet {
+                columns: vec!["label".to_string()],
+                rows: vec![vec![text("c")]],
+            }))
+        );
+    }
+
+    #[test]
+    fn with_recursive_counts_to_fixpoint() {
+        let mut session = Session::new();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "with recursive t (n) as (select 1 union all select n + 1 from t where n < 5) \
+                 select n from t"
+            ),
+            vec![
+                vec![int(1)],
+                vec![int(2)],
+                vec![int(3)],
+                vec![int(4)],
+                vec![int(5)]
+            ]
+        );
+    }
+
+    #[test]
+    fn with_recursive_org_chart() {
+        let mut session = Session::new();
+        session
+            .execute("create table employees (id int, manager int, name text)")
+            .unwrap();
+        session
+            .execute(
+                "insert into employees values (1, NULL, 'ceo'), (2, 1, 'cto'), (3, 2, 'dev'), (4, 1, 'cfo')",
+            )
+            .unwrap();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "with recursive chain (id, manager, name) as ( \
+                   select id, manager, name from employees where id = 3 \
+                   union all \
+                   select employees.id, employees.manager, employees.name from employees, chain \
+                   where employees.id = chain.manager \
+                 ) select name from chain"
+            ),
+            vec![vec![text("dev")], vec![text("cto")], vec![text("ceo")]]
+        );
+    }
+
+    #[test]
+    fn with_recursive_union_stops_on_cycles() {
+        let mut session = Session::new();
+        session
+            .execute("create table edges (src int, dst int)")
+            .unwrap();
+        session
+            .execute("insert into edges values (1, 2), (2, 3), (3, 1), (4, 5)")
+            .unwrap();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "with recursive reach (node) as ( \
+                   select 1 union select edges.dst from edges, reach where edges.src = reach.node \
+                 ) select node from reach"
+            ),
+            vec![vec![int(1)], vec![int(2)], vec![int(3)]]
+        );
+    }
+
+    fn staff_session() -> Session {
+        let mut session = Session::new();
+
+        session
+            .execute("create table staff (dept text, name text, salary int)")
+            .unwrap();
+        session
+            .execute(
+                "insert into staff values ('b', 'x', 10), ('a', 'p', 30), ('a', 'q', 20), \
+                 ('a', 'r', 30), ('b', 'y', NULL), ('a', 's', 10)",
+            )
+            .unwrap();
+
+        session
+    }
+
+    #[test]
+    fn window_ranking() {
+        let mut session = staff_session();
+
+        assert_eq!(
+            session.execute(
+                "select name, \
+                 row_number() over (partition by dept order by salary desc), \
+                 rank() over (partition by dept order by salary desc), \
+                 dense_rank() over (partition by dept order by salary desc) \
+                 from staff"
+            ),
+            Ok(QueryResult::Rows(ResultSet {
+                columns: vec![
+                    "name".to_string(),
+                    "row_number".to_string(),
+                    "rank".to_string(),
+                    "dense_rank".to_string(),
+                ],
+                rows: vec![
+                    vec![text("p"), int(1), int(1), int(1)],
+                    vec![text("r"), int(2), int(1), int(1)],
+                    vec![text("q"), int(3), int(3), int(2)],
+                    vec![text("s"), int(4), int(4), int(3)],
+                    vec![text("y"), int(1), int(1), int(1)],
+                    vec![text("x"), int(2), int(2), int(2)],
+                ],
+            }))
+        );
+    }
+
+    #[test]
+    fn window_lag_lead_first_value() {
+        let mut session = staff_session();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "select name, \
+                 lag(name) over (partition by dept order by name), \
+                 lead(salary, 2, 0) over (partition by dept order by name), \
+                 first_value(name) over (partition by dept order by name rows between 1 preceding and current row) \
+                 from staff where dept = 'a'"
+            ),
+            vec![
+                vec![text("p"), Value::Null, int(30), text("p")],
+                vec![text("q"), text("p"), int(10), text("p")],
+                vec![text("r"), text("q"), int(0), text("q")],
+                vec![text("s"), text("r"), int(0), text("r")],
+            ]
+        );
+    }
+
+    #[test]
+    fn window_running_sum() {
+        let mut session = staff_session();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "select name, \
+                 sum(salary) over (order by salary), \
+                 sum(salary) over (order by name rows between 1 preceding and 1 following), \
+                 sum(salary) over (partition by dept) \
+                 from staff"
+            ),
+            vec![
+                vec![text("p"), int(100), int(50), int(90)],
+                vec![text("q"), int(40), int(80), int(90)],
+                vec![text("r"), int(100), int(60), int(90)],
+                vec![text("s"), int(20), int(50), int(90)],
+                vec![text("x"), int(20), int(20), int(10)],
+                vec![text("y"), int(100), int(10), int(10)],
+            ]
+        );
+    }
+
+    #[test]
+    fn views() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (2, 'b'), (3, 'c')")
+            .unwrap();
+        session
+            .execute("create view big (n, name) as select col4, col5 from table2 where col4 > 1")
+            .unwrap();
+        session
+            .execute("create view names as select name from big")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("select * from names"),
+            Ok(QueryResult::Rows(ResultSet {
+                columns: vec!["name".to_string()],
+                rows: vec![vec![text("b")], vec![text("c")]],
+            }))
+        );
+
+        session
+            .execute("insert into table2 values (4, 'd')")
+            .unwrap();
+
+        assert_eq!(
+            query(&mut session, "select big.n from big where name = 'd'"),
+            vec![vec![int(4)]]
+        );
+    }
+
+    #[test]
+    fn replace_view() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (2, 'b')")
+            .unwrap();
+        session
+            .execute("create view v as select col4 from table2")
+            .unwrap();
+        session
+            .execute("create or replace view v as select col4, col5 from table2 where col4 = 2")
+            .unwrap();
+
+        assert_eq!(
+            query(&mut session, "select * from v"),
+            vec![vec![int(2), text("b")]]
+        );
+    }
+
+    #[test]
+    fn drop_table_cascade_drops_views() {
+        let mut session = session();
+        session
+            .execute("create view v1 as select col4 from table2")
+            .unwrap();
+        session
+            .execute("create view v2 as select col4 from v1")
+            .unwrap();
+
+        session.execute("drop view v2").unwrap();
+        session
+            .execute("create view v2 as select col4 from v1")
+            .unwrap();
+        session.execute("drop table table2 cascade").unwrap();
+
+        session.execute("create table v1 (a int)").unwrap();
+        session
+            .execute("create view v2 as select a from v1")
+            .unwrap();
+    }
+
+    #[test]
+    fn drop_view_with_dependents() {
+        let mut session = session();
+        session
+            .execute("create view v1 as select col4 from table2")
+            .unwrap();
+        session
+            .execute("create view v2 as select col4 from v1")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("drop view v1"),
+            Err(ExecutionError::Analyze(AnalyzeError {
+                kind: AnalyzeErrorKind::Invalid(
+                    "Cannot drop v1 because other objects depend on it: v2".to_string()
+                ),
+                span: Some(Span { start: 0, end: 12 }),
+            }))
+        );
+    }
+
+    #[test]
+    fn rerunnable_migration() {
+        let mut session = session();
+        let migration = [
+            "drop table if exists orders, customers",
+            "create table if not exists customers (id int primary key, name text)",
+            "create table if not exists orders (id int, customer int references customers)",
+            "insert into customers values (1, 'a')",
+        ];
+
+        for _ in 0..2 {
+            for statement in &migration {
+                session.execute(statement).unwrap();
+            }
+        }
+
+        assert_eq!(
+            query(&mut session, "select name from customers"),
+            vec![vec![text("a")]]
+        );
+    }
+
+    #[test]
+    fn drop_referenced_table() {
+        let mut session = session();
+        session
+            .execute("create table customers (id int primary key)")
+            .unwrap();
+        session
+            .execute("create table orders (customer int references customers)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("drop table customers"),
+            Err(ExecutionError::Analyze(AnalyzeError {
+                kind: AnalyzeErrorKind::Invalid(
+                    "Cannot drop customers because other objects depend on it: orders".to_string()
+                ),
+                span: Some(Span { start: 0, end: 20 }),
+            }))
+        );
+    }
+
+    #[test]
+    fn drop_referenced_table_cascade() {
+        let mut session = session();
+        session
+            .execute("create table customers (id int primary key)")
+            .unwrap();
+        session
+            .execute("create table orders (customer int references customers)")
+            .unwrap();
+        session
+            .execute("create view customer_ids as select id from customers")
+            .unwrap();
+
+        session.execute("drop table customers cascade").unwrap();
+
+        session.execute("drop table orders").unwrap();
+        session
+            .execute("create table customer_ids (id int)")
+            .unwrap();
+    }
+
+    #[test]
+    fn create_table_as_select() {
+        let mut session = session();
+        session
+            .execute("insert into table1 values (1, 'a', 1.5), (2, 'b', 2.5)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("create table copy as select col1, col3 from table1 where col1 > 1"),
+            Ok(QueryResult::RowsAffected(1))
+        );
+        assert_eq!(
+            session.execute("insert into copy values (3, 3)"),
+            Ok(QueryResult::RowsAffected(1))
+        );
+        assert_eq!(
+            session.execute("select * from copy"),
+            Ok(QueryResult::Rows(ResultSet {
+                columns: vec!["col1".to_string(), "col3".to_string()],
+                rows: vec![
+                    vec![int(2), Value::Float(2.5)],
+                    vec![int(3), Value::Float(3.0)],
+                ],
+            }))
+        );
+    }
+
+    #[test]
+    fn transaction_commit_and_rollback() {
+        let mut session = session();
+
+        session.execute("begin").unwrap();
+        session
+            .execute("insert into table2 values (1, 'a')")
+            .unwrap();
+        session.execute("commit").unwrap();
+
+        session
+            .execute("begin transaction isolation level serializable")
+            .unwrap();
+        assert!(session.in_transaction());
+        session
+            .execute("insert into table2 values (2, 'b')")
+            .unwrap();
+        session.execute("drop table table1").unwrap();
+        session.execute("rollback").unwrap();
+
+        assert!(!session.in_transaction());
+        assert_eq!(
+            query(&mut session, "select * from table2"),
+            vec![vec![int(1), text("a")]]
+        );
+        assert_eq!(
+            query(&mut session, "select col1 from table1"),
+            Vec::<Vec<Value>>::new()
+        );
+    }
+
+    #[test]
+    fn transaction_savepoints() {
+        let mut session = session();
+
+        session.execute("begin").unwrap();
+        session
+            .execute("insert into table2 values (1, 'a')")
+            .unwrap();
+        session.execute("savepoint s1").unwrap();
+        session
+            .execute("insert into table2 values (2, 'b')")
+            .unwrap();
+        session.execute("savepoint s2").unwrap();
+        session
+            .execute("insert into table2 values (3, 'c')")
+            .unwrap();
+        session.execute("rollback to savepoint s1").unwrap();
+
+        assert_eq!(
+            session.execute("release s2"),
+            Err(ExecutionError::Transaction(
+                TransactionError::UnknownSavepoint("s2".to_string())
+            ))
+        );
+
+        session
+            .execute("insert into table2 values (4, 'd')")
+            .unwrap();
+        session.execute("release savepoint s1").unwrap();
+        session.execute("commit").unwrap();
+
+        assert_eq!(
+            query(&mut session, "select col4 from table2"),
+            vec![vec![int(1)], vec![int(4)]]
+        );
+    }
+
+    #[test]
+    fn transaction_errors() {
+        let mut session = session();
+
+        assert_eq!(
+            session.execute("commit"),
+            Err(ExecutionError::Transaction(TransactionError::NoTransaction))
+        );
+
+        session.execute("begin").unwrap();
+
+        assert_eq!(
+            session.execute("begin"),
+            Err(ExecutionError::Transaction(
+                TransactionError::TransactionInProgress
+            ))
+        );
+    }
+
+    #[test]
+    fn explain() {
+        let mut session = session();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "explain select col5 from table2 where col4 = 1"
+            ),
+            vec![
+                vec![text("Projection: col5")],
+                vec![text("  Filter: col4 = 1")],
+                vec![text("    Read: table2")],
+            ]
+        );
+    }
+
+    #[test]
+    fn explain_analyze() {
+        let mut session = session();
+        session
+            .execute("insert into table2 values (1, 'a'), (2, 'b'), (3, 'c')")
+            .unwrap();
+
+        let lines: Vec<String> = query(
+            &mut session,
+            "explain analyze select col5 from table2 where col4 > 1",
+        )
+        .into_iter()
+        .map(|row| match &row[0] {
+            Value::String(line) => line.split(" time=").next().unwrap().to_string(),
+            value => panic!("Unexpected value: {:?}", value),
+        })
+        .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "Projection: col5  (rows=2 loops=1",
+                "  Filter: col4 > 1  (rows=2 loops=1",
+                "    Read: table2  (rows=3 loops=1",
+            ]
+        );
+
+        let lines = query(
+            &mut session,
+            "explain analyze insert into table2 select col4 + 10, col5 from table2",
+        );
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(query(&mut session, "select * from table2").len(), 6);
+    }
+
+    #[test]
+    fn prepared_statements() {
+        let mut session = session();
+
+        let mut insert = session
+            .prepare("insert into table1 values (?, ?, ?)")
+            .unwrap();
+        assert_eq!(
+            session.execute_prepared(&mut insert, &[int(0), text("x"), text("y")]),
+            Err(ExecutionError::TypeMismatch(
+                "parameter $3: expected Float, got String".to_string()
+            ))
+        );
+
+        for i in 0..3 {
+            session
+                .execute_prepared(&mut insert, &[int(i), text("x"), int(i)])
+                .unwrap();
+        }
+
+        let mut select = session
+            .prepare("select col1, col3 from table1 where col1 >= :min and col2 = :name")
+            .unwrap();
+
+        assert_eq!(
+            session.execute_named(&mut select, &[("name", text("x")), ("min", int(1))]),
+            Ok(QueryResult::Rows(ResultSet {
+                columns: vec!["col1".to_string(), "col3".to_string()],
+                rows: vec![
+                    vec![int(1), Value::Float(1.0)],
+                    vec![int(2), Value::Float(2.0)],
+                ],
+            }))
+        );
+        assert_eq!(
+            session.execute_named(&mut select, &[("min", int(1))]),
+            Err(ExecutionError::UnboundParameter("name".to_string()))
+        );
+        assert_eq!(
+            session.execute_prepared(&mut select, &[int(1)]),
+            Err(ExecutionError::ParameterCount {
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn prepared_statement_is_planned_again_after_schema_change() {
+        let mut session = session();
+        let mut select = session
+            .prepare("select * from table2 where col4 = ?")
+            .unwrap();
+
+        session.execute("drop table table2").unwrap();
+        session
+            .execute("create table table2 (col4 int, col6 boolean)")
+            .unwrap();
+        session
+            .execute("insert into table2 values (1, true)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute_prepared(&mut select, &[int(1)]),
+            Ok(QueryResult::Rows(ResultSet {
+                columns: vec!["col4".to_string(), "col6".to_string()],
+                rows: vec![vec![int(1), Value::Bool(true)]],
+            }))
+        );
+
+        session.execute("drop table table2").unwrap();
+
+        assert_eq!(
+            session.execute_prepared(&mut select, &[int(1)]),
+            Err(ExecutionError::Analyze(AnalyzeError {
+                kind: AnalyzeErrorKind::UnknownTable("table2".to_string()),
+                span: Some(Span { start: 14, end: 20 }),
+            }))
+        );
+    }
+
+    #[test]
+    fn analyze_errors_point_at_the_sql() {
+        let mut session = session();
+
+        let error = |result: Result<QueryResult, ExecutionError>| match result {
+            Err(ExecutionError::Analyze(error)) => error,
+            result => panic!("Unexpected result: {:?}", result),
+        };
+
+        let analyze_error = error(session.execute("select col1,\n  col9 + 1 from table1"));
+        assert_eq!(
+            analyze_error.kind,
+            AnalyzeErrorKind::UnknownColumn("col9".to_string())
+        );
+        assert_eq!(analyze_error.span, Some(Span { start: 15, end: 19 }));
+        assert_eq!(analyze_error.to_string(), "Unknown column: col9 at 15..19");
+
+        let analyze_error =
+            error(session.execute("update table1 set col3 = 1.5 where col1 = 1 and col2 + 1 = 2"));
+        assert_eq!(
+            analyze_error.kind,
+            AnalyzeErrorKind::TypeMismatch("Some(String) Plus Some(Int)".to_string())
+        );
+        assert_eq!(analyze_error.span, Some(Span { start: 48, end: 56 }));
+    }
+
+    #[test]
+    fn syntax_errors_are_returned() {
+        let mut session = session();
+
+        assert_eq!(
+            session.execute("select from table1"),
+            Err(ExecutionError::Parse(ParseError {
+                kind: ParseErrorKind::UnexpectedToken("from".to_string()),
+                span: Span { start: 7, end: 11 },
+            }))
+        );
+        assert_eq!(
+            session.execute("insert into table1 values (1,"),
+            Err(ExecutionError::Parse(ParseError {
+                kind: ParseErrorKind::UnexpectedEnd,
+                span: Span { start: 29, end: 29 },
+            }))
+        );
+    }
+
+    #[test]
+    fn statement_with_parameters_needs_values() {
+        let mut session = session();
+
+        assert_eq!(
+            session.execute("select col4 from table2 where col4 = ?"),
+            Err(ExecutionError::ParameterCount {
+                expected: 1,
+                actual: 0
+            })
+        );
+    }
+
+    #[test]
+    fn quoted_strings_and_names() {
+        let mut session = session();
+
+        session
+            .execute(r#"create table "Order Items" ("the item" text)"#)
+            .unwrap();
+        session
+            .execute(r#"insert into "Order Items" values ('it''s'), (E'a\tb')"#)
+            .unwrap();
+
+        assert_eq!(
+            query(&mut session, r#"select "the item" from "Order Items""#),
+            vec![vec![text("it's")], vec![text("a\tb")]]
+        );
+    }
+
+    #[test]
+    fn keywords_as_names() {
+        let mut session = session();
+
+        session
+            .execute("create table t (level int, rows int)")
+            .unwrap();
+        session
+            .execute("insert into t (level, rows) values (1, 2)")
+            .unwrap();
+
+        assert_eq!(
+            query(&mut session, "select rows, level from t where level = 1"),
+            vec![vec![int(2), int(1)]]
+        );
+    }
+
+    #[test]
+    fn identifier_case() {
+        let mut session = session();
+
+        session.execute("create table Orders (Id int)").unwrap();
+        session
+            .execute(r#"create table "Orders" ("Id" int, "select" int)"#)
+            .unwrap();
+        session.execute("insert into ORDERS values (1)").unwrap();
+        session
+            .execute(r#"insert into "Orders" values (2, 3)"#)
+            .unwrap();
+
+        assert_eq!(
+            query(&mut session, "select orders.ID from orders"),
+            vec![vec![int(1)]]
+        );
+        assert_eq!(
+            query(
+                &mut session,
+                r#"select "Orders"."Id", "select" from "Orders""#
+            ),
+            vec![vec![int(2), int(3)]]
+        );
+    }
+
+    #[test]
+    fn numeric_literals() {
+        let mut session = session();
+
+        session
+            .execute("insert into table1 values (9000000000, 'a', 2.5e1), (0x10, 'b', .5)")
+            .unwrap();
+
+        assert_eq!(
+            query(&mut session, "select col1, col3 from table1"),
+            vec![
+                vec![int(9_000_000_000), Value::Float(25.0)],
+                vec![int(16), Value::Float(0.5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn dates_and_intervals() {
+        let mut session = session();
+
+        session
+            .execute("create table events (id int, day date, at timestamp, length interval)")
+            .unwrap();
+        session
+            .execute(
+                "insert into events values \
+                 (1, date '2024-01-31', timestamp '2024-01-31 12:00', interval '90 minutes'), \
+                 (2, date '2024-03-01', date '2024-03-01', interval '1 day')",
+            )
+            .unwrap();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "select day + 1, day - date '2024-01-01', at - interval '1 month', at + length \
+                 from events where at < timestamp '2024-02-01'"
+            ),
+            vec![vec![
+                Value::Date(Date::parse("2024-02-01").unwrap()),
+                int(30),
+                Value::Timestamp(Timestamp::parse("2023-12-31 12:00").unwrap()),
+                Value::Timestamp(Timestamp::parse("2024-01-31 13:30").unwrap()),
+            ]]
+        );
+        assert_eq!(
+            query(
+                &mut session,
+                "select id from events where length > interval '2 hours'"
+            ),
+            vec![vec![int(2)]]
+        );
+        assert_eq!(
+            session.execute("select day - 3000000 from events"),
+            Err(ExecutionError::Temporal(TemporalError::OutOfRange))
+        );
+        assert_eq!(
+            session.execute("select day + 9223372036854775807 from events"),
+            Err(ExecutionError::Temporal(TemporalError::OutOfRange))
+        );
+        assert_eq!(
+            session.execute("select day - (0 - 9223372036854775807 - 1) from events"),
+            Err(ExecutionError::Temporal(TemporalError::OutOfRange))
+        );
+    }
+
+    #[test]
+    fn invalid_temporal_literal() {
+        let mut session = session();
+
+        assert_eq!(
+            session.execute("select date '2023-02-29'"),
+            Err(ExecutionError::Parse(ParseError {
+                kind: ParseErrorKind::Temporal(TemporalError::InvalidDate(
+                    "2023-02-29".to_string()
+                )),
+                span: Span { start: 12, end: 24 },
+            }))
+        );
+    }
+
+    #[test]
+    fn exact_numeric_literals() {
+        let mut session = session();
+        let decimal = |mantissa, scale| Value::Decimal(Decimal { mantissa, scale });
+
+        assert_eq!(
+            query(
+                &mut session,
+                "select 0.1 + 0.2, 0.1 + 0.2 = 0.3, 1.5 * 2, 2 / 3.0, 7.5 % 2, - 2.50, 0.5 + 1e0"
+            ),
+            vec![vec![
+                decimal(3, 1),
+                Value::Bool(true),
+                decimal(30, 1),
+                decimal(666667, 6),
+                decimal(15, 1),
+                decimal(-250, 2),
+                Value::Float(1.5),
+            ]]
+        );
+        assert_eq!(
+            query(
+                &mut session,
+                "select 99999999999999999999 + 1, 0xFFFFFFFFFFFFFFFFFF, 9223372036854775807 + 1.0"
+            ),
+            vec![vec![
+                decimal(100000000000000000000, 0),
+                decimal(0xFFFFFFFFFFFFFFFFFF, 0),
+                decimal(92233720368547758080, 1),
+            ]]
+        );
+        assert_eq!(
+            session.execute("select 170141183460469231731687303715884105727 + 1"),
+            Err(ExecutionError::NumericOverflow)
+        );
+        assert_eq!(
+            session.execute("select 1.5 / 0"),
+            Err(ExecutionError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn decimal_columns() {
+        let mut session = session();
+
+        session
+            .execute("create table prices (price decimal, amount float)")
+            .unwrap();
+        session
+            .execute("insert into prices values (1.10, 1.10), (2, 2)")
+            .unwrap();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "select price * 3, amount, price || '' from prices where price > 1.5"
+            ),
+            vec![vec![
+                Value::Decimal(Decimal {
+                    mantissa: 6,
+                    scale: 0
+                }),
+                Value::Float(2.0),
+                text("2"),
+            ]]
+        );
+        assert_eq!(
+            query(
+                &mut session,
+                "select sum(price) over () from prices where price < 2"
+            ),
+            vec![vec![Value::Decimal(Decimal {
+                mantissa: 110,
+                scale: 2
+            })]]
+        );
+    }
+
+    #[test]
+    fn unary_modulo_and_concat() {
+        let mut session = session();
+
+        session
+            .execute("insert into table1 values (7, 'a', 2.5), (-7, NULL, NULL)")
+            .unwrap();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "select - col1, col1 % 3, - col3, col3 % 1, col2 || '-' || col1 from table1"
+            ),
+            vec![
+                vec![
+                    int(-7),
+                    int(1),
+                    Value::Float(-2.5),
+                    Value::Float(0.5),
+                    text("a-7")
+                ],
+                vec![int(7), int(-1), Value::Null, Value::Null, Value::Null],
+            ]
+        );
+        assert_eq!(
+            session.execute("select col1 % 0 from table1"),
+            Err(ExecutionError::DivisionByZero)
+        );
+
+        session
+            .execute("insert into table1 (col1) values (0 - 9223372036854775807 - 1)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("select - col1 from table1"),
+            Err(ExecutionError::NumericOverflow)
+        );
+        assert_eq!(
+            session.execute("select col1 % -1 from table1"),
+            Err(ExecutionError::NumericOverflow)
+        );
+    }
+
+    #[test]
+    fn statements_without_whitespace() {
+        let mut session = session();
+
+        session
+            .execute("insert into table1(col1,col2)values(1,'a'),(2,'b')")
+            .unwrap();
+
+        assert_eq!(
+            query(
+                &mut session,
+                "select col1-1,-col1,col2||'!'from table1 where col1>=2"
+            ),
+            vec![vec![int(1), int(-2), text("b!")]]
+        );
+    }
+
+    #[test]
+    fn column_defaults_and_not_null() {
+        let mut session = Session::new();
+
+        session
+            .execute("create table items (id int primary key, name text not null default 'item ' || 'x', qty int default -1, note text)")
+            .unwrap();
+        session
+            .execute("insert into items (id) values (1)")
+            .unwrap();
+        session
+            .execute("insert into items values (2, DEFAULT, 5, DEFAULT)")
+            .unwrap();
+        session
+            .execute("create table new_items (new_id int)")
+            .unwrap();
+        session.execute("insert into new_items values (3)").unwrap();
+        session
+            .execute(
+                "merge into items using new_items on id = new_id \
+                 when not matched then insert (id, qty) values (new_id, DEFAULT)",
+            )
+            .unwrap();
+
+        assert_eq!(
+            query(&mut session, "select * from items"),
+            vec![
+                vec![int(1), text("item x"), int(-1), Value::Null],
+                vec![int(2), text("item x"), int(5), Value::Null],
+                vec![int(3), text("item x"), int(-1), Value::Null],
+            ]
+        );
+        assert_eq!(
+            session.execute("insert into items (id, name) values (3, NULL)"),
+            Err(ExecutionError::NotNullViolation("name".to_string()))
+        );
+        assert_eq!(
+            session.execute("insert into items (name) values ('a')"),
+            Err(ExecutionError::NotNullViolation("id".to_string()))
+        );
+        assert_eq!(
+            session.execute("update items set name = NULL where id = 1"),
+            Err(ExecutionError::NotNullViolation("name".to_string()))
+        );
+    }
+
+    #[test]
+    fn unique_indexes() {
+        let mut session = session();
+
+        session
+            .execute("insert into table1 values (1, 'a', 1.0), (2, 'a', 2.0)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("create unique index table1_col2 on table1 (col2)"),
+            Err(ExecutionError::UniqueViolation("table1".to_string()))
+        );
+
+        session
+            .execute("create unique index table1_col1 on table1 (col1)")
+            .unwrap();
+        session
+            .execute("create index if not exists table1_col1 on table1 (col2)")
+            .unwrap();
+
+        assert_eq!(
+            session.execute("insert into table1 values (1, 'b', 3.0)"),
+            Err(ExecutionError::UniqueViolation("table1".to_string()))
+        );
+        assert_eq!(
+            session
+                .execute("insert into table1 values (1, 'b', 3.0) on conflict (col1) do nothing"),
+            Ok(QueryResult::RowsAffected(0))
+        );
+
+        session.execute("drop index table1_col1").unwrap();
+
+        assert_eq!(
+            session.execute("insert into table1 values (1, 'b', 3.0)"),
+            Ok(QueryResult::RowsAffected(1))
+        );
+    }
+}
@@ -0,0 +1,143 @@
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+use std::rc::Rc;
+
+use crate::executor::value::Row;
+
+pub type RowId = u64;
+
+/// In-memory row store.
+///
+/// Every table keeps its rows behind an `Rc`, so cloning the storage or
+/// starting a scan is cheap and a scan keeps seeing the rows as they were
+/// when it started, even if the table is modified while it is consumed.
+#[derive(Debug, Default, Clone)]
+pub struct Storage {
+    tables: HashMap<String, TableData>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct TableData {
+    rows: Rc<BTreeMap<RowId, Row>>,
+    next_row_id: RowId,
+}
+
+impl Storage {
+    pub fn new() -> Self {
+        Storage {
+            tables: HashMap::new(),
+        }
+    }
+
+    pub fn create_table(&mut self, name: &str) {
+        self.tables.insert(name.to_string(), TableData::default());
+    }
+
+    pub fn drop_table(&mut self, name: &str) {
+        self.tables.remove(name);
+    }
+
+    pub fn scan(&self, name: &str) -> Scan {
+        Scan {
+            rows: self.table(name).rows.clone(),
+            last_row_id: None,
+        }
+    }
+
+    pub fn get(&self, name: &str, row_id: RowId) -> Option<&Row> {
+        self.table(name).rows.get(&row_id)
+    }
+
+    pub fn insert(&mut self, name: &str, row: Row) -> RowId {
+        let table = self.table_mut(name);
+        let row_id = table.next_row_id;
+
+        table.next_row_id += 1;
+        Rc::make_mut(&mut table.rows).insert(row_id, row);
+
+        row_id
+    }
+
+    pub fn update(&mut self, name: &str, row_id: RowId, row: Row) {
+        Rc::make_mut(&mut self.table_mut(name).rows).insert(row_id, row);
+    }
+
+    pub fn delete(&mut self, name: &str, row_id: RowId) {
+        Rc::make_mut(&mut self.table_mut(name).rows).remove(&row_id);
+    }
+
+    fn table(&self, name: &str) -> &TableData {
+        match self.tables.get(name) {
+            Some(table) => table,
+            None => panic!("Unknown table: {}", name),
+        }
+    }
+
+    fn table_mut(&mut self, name: &str) -> &mut TableData {
+        match self.tables.get_mut(name) {
+            Some(table) => table,
+            None => panic!("Unknown table: {}", name),
+        }
+    }
+}
+
+/// Iterator over a snapshot of a table's rows in row id order.
+pub struct Scan {
+    rows: Rc<BTreeMap<RowId, Row>>,
+    last_row_id: Option<RowId>,
+}
+
+impl Iterator for Scan {
+    type Item = (RowId, Row);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = match self.last_row_id {
+            Some(row_id) => Bound::Excluded(row_id),
+            None => Bound::Unbounded,
+        };
+
+        let (row_id, row) = self.rows.range((start, Bound::Unbounded)).next()?;
+        self.last_row_id = Some(*row_id);
+
+        Some((*row_id, row.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::executor::value::Value;
+    use crate::storage::Storage;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn scan_sees_snapshot() {
+        let mut storage = Storage::new();
+        storage.create_table("table1");
+        storage.insert("table1", vec![Value::Int(1)]);
+
+        let scan = storage.scan("table1");
+        storage.insert("table1", vec![Value::Int(2)]);
+
+        let actual: Vec<_> = scan.collect();
+
+        assert_eq!(actual, vec![(0, vec![Value::Int(1)])]);
+        assert_eq!(storage.scan("table1").count(), 2);
+    }
+
+    #[test]
+    fn update_and_delete() {
+        let mut storage = Storage::new();
+        storage.create_table("table1");
+        let first = storage.insert("table1", vec![Value::Int(1)]);
+        let second = storage.insert("table1", vec![Value::Int(2)]);
+
+        storage.update("table1", first, vec![Value::Int(10)]);
+        storage.delete("table1", second);
+
+        let actual: Vec<_> = storage.scan("table1").collect();
+
+        assert_eq!(actual, vec![(first, vec![Value::Int(10)])]);
+    }
+}
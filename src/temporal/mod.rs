@@ -0,0 +1,638 @@
+#![allow(dead_code)]
+
+use std::cmp::Ordering;
+use std::fmt;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TemporalError {
+    InvalidDate(String),
+    InvalidTime(String),
+    InvalidTimestamp(String),
+    InvalidInterval(String),
+    /// A result before year 1 or after year 9999.
+    OutOfRange,
+}
+
+impl fmt::Display for TemporalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemporalError::InvalidDate(s) => write!(f, "Invalid date: '{}'", s),
+            TemporalError::InvalidTime(s) => write!(f, "Invalid time: '{}'", s),
+            TemporalError::InvalidTimestamp(s) => write!(f, "Invalid timestamp: '{}'", s),
+            TemporalError::InvalidInterval(s) => write!(f, "Invalid interval: '{}'", s),
+            TemporalError::OutOfRange => write!(f, "Date out of range"),
+        }
+    }
+}
+
+/// A day of the proleptic Gregorian calendar, from 0001-01-01 to
+/// 9999-12-31.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct Date {
+    /// Days since 1970-01-01.
+    days: i32,
+}
+
+impl Date {
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Date> {
+        if !(1..=9999).contains(&year)
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+        {
+            return None;
+        }
+
+        Some(Date {
+            days: days_from_civil(year, month, day),
+        })
+    }
+
+    /// `YYYY-MM-DD`.
+    pub fn parse(s: &str) -> Result<Date, TemporalError> {
+        let invalid = || TemporalError::InvalidDate(s.to_string());
+        let mut parts = s.splitn(3, '-');
+
+        let year = parse_digits(parts.next(), 4, 4).ok_or_else(invalid)?;
+        let month = parse_digits(parts.next(), 1, 2).ok_or_else(invalid)?;
+        let day = parse_digits(parts.next(), 1, 2).ok_or_else(invalid)?;
+
+        Date::from_ymd(year as i32, month as u32, day as u32).ok_or_else(invalid)
+    }
+
+    pub fn ymd(self) -> (i32, u32, u32) {
+        civil_from_days(self.days)
+    }
+
+    /// Days from `other` to this date.
+    pub fn days_since(self, other: Date) -> i64 {
+        self.days as i64 - other.days as i64
+    }
+
+    pub fn add_days(self, days: i64) -> Result<Date, TemporalError> {
+        let days = (self.days as i64)
+            .checked_add(days)
+            .ok_or(TemporalError::OutOfRange)?;
+
+        Date::from_days(days)
+    }
+
+    fn from_days(days: i64) -> Result<Date, TemporalError> {
+        let days = i32::try_from(days).map_err(|_| TemporalError::OutOfRange)?;
+        let (year, _, _) = civil_from_days(days);
+
+        match (1..=9999).contains(&year) {
+            true => Ok(Date { days }),
+            false => Err(TemporalError::OutOfRange),
+        }
+    }
+
+    /// The same day that many months later, or the last day of that month
+    /// if it is shorter.
+    pub fn add_months(self, months: i64) -> Result<Date, TemporalError> {
+        let (year, month, day) = self.ymd();
+        let month = year as i64 * 12 + (month as i64 - 1) + months;
+        let (year, month) = (month.div_euclid(12), month.rem_euclid(12) as u32 + 1);
+
+        if !(1..=9999).contains(&year) {
+            return Err(TemporalError::OutOfRange);
+        }
+
+        let year = year as i32;
+        let day = day.min(days_in_month(year, month));
+
+        Ok(Date {
+            days: days_from_civil(year, month, day),
+        })
+    }
+
+    pub fn to_timestamp(self) -> Timestamp {
+        Timestamp {
+            micros: self.days as i64 * MICROS_PER_DAY,
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// A time of day, to the microsecond.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct Time {
+    /// Microseconds since midnight.
+    micros: i64,
+}
+
+impl Time {
+    /// `HH:MM[:SS[.ffffff]]`.
+    pub fn parse(s: &str) -> Result<Time, TemporalError> {
+        parse_clock(s)
+            .filter(|micros| *micros < MICROS_PER_DAY)
+            .map(|micros| Time { micros })
+            .ok_or_else(|| TemporalError::InvalidTime(s.to_string()))
+    }
+
+    /// Wraps around midnight.
+    pub fn add_interval(self, interval: Interval) -> Time {
+        Time {
+            micros: (self.micros + interval.micros % MICROS_PER_DAY).rem_euclid(MICROS_PER_DAY),
+        }
+    }
+
+    pub fn since(self, other: Time) -> Interval {
+        Interval::from_micros(self.micros - other.micros)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_clock(f, self.micros)
+    }
+}
+
+/// A date and a time of day, without a time zone.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct Timestamp {
+    /// Microseconds since 1970-01-01 00:00:00.
+    micros: i64,
+}
+
+impl Timestamp {
+    /// `YYYY-MM-DD`, optionally followed by a space or `T` and a time.
+    pub fn parse(s: &str) -> Result<Timestamp, TemporalError> {
+        let invalid = || TemporalError::InvalidTimestamp(s.to_string());
+
+        let (date, time) = match s.split_once([' ', 'T']) {
+            Some((date, time)) => (date, Time::parse(time.trim()).map_err(|_| invalid())?),
+            None => (s, Time { micros: 0 }),
+        };
+        let date = Date::parse(date).map_err(|_| invalid())?;
+
+        Ok(Timestamp {
+            micros: date.to_timestamp().micros + time.micros,
+        })
+    }
+
+    pub fn date(self) -> Date {
+        Date {
+            days: self.micros.div_euclid(MICROS_PER_DAY) as i32,
+        }
+    }
+
+    pub fn time(self) -> Time {
+        Time {
+            micros: self.micros.rem_euclid(MICROS_PER_DAY),
+        }
+    }
+
+    /// Adds the months, then the days, then the time of the interval.
+    pub fn add_interval(self, interval: Interval) -> Result<Timestamp, TemporalError> {
+        let date = self
+            .date()
+            .add_months(interval.months as i64)?
+            .add_days(interval.days as i64)?;
+        let micros = date.to_timestamp().micros + self.time().micros + interval.micros;
+
+        Date::from_days(micros.div_euclid(MICROS_PER_DAY))?;
+
+        Ok(Timestamp { micros })
+    }
+
+    pub fn since(self, other: Timestamp) -> Interval {
+        Interval::from_micros(self.micros - other.micros)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.date(), self.time())
+    }
+}
+
+/// A span of time. Months and days are kept apart from the rest, as
+/// their length depends on the date they are added to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub micros: i64,
+}
+
+impl Interval {
+    /// Whole days and the time left over, both with the same sign.
+    pub fn from_micros(micros: i64) -> Interval {
+        Interval {
+            months: 0,
+            days: (micros / MICROS_PER_DAY) as i32,
+            micros: micros % MICROS_PER_DAY,
+        }
+    }
+
+    /// Quantities with units, such as `1 year 2 months 3 days`, and
+    /// optionally a signed `HH:MM[:SS[.ffffff]]`. Units may be singular or
+    /// plural: year, month (mon), week, day, hour, minute (min) and second
+    /// (sec).
+    pub fn parse(s: &str) -> Result<Interval, TemporalError> {
+        let invalid = || TemporalError::InvalidInterval(s.to_string());
+        let mut interval = Interval {
+            months: 0,
+            days: 0,
+            micros: 0,
+        };
+        let mut words = s.split_whitespace().peekable();
+
+        if words.peek().is_none() {
+            return Err(invalid());
+        }
+
+        while let Some(word) = words.next() {
+            if word.contains(':') {
+                let (negative, clock) = match word.strip_prefix('-') {
+                    Some(clock) => (true, clock),
+                    None => (false, word.strip_prefix('+').unwrap_or(word)),
+                };
+                let micros = parse_clock(clock).ok_or_else(invalid)?;
+                let micros = if negative { -micros } else { micros };
+
+                interval.micros = interval.micros.checked_add(micros).ok_or_else(invalid)?;
+                continue;
+            }
+
+            let unit = words.next().ok_or_else(invalid)?.to_lowercase();
+            let unit = unit.strip_suffix('s').unwrap_or(&unit);
+
+            if let Some(per_unit) = match unit {
+                "hour" => Some(MICROS_PER_HOUR),
+                "minute" | "min" => Some(MICROS_PER_MINUTE),
+                "second" | "sec" => Some(MICROS_PER_SECOND),
+                _ => None,
+            } {
+                let quantity: f64 = word.parse().map_err(|_| invalid())?;
+                let micros = (quantity * per_unit as f64).round();
+
+                if !micros.is_finite() || micros.abs() >= i64::MAX as f64 {
+                    return Err(invalid());
+                }
+
+                interval.micros = interval
+                    .micros
+                    .checked_add(micros as i64)
+                    .ok_or_else(invalid)?;
+                continue;
+            }
+
+            let quantity: i32 = word.parse().map_err(|_| invalid())?;
+            let (field, per_unit) = match unit {
+                "year" => (&mut interval.months, 12),
+                "month" | "mon" => (&mut interval.months, 1),
+                "week" => (&mut interval.days, 7),
+                "day" => (&mut interval.days, 1),
+                _ => return Err(invalid()),
+            };
+
+            *field = quantity
+                .checked_mul(per_unit)
+                .and_then(|quantity| field.checked_add(quantity))
+                .ok_or_else(invalid)?;
+        }
+
+        Ok(interval)
+    }
+
+    pub fn negate(self) -> Interval {
+        Interval {
+            months: -self.months,
+            days: -self.days,
+            micros: -self.micros,
+        }
+    }
+
+    pub fn add(self, other: Interval) -> Result<Interval, TemporalError> {
+        Ok(Interval {
+            months: self
+                .months
+                .checked_add(other.months)
+                .ok_or(TemporalError::OutOfRange)?,
+            days: self
+                .days
+                .checked_add(other.days)
+                .ok_or(TemporalError::OutOfRange)?,
+            micros: self
+                .micros
+                .checked_add(other.micros)
+                .ok_or(TemporalError::OutOfRange)?,
+        })
+    }
+
+    /// Compares as if months had 30 days, as PostgreSQL does.
+    pub fn compare(&self, other: &Interval) -> Ordering {
+        self.approximate_micros().cmp(&other.approximate_micros())
+    }
+
+    fn approximate_micros(&self) -> i128 {
+        (self.months as i128 * 30 + self.days as i128) * MICROS_PER_DAY as i128
+            + self.micros as i128
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+
+        for (quantity, unit) in [
+            (self.months / 12, "year"),
+            (self.months % 12, "month"),
+            (self.days, "day"),
+        ] {
+            match quantity {
+                0 => {}
+                1 | -1 => parts.push(format!("{} {}", quantity, unit)),
+                _ => parts.push(format!("{} {}s", quantity, unit)),
+            }
+        }
+
+        if self.micros != 0 || parts.is_empty() {
+            let mut clock = String::new();
+
+            if self.micros < 0 {
+                clock.push('-');
+            }
+
+            clock.push_str(&Clock(self.micros.abs()).to_string());
+            parts.push(clock);
+        }
+
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Microseconds as `HH:MM:SS[.ffffff]`.
+struct Clock(i64);
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_clock(f, self.0)
+    }
+}
+
+fn write_clock(f: &mut fmt::Formatter<'_>, micros: i64) -> fmt::Result {
+    write!(
+        f,
+        "{:02}:{:02}:{:02}",
+        micros / MICROS_PER_HOUR,
+        micros % MICROS_PER_HOUR / MICROS_PER_MINUTE,
+        micros % MICROS_PER_MINUTE / MICROS_PER_SECOND
+    )?;
+
+    match micros % MICROS_PER_SECOND {
+        0 => Ok(()),
+        fraction => write!(f, ".{}", format!("{:06}", fraction).trim_end_matches('0')),
+    }
+}
+
+/// `HH:MM[:SS[.ffffff]]` as microseconds. Hours are not limited to a day.
+fn parse_clock(s: &str) -> Option<i64> {
+    let mut parts = s.splitn(3, ':');
+
+    let hours = parse_digits(parts.next(), 1, 9)?;
+    let minutes = parse_digits(parts.next(), 2, 2)?;
+    let (seconds, fraction) = match parts.next() {
+        Some(seconds) => match seconds.split_once('.') {
+            Some((seconds, fraction)) => (seconds, Some(fraction)),
+            None => (seconds, None),
+        },
+        None => ("00", None),
+    };
+    let seconds = parse_digits(Some(seconds), 2, 2)?;
+    let fraction = match fraction {
+        Some(fraction) => parse_digits(Some(&format!("{:0<6}", fraction)), 6, 6)?,
+        None => 0,
+    };
+
+    if minutes > 59 || seconds > 59 {
+        return None;
+    }
+
+    Some(
+        hours * MICROS_PER_HOUR
+            + minutes * MICROS_PER_MINUTE
+            + seconds * MICROS_PER_SECOND
+            + fraction,
+    )
+}
+
+/// Between `min` and `max` ASCII digits.
+fn parse_digits(s: Option<&str>, min: usize, max: usize) -> Option<i64> {
+    let s = s?;
+
+    if s.len() < min || s.len() > max || !s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01, after Howard Hinnant's `days_from_civil`.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i32 {
+    let year = (if month <= 2 { year - 1 } else { year }) as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    (era * 146_097 + day_of_era - 719_468) as i32
+}
+
+fn civil_from_days(days: i32) -> (i32, u32, u32) {
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year as i32, month as u32, day as u32)
+}
+
+/// Values are serialized as the text of their literals, such as
+/// `"2024-01-31"` or `"1 day 02:00:00"`, which `parse` reads back.
+#[cfg(feature = "serde")]
+macro_rules! serde_as_text {
+    ($($typ:ident),*) => {
+        $(
+            impl serde::Serialize for $typ {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $typ {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let text = String::deserialize(deserializer)?;
+
+                    $typ::parse(&text).map_err(serde::de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "serde")]
+serde_as_text!(Date, Time, Timestamp, Interval);
+
+#[cfg(test)]
+mod tests {
+    use crate::temporal::{Date, Interval, TemporalError, Time, Timestamp};
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn date(s: &str) -> Date {
+        Date::parse(s).unwrap()
+    }
+
+    fn timestamp(s: &str) -> Timestamp {
+        Timestamp::parse(s).unwrap()
+    }
+
+    fn interval(s: &str) -> Interval {
+        Interval::parse(s).unwrap()
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(date("1970-01-01"), Date { days: 0 });
+        assert_eq!(date("2024-02-29").ymd(), (2024, 2, 29));
+        assert_eq!(date("0001-01-01").to_string(), "0001-01-01");
+        assert_eq!(date("2024-03-01").days_since(date("2023-03-01")), 366);
+        assert_eq!(date("2024-01-31").add_months(1), Ok(date("2024-02-29")));
+        assert_eq!(date("2024-01-31").add_months(-13), Ok(date("2022-12-31")));
+        assert_eq!(
+            date("9999-12-31").add_days(1),
+            Err(TemporalError::OutOfRange)
+        );
+    }
+
+    #[rstest]
+    #[case("2023-02-29")]
+    #[case("2024-13-01")]
+    #[case("2024-04-31")]
+    #[case("24-01-01")]
+    #[case("2024-01-01x")]
+    #[case("0000-01-01")]
+    fn invalid_dates(#[case] s: &str) {
+        assert_eq!(
+            Date::parse(s),
+            Err(TemporalError::InvalidDate(s.to_string()))
+        );
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(Time::parse("12:34").unwrap().to_string(), "12:34:00");
+        assert_eq!(Time::parse("23:59:59.5").unwrap().to_string(), "23:59:59.5");
+        assert_eq!(
+            Time::parse("24:00:00"),
+            Err(TemporalError::InvalidTime("24:00:00".to_string()))
+        );
+        assert_eq!(
+            Time::parse("01:00")
+                .unwrap()
+                .add_interval(interval("-2 hours")),
+            Time::parse("23:00").unwrap()
+        );
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(
+            timestamp("2024-01-31T12:00:00").to_string(),
+            "2024-01-31 12:00:00"
+        );
+        assert_eq!(timestamp("2024-01-31"), date("2024-01-31").to_timestamp());
+        assert_eq!(
+            timestamp("2024-03-31 08:00").add_interval(interval("-1 month 1 day 16:00")),
+            Ok(timestamp("2024-03-02 00:00"))
+        );
+        assert_eq!(
+            timestamp("2024-01-01 00:00").since(timestamp("2024-01-02 06:00")),
+            interval("-1 day -06:00")
+        );
+        assert_eq!(
+            Timestamp::parse("2024-01-01 25:00"),
+            Err(TemporalError::InvalidTimestamp(
+                "2024-01-01 25:00".to_string()
+            ))
+        );
+    }
+
+    #[rstest]
+    #[case("3 days", 0, 3, 0)]
+    #[case("1 year 2 mons", 14, 0, 0)]
+    #[case("2 weeks -1 day", 0, 13, 0)]
+    #[case("1.5 seconds", 0, 0, 1_500_000)]
+    #[case("1 hour 30 minutes", 0, 0, 5_400_000_000)]
+    #[case("1 day -01:30", 0, 1, -5_400_000_000)]
+    fn intervals(#[case] s: &str, #[case] months: i32, #[case] days: i32, #[case] micros: i64) {
+        let parsed = interval(s);
+
+        assert_eq!(
+            parsed,
+            Interval {
+                months,
+                days,
+                micros
+            }
+        );
+        assert_eq!(interval(&parsed.to_string()), parsed);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("3")]
+    #[case("3 fortnights")]
+    #[case("1.5 days")]
+    #[case("1:2")]
+    fn invalid_intervals(#[case] s: &str) {
+        assert_eq!(
+            Interval::parse(s),
+            Err(TemporalError::InvalidInterval(s.to_string()))
+        );
+    }
+
+    #[test]
+    fn interval_display() {
+        assert_eq!(
+            interval("14 months 1 day 1.25 seconds").to_string(),
+            "1 year 2 months 1 day 00:00:01.25"
+        );
+        assert_eq!(interval("0 days").to_string(), "00:00:00");
+        assert_eq!(interval("-2 hours").to_string(), "-02:00:00");
+    }
+}
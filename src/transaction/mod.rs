@@ -0,0 +1,236 @@
+#![allow(dead_code)]
+
+use crate::catalog::Catalog;
+use crate::storage::Storage;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TransactionError {
+    /// COMMIT, ROLLBACK or a savepoint command outside of a transaction.
+    NoTransaction,
+    /// BEGIN inside of a transaction.
+    TransactionInProgress,
+    UnknownSavepoint(String),
+}
+
+/// The isolation level asked for by BEGIN. It is only recorded: a session
+/// runs one statement at a time against its own copy of the database, so
+/// statements run the same at every level, which behaves as SERIALIZABLE.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    #[default]
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+/// The state of the database at some point, to go back to on rollback.
+/// Both halves share their data with the live copies until either side is
+/// written.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub catalog: Catalog,
+    pub storage: Storage,
+}
+
+/// Tracks the transaction of a session and the snapshots it can roll back
+/// to.
+#[derive(Debug, Default)]
+pub struct TransactionManager {
+    transaction: Option<Transaction>,
+}
+
+#[derive(Debug)]
+struct Transaction {
+    /// Reported by `isolation_level`, never used to run statements.
+    isolation_level: IsolationLevel,
+    start: Snapshot,
+    /// Savepoints, innermost last. Names may repeat; the innermost wins.
+    savepoints: Vec<(String, Snapshot)>,
+}
+
+impl TransactionManager {
+    pub fn new() -> Self {
+        TransactionManager { transaction: None }
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    pub fn isolation_level(&self) -> Option<IsolationLevel> {
+        self.transaction
+            .as_ref()
+            .map(|transaction| transaction.isolation_level)
+    }
+
+    pub fn begin(
+        &mut self,
+        isolation_level: IsolationLevel,
+        start: Snapshot,
+    ) -> Result<(), TransactionError> {
+        if self.transaction.is_some() {
+            return Err(TransactionError::TransactionInProgress);
+        }
+
+        self.transaction = Some(Transaction {
+            isolation_level,
+            start,
+            savepoints: vec![],
+        });
+
+        Ok(())
+    }
+
+    pub fn commit(&mut self) -> Result<(), TransactionError> {
+        self.transaction
+            .take()
+            .map(|_| ())
+            .ok_or(TransactionError::NoTransaction)
+    }
+
+    /// Ends the transaction, returning the state it started from.
+    pub fn rollback(&mut self) -> Result<Snapshot, TransactionError> {
+        self.transaction
+            .take()
+            .map(|transaction| transaction.start)
+            .ok_or(TransactionError::NoTransaction)
+    }
+
+    pub fn savepoint(&mut self, name: &str, snapshot: Snapshot) -> Result<(), TransactionError> {
+        self.transaction_mut()?
+            .savepoints
+            .push((name.to_string(), snapshot));
+
+        Ok(())
+    }
+
+    /// Returns the state at the savepoint, forgetting the savepoints made
+    /// after it. The savepoint itself stays.
+    pub fn rollback_to(&mut self, name: &str) -> Result<Snapshot, TransactionError> {
+        let savepoints = &mut self.transaction_mut()?.savepoints;
+        let index = find_savepoint(savepoints, name)?;
+
+        savepoints.truncate(index + 1);
+
+        Ok(savepoints[index].1.clone())
+    }
+
+    /// Forgets the savepoint and the savepoints made after it, keeping
+    /// their changes.
+    pub fn release(&mut self, name: &str) -> Result<(), TransactionError> {
+        let savepoints = &mut self.transaction_mut()?.savepoints;
+        let index = find_savepoint(savepoints, name)?;
+
+        savepoints.truncate(index);
+
+        Ok(())
+    }
+
+    fn transaction_mut(&mut self) -> Result<&mut Transaction, TransactionError> {
+        self.transaction
+            .as_mut()
+            .ok_or(TransactionError::NoTransaction)
+    }
+}
+
+fn find_savepoint(
+    savepoints: &[(String, Snapshot)],
+    name: &str,
+) -> Result<usize, TransactionError> {
+    savepoints
+        .iter()
+        .rposition(|(savepoint, _)| savepoint == name)
+        .ok_or_else(|| TransactionError::UnknownSavepoint(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::catalog::Catalog;
+    use crate::executor::value::Value;
+    use crate::storage::Storage;
+    use crate::transaction::{IsolationLevel, Snapshot, TransactionError, TransactionManager};
+    use pretty_assertions::assert_eq;
+
+    fn snapshot(values: &[i64]) -> Snapshot {
+        let mut storage = Storage::new();
+        storage.create_table("table1");
+
+        for value in values {
+            storage.insert("table1", vec![Value::Int(*value)]);
+        }
+
+        Snapshot {
+            catalog: Catalog::new(),
+            storage,
+        }
+    }
+
+    fn values(snapshot: &Snapshot) -> Vec<Value> {
+        snapshot
+            .storage
+            .scan("table1")
+            .map(|(_, row)| row[0].clone())
+            .collect()
+    }
+
+    #[test]
+    fn savepoints() {
+        let mut manager = TransactionManager::new();
+
+        manager
+            .begin(IsolationLevel::Serializable, snapshot(&[]))
+            .unwrap();
+        manager.savepoint("a", snapshot(&[1])).unwrap();
+        manager.savepoint("b", snapshot(&[1, 2])).unwrap();
+        manager.savepoint("a", snapshot(&[1, 2, 3])).unwrap();
+
+        assert_eq!(
+            values(&manager.rollback_to("a").unwrap()),
+            vec![Value::Int(1), Value::Int(2), Value::Int(3)]
+        );
+
+        manager.release("a").unwrap();
+        assert_eq!(
+            values(&manager.rollback_to("a").unwrap()),
+            vec![Value::Int(1)]
+        );
+        assert_eq!(
+            manager.rollback_to("b").unwrap_err(),
+            TransactionError::UnknownSavepoint("b".to_string())
+        );
+
+        assert_eq!(
+            manager.isolation_level(),
+            Some(IsolationLevel::Serializable)
+        );
+        assert_eq!(values(&manager.rollback().unwrap()), vec![]);
+        assert!(!manager.in_transaction());
+    }
+
+    #[test]
+    fn errors_outside_of_transaction() {
+        let mut manager = TransactionManager::new();
+
+        assert_eq!(manager.commit(), Err(TransactionError::NoTransaction));
+        assert_eq!(
+            manager.savepoint("a", snapshot(&[])),
+            Err(TransactionError::NoTransaction)
+        );
+
+        manager
+            .begin(IsolationLevel::default(), snapshot(&[]))
+            .unwrap();
+
+        assert_eq!(
+            manager.begin(IsolationLevel::default(), snapshot(&[])),
+            Err(TransactionError::TransactionInProgress)
+        );
+        assert_eq!(manager.commit(), Ok(()));
+    }
+}